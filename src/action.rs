@@ -10,6 +10,7 @@ pub enum Action {
     GoToTop,
     GoToBottom,
     ToggleCollapse,
+    CycleListMode,
     FocusSearch,
     ExitSearch,
     SwitchFocus,
@@ -21,11 +22,37 @@ pub enum Action {
     CreateAlias,
     EditAlias,
     DeleteAlias,
+    ToggleDisabled,
+
+    // Clipboard
+    CopyName,
+    CopyCommand,
+    CopyLine,
+
+    // Using an alias
+    PickAlias,
+    RunAlias,
+    CloseRun,
+
+    // Marks and bulk actions
+    ToggleMark,
+    MarkRange,
+    ClearMarks,
+    ExportAliases,
+    MoveAliases,
 
     // Edit mode
     EditInput(char),
     EditBackspace,
     EditClear,
+    EditDelete,
+    EditDeleteWord,
+    EditLeft,
+    EditRight,
+    EditHome,
+    EditEnd,
+    EditWordLeft,
+    EditWordRight,
     EditNextField,
     EditPrevField,
     SaveEdit,
@@ -34,6 +61,7 @@ pub enum Action {
     // Confirm mode
     ConfirmYes,
     ConfirmNo,
+    ConfirmNext,
 
     // Result popup
     DismissResult,
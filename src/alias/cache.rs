@@ -0,0 +1,152 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use crate::context::Context;
+
+const HEADER: &str = "zam-cache 5";
+const SEPARATOR: &str = "--";
+
+/// One cache file per shell, since each collects a different alias set.
+fn cache_file_path(ctx: &Context) -> Option<PathBuf> {
+    if ctx.cache_dir.as_os_str().is_empty() {
+        return None;
+    }
+    Some(ctx.cache_dir.join(format!("aliases.{}", ctx.shell.file_ext())))
+}
+
+/// One `(path, mtime, size)` entry per watched file. Two fingerprints are
+/// equal exactly when no watched file was added, removed, or modified.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Fingerprint(Vec<String>);
+
+impl Fingerprint {
+    pub fn of(files: &[PathBuf]) -> Self {
+        let mut fingerprint = Fingerprint(Vec::new());
+        fingerprint.add(files);
+        fingerprint
+    }
+
+    /// Watch more files; one already watched isn't listed twice.
+    pub fn add(&mut self, files: &[PathBuf]) {
+        self.0.extend(files.iter().filter_map(|p| file_entry(p)));
+        self.0.sort();
+        self.0.dedup();
+    }
+}
+
+fn file_entry(path: &Path) -> Option<String> {
+    let meta = fs::metadata(path).ok()?;
+    let mtime = meta.modified().ok()?.duration_since(UNIX_EPOCH).ok()?;
+    Some(format!(
+        "{}.{:09} {} {}",
+        mtime.as_secs(),
+        mtime.subsec_nanos(),
+        meta.len(),
+        path.display()
+    ))
+}
+
+/// Return the cached raw `alias` output if its fingerprint still matches
+/// `expected`, which is computed from the cached output itself since that
+/// records where the shell's configuration lives.
+pub fn read(ctx: &Context, expected: impl FnOnce(&str) -> Fingerprint) -> Option<String> {
+    let content = fs::read_to_string(cache_file_path(ctx)?).ok()?;
+    parse(&content, expected)
+}
+
+/// Store raw `alias` output alongside the fingerprint it was collected under.
+/// Failures are ignored: the cache is an optimization, never a requirement.
+pub fn write(ctx: &Context, fingerprint: &Fingerprint, raw_output: &str) {
+    let Some(path) = cache_file_path(ctx) else {
+        return;
+    };
+    if fs::create_dir_all(&ctx.cache_dir).is_err() {
+        return;
+    }
+    let _ = fs::write(path, render(fingerprint, raw_output));
+}
+
+fn render(fingerprint: &Fingerprint, raw_output: &str) -> String {
+    let mut out = String::with_capacity(raw_output.len() + 64 * fingerprint.0.len());
+    out.push_str(HEADER);
+    out.push('\n');
+    for entry in &fingerprint.0 {
+        out.push_str(entry);
+        out.push('\n');
+    }
+    out.push_str(SEPARATOR);
+    out.push('\n');
+    out.push_str(raw_output);
+    out
+}
+
+fn parse(content: &str, expected: impl FnOnce(&str) -> Fingerprint) -> Option<String> {
+    let rest = content.strip_prefix(HEADER)?.strip_prefix('\n')?;
+    let mut entries = Vec::new();
+    let mut offset = 0;
+    for line in rest.split_inclusive('\n') {
+        offset += line.len();
+        let line = line.trim_end_matches('\n');
+        if line == SEPARATOR {
+            let raw_output = &rest[offset..];
+            if entries != expected(raw_output).0 {
+                return None;
+            }
+            return Some(raw_output.to_string());
+        }
+        entries.push(line.to_string());
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fp(entries: &[&str]) -> Fingerprint {
+        Fingerprint(entries.iter().map(|s| s.to_string()).collect())
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let fingerprint = fp(&["1.000000000 10 /a", "2.000000000 20 /b"]);
+        let raw = "ga='git add'\ngst='git status'\n";
+        let content = render(&fingerprint, raw);
+        assert_eq!(parse(&content, |_| fingerprint.clone()).as_deref(), Some(raw));
+    }
+
+    #[test]
+    fn test_stale_fingerprint() {
+        let content = render(&fp(&["1.000000000 10 /a"]), "ll='ls -la'\n");
+        assert_eq!(parse(&content, |_| fp(&["2.000000000 10 /a"])), None);
+        assert_eq!(parse(&content, |_| fp(&[])), None);
+    }
+
+    #[test]
+    fn test_expected_sees_raw_output() {
+        let fingerprint = fp(&["1.000000000 10 /a"]);
+        let content = render(&fingerprint, "ll='ls -la'\n");
+        let mut seen = String::new();
+        parse(&content, |raw| {
+            seen = raw.to_string();
+            fingerprint.clone()
+        });
+        assert_eq!(seen, "ll='ls -la'\n");
+    }
+
+    #[test]
+    fn test_output_containing_separator() {
+        let fingerprint = fp(&[]);
+        let raw = "--\nx=y\n";
+        let content = render(&fingerprint, raw);
+        assert_eq!(parse(&content, |_| fingerprint.clone()).as_deref(), Some(raw));
+    }
+
+    #[test]
+    fn test_wrong_header() {
+        assert_eq!(parse("zam-cache 4\n--\n", |_| fp(&[])), None);
+    }
+}
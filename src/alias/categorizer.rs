@@ -2,32 +2,72 @@
 
 use std::collections::HashMap;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
-use super::types::{Alias, AliasGroup, AliasSource};
+use super::collector;
+use super::parser;
+use super::plugins::{self, Plugin};
+use super::types::{Alias, AliasGroup, AliasKind, AliasSource, AliasState};
+use crate::context::Context;
+use crate::shell::{Shell, ShellPaths};
 
-/// Given raw (name, command) pairs, categorize each alias by scanning source files.
-pub fn categorize_aliases(raw: Vec<(String, String)>) -> Vec<AliasGroup> {
-    let Some(home) = dirs::home_dir() else {
+/// Where bash reads system-wide interactive configuration.
+const SYSTEM_BASHRC: &str = "/etc/bash.bashrc";
+
+/// Given raw (name, command, kind) triples, categorize each alias by scanning source files.
+pub fn categorize_aliases(ctx: &Context, raw: Vec<(String, String, AliasKind)>) -> Vec<AliasGroup> {
+    if ctx.home().as_os_str().is_empty() {
         return group_all_unknown(raw);
-    };
+    }
+    let home = ctx.home();
 
     let mut attribution: HashMap<String, (AliasSource, Option<String>)> = HashMap::new();
 
-    categorize_zsh(&home, &mut attribution);
+    match ctx.shell {
+        Shell::Zsh => categorize_zsh(&ctx.paths, &mut attribution),
+        Shell::Bash => categorize_bash(home, &mut attribution),
+        Shell::Fish => categorize_fish(home, &mut attribution),
+    }
+    categorize_zam(ctx, &mut attribution);
+
+    // Named directories live in their own table; only zam's are attributed
+    let mut dir_attribution = HashMap::new();
+    scan_file_for_named_dirs(
+        &ctx.config_dir.join("dirs/custom.zsh"),
+        &mut dir_attribution,
+    );
 
     // Build aliases with attribution
     let mut source_map: HashMap<String, Vec<Alias>> = HashMap::new();
-    for (name, command) in raw {
-        let (source, description) = attribution
-            .remove(&name)
-            .unwrap_or((AliasSource::Unknown, None));
+    for alias in disabled_aliases(ctx, &attribution) {
+        let active = raw
+            .iter()
+            .any(|(name, _, kind)| *name == alias.name && kind.same_table(alias.kind));
+        if !active {
+            source_map.entry(alias.source.display_name()).or_default().push(alias);
+        }
+    }
+    for (name, command, kind) in raw {
+        let (source, description) = if kind == AliasKind::NamedDir {
+            match dir_attribution.remove(&name) {
+                Some(description) => (AliasSource::ZamDirs, description),
+                None => (AliasSource::NamedDirs, None),
+            }
+        } else {
+            attribution
+                .remove(&name)
+                .unwrap_or((AliasSource::Unknown, None))
+        };
         let display = source.display_name();
         let alias = Alias {
             name,
             command,
+            kind,
             source,
             description,
+            state: AliasState::Enabled,
+            tags: Vec::new(),
+            missing: false,
         };
         source_map.entry(display).or_default().push(alias);
     }
@@ -40,18 +80,152 @@ pub fn categorize_aliases(raw: Vec<(String, String)>) -> Vec<AliasGroup> {
             AliasGroup {
                 source,
                 aliases,
-                collapsed: false,
             }
         })
         .collect();
 
-    groups.sort_by(|a, b| a.source.sort_key().cmp(&b.source.sort_key()));
+    groups.sort_by_key(AliasGroup::sort_key);
     groups
 }
 
-fn categorize_zsh(home: &Path, attribution: &mut HashMap<String, (AliasSource, Option<String>)>) {
-    let omz_dir = home.join(".oh-my-zsh");
-    let zshrc_path = home.join(".zshrc");
+/// Group functions by the file that defined them, as reported by zsh's
+/// `$functions_source` or bash's `declare -F`.
+pub fn categorize_functions(
+    ctx: &Context,
+    raw: Vec<(String, String, Option<String>)>,
+) -> Vec<AliasGroup> {
+    let plugins = plugins::detect_all(&ctx.paths);
+    let mut file_cache: HashMap<String, Vec<String>> = HashMap::new();
+
+    let mut source_map: HashMap<String, Vec<Alias>> = HashMap::new();
+    for (name, body, file) in raw {
+        let (source, description) = match &file {
+            Some(file) => {
+                let lines = file_cache
+                    .entry(file.clone())
+                    .or_insert_with(|| read_lines(Path::new(file)));
+                let lines: Vec<&str> = lines.iter().map(String::as_str).collect();
+                let description = find_function_definition(&lines, &name)
+                    .and_then(|i| extract_description(&lines, i));
+                (source_for_path(ctx, &plugins, Path::new(file)), description)
+            }
+            None => (AliasSource::Unknown, None),
+        };
+        let alias = Alias {
+            name,
+            command: body,
+            kind: AliasKind::Function,
+            source,
+            description,
+            state: AliasState::Enabled,
+            tags: Vec::new(),
+            missing: false,
+        };
+        source_map
+            .entry(alias.source.display_name())
+            .or_default()
+            .push(alias);
+    }
+
+    let mut groups: Vec<AliasGroup> = source_map
+        .into_values()
+        .map(|mut aliases| {
+            aliases.sort_by(|a, b| a.name.cmp(&b.name));
+            let source = aliases[0].source.clone();
+            AliasGroup {
+                source,
+                aliases,
+            }
+        })
+        .collect();
+
+    groups.sort_by_key(AliasGroup::sort_key);
+    groups
+}
+
+/// Map a file path to the `AliasSource` the categorizer would give
+/// definitions found in it.
+fn source_for_path(ctx: &Context, plugins: &[Plugin], path: &Path) -> AliasSource {
+    let paths = &ctx.paths;
+    let home = ctx.home();
+    let omz_dir = &paths.omz;
+    let file_name = || {
+        path.file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string()
+    };
+
+    if path.parent() == Some(&omz_dir.join("lib")) {
+        return AliasSource::OhMyZshLib(file_name());
+    }
+    for plugins_dir in [paths.omz_custom.join("plugins"), omz_dir.join("plugins")] {
+        if let Ok(rest) = path.strip_prefix(plugins_dir) {
+            if let Some(plugin) = rest.components().next() {
+                let plugin = plugin.as_os_str().to_string_lossy().to_string();
+                return AliasSource::OhMyZshPlugin(plugin);
+            }
+        }
+    }
+    if path.parent() == Some(&paths.omz_custom) {
+        return AliasSource::OhMyZshCustom(file_name());
+    }
+    if let Some(plugin) = plugins.iter().find(|p| path.starts_with(&p.dir)) {
+        return AliasSource::Plugin {
+            manager: plugin.manager,
+            name: plugin.name.clone(),
+        };
+    }
+    if path == paths.rc_file(Shell::Zsh) {
+        return AliasSource::Zshrc;
+    }
+    if path == home.join(".bashrc") {
+        return AliasSource::Bashrc;
+    }
+    if path == home.join(".bash_aliases") {
+        return AliasSource::BashAliases;
+    }
+    if path == Path::new(SYSTEM_BASHRC) {
+        return AliasSource::SystemBashrc;
+    }
+    if path.parent() == Some(&ctx.config_dir.join("aliases")) {
+        let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+        if stem == "custom" {
+            return AliasSource::ZamCustom;
+        }
+        return AliasSource::ZamExtension(stem.to_string());
+    }
+    AliasSource::Unknown
+}
+
+fn read_lines(path: &Path) -> Vec<String> {
+    fs::read_to_string(path)
+        .map(|c| c.lines().map(String::from).collect())
+        .unwrap_or_default()
+}
+
+/// Find the line defining `name` as `name()` or `function name`. A line
+/// that is just the name is a call, not a definition.
+fn find_function_definition(lines: &[&str], name: &str) -> Option<usize> {
+    lines.iter().position(|line| {
+        let line = line.trim_start();
+        if let Some(rest) = line.strip_prefix("function ") {
+            // Then `()`, `{`, fish's options, or the body on the next line
+            return rest.trim_start().strip_prefix(name).is_some_and(|rest| {
+                rest.is_empty() || rest.starts_with(|c: char| c.is_whitespace() || c == '(' || c == '{')
+            });
+        }
+        line.strip_prefix(name)
+            .is_some_and(|rest| rest.trim_start().starts_with("()"))
+    })
+}
+
+fn categorize_zsh(
+    paths: &ShellPaths,
+    attribution: &mut HashMap<String, (AliasSource, Option<String>)>,
+) {
+    let omz_dir = &paths.omz;
+    let zshrc_path = paths.rc_file(Shell::Zsh);
 
     // 1. oh-my-zsh lib files
     if omz_dir.join("lib").is_dir() {
@@ -66,10 +240,7 @@ fn categorize_zsh(home: &Path, attribution: &mut HashMap<String, (AliasSource, O
     // 2. Enabled plugins from .zshrc
     let enabled_plugins = parse_zsh_plugins(&zshrc_path);
     for plugin in &enabled_plugins {
-        let plugin_file = omz_dir
-            .join("plugins")
-            .join(plugin)
-            .join(format!("{plugin}.plugin.zsh"));
+        let plugin_file = paths.omz_plugin_file(plugin);
         if plugin_file.is_file() {
             scan_file_for_aliases(
                 &plugin_file,
@@ -80,33 +251,96 @@ fn categorize_zsh(home: &Path, attribution: &mut HashMap<String, (AliasSource, O
     }
 
     // 3. oh-my-zsh custom directory
-    if omz_dir.join("custom").is_dir() {
+    if paths.omz_custom.is_dir() {
         scan_dir_for_aliases(
-            &omz_dir.join("custom"),
+            &paths.omz_custom,
             "zsh",
             attribution,
             AliasSource::OhMyZshCustom,
         );
     }
 
-    // 4. ~/.zshrc itself
+    // 4. Prezto, zinit, antidote and zplug plugins
+    for plugin in plugins::detect_all(paths) {
+        let source = AliasSource::Plugin {
+            manager: plugin.manager,
+            name: plugin.name.clone(),
+        };
+        for file in plugin.files() {
+            scan_file_for_aliases(&file, attribution, source.clone());
+        }
+    }
+
+    // 5. ~/.zshrc itself
     if zshrc_path.is_file() {
         scan_file_for_aliases(&zshrc_path, attribution, AliasSource::Zshrc);
     }
+}
+
+fn categorize_bash(home: &Path, attribution: &mut HashMap<String, (AliasSource, Option<String>)>) {
+    for (path, source) in [
+        (PathBuf::from(SYSTEM_BASHRC), AliasSource::SystemBashrc),
+        (home.join(".bashrc"), AliasSource::Bashrc),
+        (home.join(".bash_aliases"), AliasSource::BashAliases),
+    ] {
+        if path.is_file() {
+            scan_file_for_aliases(&path, attribution, source);
+        }
+    }
+}
+
+/// fish loads universal variables first, then conf.d/ snippets in name
+/// order, then config.fish.
+fn categorize_fish(home: &Path, attribution: &mut HashMap<String, (AliasSource, Option<String>)>) {
+    let fish_dir = home.join(".config/fish");
+
+    scan_fish_variables(&fish_dir.join("fish_variables"), attribution);
+
+    let mut snippets = list_dir_files(&fish_dir.join("conf.d"), "fish");
+    snippets.sort();
+    for path in snippets {
+        // conf.d/zam.fish holds zam's custom aliases
+        if path == home.join(Shell::Fish.rc_file()) {
+            continue;
+        }
+        let filename = path
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string();
+        scan_fish_file(&path, attribution, AliasSource::FishConfD(filename));
+    }
 
-    // 5. zam custom aliases
-    let custom_path = home.join(".config/zam/aliases/custom.zsh");
+    scan_fish_file(&fish_dir.join("config.fish"), attribution, AliasSource::FishConfig);
+}
+
+/// zam's own alias files for `shell`, which live side by side in
+/// ~/.config/zam/aliases/ and differ only by extension. fish keeps custom
+/// aliases in conf.d/zam.fish instead of aliases/custom.fish.
+fn categorize_zam(ctx: &Context, attribution: &mut HashMap<String, (AliasSource, Option<String>)>) {
+    let shell = ctx.shell;
+    let ext = shell.file_ext();
+    let scan = match shell {
+        Shell::Zsh | Shell::Bash => scan_file_for_aliases,
+        Shell::Fish => scan_fish_file,
+    };
+
+    // zam custom aliases
+    let custom_path = match shell {
+        Shell::Zsh | Shell::Bash => ctx.config_dir.join(format!("aliases/custom.{ext}")),
+        Shell::Fish => ctx.paths.rc_file(shell),
+    };
     if custom_path.is_file() {
-        scan_file_for_aliases(&custom_path, attribution, AliasSource::ZamCustom);
+        scan(&custom_path, attribution, AliasSource::ZamCustom);
     }
 
-    // 6. zam extension files (any .zsh in aliases/ that isn't custom.zsh)
-    let aliases_dir = home.join(".config/zam/aliases");
+    // zam extension files (any other file with the shell's extension)
+    let aliases_dir = ctx.config_dir.join("aliases");
     if aliases_dir.is_dir() {
         if let Ok(entries) = fs::read_dir(&aliases_dir) {
             for entry in entries.flatten() {
                 let path = entry.path();
-                if path.extension().and_then(|e| e.to_str()) != Some("zsh") {
+                if path.extension().and_then(|e| e.to_str()) != Some(ext) {
                     continue;
                 }
                 let filename = path
@@ -118,28 +352,185 @@ fn categorize_zsh(home: &Path, attribution: &mut HashMap<String, (AliasSource, O
                     continue;
                 }
                 let source = AliasSource::ZamExtension(filename);
-                scan_file_for_aliases(&path, attribution, source);
+                scan(&path, attribution, source);
             }
         }
     }
 }
 
-fn group_all_unknown(raw: Vec<(String, String)>) -> Vec<AliasGroup> {
-    let mut aliases: Vec<Alias> = raw
+/// Definitions disabled by zam: commented out in zam's own files, or kept
+/// in an override file for an alias defined elsewhere, which is attributed
+/// to the file that defines it.
+fn disabled_aliases(
+    ctx: &Context,
+    attribution: &HashMap<String, (AliasSource, Option<String>)>,
+) -> Vec<Alias> {
+    let shell = ctx.shell;
+    let ext = shell.file_ext();
+    let mut files = Vec::new();
+    for path in list_dir_files(&ctx.config_dir.join("aliases"), ext) {
+        let stem = path.file_stem().unwrap_or_default().to_string_lossy().to_string();
+        let source = match stem.as_str() {
+            "custom" if shell == Shell::Fish => continue,
+            "custom" => AliasSource::ZamCustom,
+            _ => AliasSource::ZamExtension(stem),
+        };
+        files.push((path, Some(source)));
+    }
+    if shell == Shell::Fish {
+        files.push((ctx.paths.rc_file(shell), Some(AliasSource::ZamCustom)));
+    } else {
+        files.push((ctx.config_dir.join("dirs/custom.zsh"), Some(AliasSource::ZamDirs)));
+    }
+    files.extend(
+        list_dir_files(&ctx.config_dir.join("overrides"), ext)
+            .into_iter()
+            .map(|path| (path, None)),
+    );
+
+    let mut aliases = Vec::new();
+    for (path, file_source) in files {
+        let content = fs::read_to_string(&path).unwrap_or_default();
+        let lines: Vec<&str> = content.lines().collect();
+        for (i, line) in lines.iter().enumerate() {
+            let Some(definition) = parser::disabled_definition(line) else {
+                continue;
+            };
+            let parsed = match shell {
+                Shell::Fish => parser::parse_fish_definition(definition),
+                Shell::Zsh | Shell::Bash => match definition.strip_prefix("hash -d ") {
+                    Some(rest) => parser::parse_named_dir_lines(rest)
+                        .pop()
+                        .map(|(name, path)| (name, path, AliasKind::NamedDir)),
+                    None => parser::parse_alias_lines(definition).pop(),
+                },
+            };
+            let Some((name, command, kind)) = parsed else {
+                continue;
+            };
+            let mut description = extract_description(&lines, i);
+            let source = match &file_source {
+                Some(source) => source.clone(),
+                None => match attribution.get(&name) {
+                    Some((source, original)) if kind != AliasKind::NamedDir => {
+                        description = description.or_else(|| original.clone());
+                        source.clone()
+                    }
+                    _ if kind == AliasKind::NamedDir => AliasSource::NamedDirs,
+                    _ => AliasSource::Unknown,
+                },
+            };
+            aliases.push(Alias {
+                name,
+                command,
+                kind,
+                source,
+                description,
+                state: AliasState::Disabled,
+                tags: Vec::new(),
+                missing: false,
+            });
+        }
+    }
+    aliases
+}
+
+/// Every file whose contents can change the alias set or its attribution:
+/// the files `categorize_zsh`/`categorize_bash` and `categorize_zam` scan,
+/// plus zam's override files.
+pub fn watched_files(ctx: &Context) -> Vec<PathBuf> {
+    if ctx.home().as_os_str().is_empty() {
+        return Vec::new();
+    }
+    let home = ctx.home();
+    let paths = &ctx.paths;
+
+    let mut files = Vec::new();
+    match ctx.shell {
+        Shell::Zsh => {
+            let zshrc_path = paths.rc_file(Shell::Zsh);
+            files.extend(list_dir_files(&paths.omz.join("lib"), "zsh"));
+            for plugin in parse_zsh_plugins(&zshrc_path) {
+                files.push(paths.omz_plugin_file(&plugin));
+            }
+            files.extend(list_dir_files(&paths.omz_custom, "zsh"));
+            files.extend(plugins::config_files(paths));
+            for plugin in plugins::detect_all(paths) {
+                files.extend(plugin.files());
+            }
+            files.push(zshrc_path);
+            files.extend(list_dir_files(&ctx.config_dir.join("dirs"), "zsh"));
+        }
+        Shell::Bash => {
+            files.push(PathBuf::from(SYSTEM_BASHRC));
+            files.push(home.join(".bashrc"));
+            files.push(home.join(".bash_aliases"));
+        }
+        Shell::Fish => {
+            let fish_dir = home.join(".config/fish");
+            files.push(fish_dir.join("fish_variables"));
+            files.push(fish_dir.join("config.fish"));
+            files.extend(list_dir_files(&fish_dir.join("conf.d"), "fish"));
+        }
+    }
+    let ext = ctx.shell.file_ext();
+    files.extend(list_dir_files(&ctx.config_dir.join("aliases"), ext));
+    files.extend(list_dir_files(&ctx.config_dir.join("overrides"), ext));
+    files
+}
+
+/// The files the shell reports its functions came from, in the collector
+/// output's functions section. Their descriptions are read from them, so
+/// they're watched too, though only known once the shell has run.
+pub fn function_files(raw_output: &str) -> Vec<PathBuf> {
+    parser::parse_function_records(collector::split_sections(raw_output).functions)
         .into_iter()
-        .map(|(name, command)| Alias {
-            name,
-            command,
-            source: AliasSource::Unknown,
-            description: None,
-        })
-        .collect();
-    aliases.sort_by(|a, b| a.name.cmp(&b.name));
-    vec![AliasGroup {
-        source: AliasSource::Unknown,
-        aliases,
-        collapsed: false,
-    }]
+        .filter_map(|(_, _, file)| file.map(PathBuf::from))
+        .collect()
+}
+
+fn list_dir_files(dir: &Path, extension: &str) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.extension().and_then(|e| e.to_str()) == Some(extension))
+        .collect()
+}
+
+fn group_all_unknown(raw: Vec<(String, String, AliasKind)>) -> Vec<AliasGroup> {
+    let (dirs, aliases): (Vec<_>, Vec<_>) = raw
+        .into_iter()
+        .partition(|(_, _, kind)| *kind == AliasKind::NamedDir);
+
+    let mut groups = Vec::new();
+    for (source, raw) in [(AliasSource::Unknown, aliases), (AliasSource::NamedDirs, dirs)] {
+        if raw.is_empty() {
+            continue;
+        }
+        let mut aliases: Vec<Alias> = raw
+            .into_iter()
+            .map(|(name, command, kind)| Alias {
+                name,
+                command,
+                kind,
+                source: source.clone(),
+                description: None,
+                state: AliasState::Enabled,
+                tags: Vec::new(),
+                missing: false,
+            })
+            .collect();
+        aliases.sort_by(|a, b| a.name.cmp(&b.name));
+        groups.push(AliasGroup {
+            source,
+            aliases,
+        });
+    }
+    groups.sort_by_key(AliasGroup::sort_key);
+    groups
 }
 
 /// Parse `plugins=(foo bar ...)` from a shell rc file.
@@ -221,25 +612,112 @@ fn scan_file_for_aliases(
 
     let lines: Vec<&str> = content.lines().collect();
     for (i, line) in lines.iter().enumerate() {
+        if parser::disabled_definition(line).is_some() {
+            continue;
+        }
         for alias_fragment in extract_alias_fragments(line) {
-            let rest = alias_fragment
-                .strip_prefix("-g ")
-                .unwrap_or(&alias_fragment);
-            if let Some(eq_pos) = rest.find('=') {
-                let alias_name = rest[..eq_pos].trim().to_string();
-                if !alias_name.is_empty() && !alias_name.contains(' ') {
-                    let description = extract_description(&lines, i);
-                    attribution
-                        .entry(alias_name)
-                        .or_insert((source.clone(), description));
-                }
+            if let Some(alias_name) = fragment_name(&alias_fragment) {
+                let description = extract_description(&lines, i);
+                attribution
+                    .entry(alias_name.to_string())
+                    .or_insert((source.clone(), description));
+            }
+        }
+    }
+}
+
+/// Scan a fish file for `abbr` and `alias` definitions and preceding comments.
+#[allow(clippy::needless_pass_by_value)] // source is cloned per-alias inside the loop
+fn scan_fish_file(
+    path: &Path,
+    attribution: &mut HashMap<String, (AliasSource, Option<String>)>,
+    source: AliasSource,
+) {
+    let Ok(content) = fs::read_to_string(path) else {
+        return;
+    };
+
+    let lines: Vec<&str> = content.lines().collect();
+    for (i, line) in lines.iter().enumerate() {
+        if let Some((name, _, _)) = parser::parse_fish_definition(line.trim()) {
+            let description = extract_description(&lines, i);
+            attribution
+                .entry(name)
+                .or_insert((source.clone(), description));
+        }
+    }
+}
+
+/// Attribute universal abbreviations, stored by fish < 3.6 as
+/// `SETUVAR _fish_abbr_<name>:<value>` with the name in fish's "var"
+/// escape style.
+fn scan_fish_variables(
+    path: &Path,
+    attribution: &mut HashMap<String, (AliasSource, Option<String>)>,
+) {
+    let Ok(content) = fs::read_to_string(path) else {
+        return;
+    };
+
+    for line in content.lines() {
+        let Some(rest) = line.strip_prefix("SETUVAR ") else {
+            continue;
+        };
+        let rest = rest.strip_prefix("--export ").unwrap_or(rest);
+        let Some((var, _)) = rest.split_once(':') else {
+            continue;
+        };
+        if let Some(name) = var.strip_prefix("_fish_abbr_").and_then(unescape_fish_var) {
+            attribution
+                .entry(name)
+                .or_insert((AliasSource::FishUniversal, None));
+        }
+    }
+}
+
+/// Undo `string escape --style=var`: `__` is `_`, `_XX_` is the byte 0xXX.
+fn unescape_fish_var(var: &str) -> Option<String> {
+    let mut bytes = Vec::with_capacity(var.len());
+    let mut rest = var.as_bytes();
+    while let Some((&b, tail)) = rest.split_first() {
+        if b != b'_' {
+            bytes.push(b);
+            rest = tail;
+        } else if let Some(tail) = tail.strip_prefix(b"_") {
+            bytes.push(b'_');
+            rest = tail;
+        } else {
+            let hex = std::str::from_utf8(tail.get(..2)?).ok()?;
+            bytes.push(u8::from_str_radix(hex, 16).ok()?);
+            rest = tail.get(2..)?.strip_prefix(b"_")?;
+        }
+    }
+    String::from_utf8(bytes).ok()
+}
+
+/// Scan a file for `hash -d name=...` lines and their preceding comments.
+fn scan_file_for_named_dirs(path: &Path, attribution: &mut HashMap<String, Option<String>>) {
+    let Ok(content) = fs::read_to_string(path) else {
+        return;
+    };
+
+    let lines: Vec<&str> = content.lines().collect();
+    for (i, line) in lines.iter().enumerate() {
+        let Some(rest) = line.trim_start().strip_prefix("hash -d ") else {
+            continue;
+        };
+        if let Some(eq_pos) = rest.find('=') {
+            let name = rest[..eq_pos].trim().to_string();
+            if !name.is_empty() && !name.contains(' ') {
+                let description = extract_description(&lines, i);
+                attribution.entry(name).or_insert(description);
             }
         }
     }
 }
 
 /// Extract the part after each `alias ` keyword in a line.
-/// Handles lines like `&& alias gfa='...'` or `alias -g foo=bar`.
+/// Handles lines like `&& alias gfa='...'`, `alias -g foo=bar` or `alias -s pdf=zathura`.
 fn extract_alias_fragments(line: &str) -> Vec<String> {
     let mut results = Vec::new();
     let mut search_from = 0;
@@ -262,6 +740,18 @@ fn extract_alias_fragments(line: &str) -> Vec<String> {
     results
 }
 
+/// The name an `alias` fragment defines, past any `-g`/`-s` flag and the
+/// `--` that guards names starting with `-`.
+fn fragment_name(fragment: &str) -> Option<&str> {
+    let rest = fragment
+        .strip_prefix("-g ")
+        .or_else(|| fragment.strip_prefix("-s "))
+        .unwrap_or(fragment);
+    let rest = rest.strip_prefix("-- ").unwrap_or(rest);
+    let name = rest[..rest.find('=')?].trim();
+    (!name.is_empty() && !name.contains(' ')).then_some(name)
+}
+
 /// Extract a description from comments immediately preceding the alias line.
 fn extract_description(lines: &[&str], alias_line: usize) -> Option<String> {
     let mut comment_lines = Vec::new();
@@ -269,7 +759,7 @@ fn extract_description(lines: &[&str], alias_line: usize) -> Option<String> {
     while j > 0 {
         j -= 1;
         let trimmed = lines[j].trim();
-        if trimmed.starts_with('#') {
+        if trimmed.starts_with('#') && parser::disabled_definition(trimmed).is_none() {
             let text = trimmed.trim_start_matches('#').trim();
             if !text.is_empty() {
                 comment_lines.push(text.to_string());
@@ -285,3 +775,27 @@ fn extract_description(lines: &[&str], alias_line: usize) -> Option<String> {
         Some(comment_lines.join(" "))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_function_definition() {
+        let lines = ["mkcd", "mkcd foo", "mkcd2() {", "mkcd () {", "}"];
+        assert_eq!(find_function_definition(&lines, "mkcd"), Some(3));
+        assert_eq!(find_function_definition(&["mkcd", "function mkcd"], "mkcd"), Some(1));
+        assert_eq!(find_function_definition(&["function mkcd --description 'x'"], "mkcd"), Some(0));
+        assert_eq!(find_function_definition(&["function mkcdir {"], "mkcd"), None);
+    }
+
+    #[test]
+    fn test_fragment_name() {
+        assert_eq!(fragment_name("ll='ls -l'"), Some("ll"));
+        assert_eq!(fragment_name("-g G='| grep'"), Some("G"));
+        assert_eq!(fragment_name("-- -x='echo x'"), Some("-x"));
+        assert_eq!(fragment_name("-s -- -y=vim"), Some("-y"));
+        assert_eq!(fragment_name("ll"), None);
+        assert_eq!(fragment_name("a b=c"), None);
+    }
+}
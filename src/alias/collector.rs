@@ -1,22 +1,267 @@
 // SPDX-License-Identifier: Apache-2.0
 
-use std::process::Command;
+use std::io::Read;
+use std::process::{Child, Stdio};
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
 
 use super::types::CollectError;
+use crate::context::Context;
+use crate::shell::Shell;
 
-/// Run zsh's alias command and return the raw output.
-pub fn collect_raw_aliases() -> Result<String, CollectError> {
-    let output = Command::new("zsh")
-        .args(["-ic", "alias"])
-        .output()
-        .map_err(|e| CollectError::SpawnFailed { source: e })?;
+/// Variables kept when running the shell with a sanitized environment.
+const KEPT_ENV: &[&str] = &[
+    "HOME", "USER", "LOGNAME", "PATH", "SHELL", "TERM", "LANG", "LC_ALL", "LC_CTYPE", "TMPDIR",
+    "ZDOTDIR", "XDG_CONFIG_HOME",
+];
 
-    if !output.status.success() && output.stdout.is_empty() {
+/// How to run the collector shell.
+#[derive(Debug, Clone, Copy)]
+pub struct CollectOptions {
+    /// Kill the shell if it hasn't exited after this long.
+    pub timeout: Duration,
+    /// Start the shell with only `KEPT_ENV` instead of the full parent environment.
+    pub clean_env: bool,
+}
+
+impl Default for CollectOptions {
+    fn default() -> Self {
+        CollectOptions {
+            timeout: Duration::from_secs(10),
+            clean_env: false,
+        }
+    }
+}
+
+/// Prefix of the marker line printed between sections of the script.
+const SECTION_MARKER: &str = "#zam-section ";
+
+/// Everything zam needs from one interactive zsh, since starting it is the
+/// slow part. `alias -L` prints each alias as a full `alias` command,
+/// including the `-g`/`-s` flag for global and suffix aliases. Functions
+/// are printed as a `#zam-function name<TAB>file` header followed by the
+/// body; completion functions (`_*`) are skipped. The `env` section
+/// reports where the rc files put zsh and oh-my-zsh configuration.
+const ZSH_SCRIPT: &str = r##"alias -L
+print -r -- '#zam-section named-dirs'
+hash -d
+print -r -- '#zam-section functions'
+for f in ${(ko)functions:#_*}; do
+  print -r -- "#zam-function $f"$'\t'"${functions_source[$f]}"
+  print -r -- "${functions[$f]}"
+done
+print -r -- '#zam-section env'
+print -r -- "ZDOTDIR=$ZDOTDIR"
+print -r -- "ZSH=$ZSH"
+print -r -- "ZSH_CUSTOM=$ZSH_CUSTOM"
+print -r -- "HISTFILE=$HISTFILE""##;
+
+/// The bash equivalent of `ZSH_SCRIPT`. `alias -p` prints the same
+/// `alias name='value'` lines; with `extdebug` set, `declare -F` reports
+/// the file each function was defined in. `declare -f` always wraps the
+/// body in `name () {` ... `}`, which is stripped to match zsh's
+/// `$functions`. bash has no named directories; its `env` section only
+/// reports the history file.
+const BASH_SCRIPT: &str = r##"alias -p
+echo '#zam-section functions'
+shopt -s extdebug
+for f in $(compgen -A function); do
+  [[ $f == _* ]] && continue
+  read -r _ _ file < <(declare -F "$f")
+  printf '#zam-function %s\t%s\n' "$f" "$file"
+  declare -f "$f" | sed '1,2d;$d'
+done
+echo '#zam-section env'
+echo "HISTFILE=$HISTFILE""##;
+
+/// fish's closest equivalents of aliases: abbreviations, printed as
+/// `abbr -a ...` commands, and functions created with `alias`, which
+/// `alias` lists as `alias name 'body'`. Both are parsed by
+/// `parser::parse_fish_lines`.
+const FISH_SCRIPT: &str = "abbr --show\nalias";
+
+fn script(shell: Shell) -> &'static str {
+    match shell {
+        Shell::Zsh => ZSH_SCRIPT,
+        Shell::Bash => BASH_SCRIPT,
+        Shell::Fish => FISH_SCRIPT,
+    }
+}
+
+/// The collector output split into its sections.
+pub struct Sections<'a> {
+    pub aliases: &'a str,
+    pub named_dirs: &'a str,
+    pub functions: &'a str,
+    pub env: &'a str,
+}
+
+/// Split raw collector output at the section markers. Unknown sections
+/// are ignored; missing ones are empty.
+pub fn split_sections(raw: &str) -> Sections<'_> {
+    let mut sections = Sections {
+        aliases: "",
+        named_dirs: "",
+        functions: "",
+        env: "",
+    };
+    let mut current = "aliases";
+    let mut start = 0;
+    let mut offset = 0;
+    for line in raw.split_inclusive('\n') {
+        if let Some(name) = line.trim_end().strip_prefix(SECTION_MARKER) {
+            sections.set(current, &raw[start..offset]);
+            current = name;
+            start = offset + line.len();
+        }
+        offset += line.len();
+    }
+    sections.set(current, &raw[start..]);
+    sections
+}
+
+impl<'a> Sections<'a> {
+    fn set(&mut self, name: &str, body: &'a str) {
+        match name {
+            "aliases" => self.aliases = body,
+            "named-dirs" => self.named_dirs = body,
+            "functions" => self.functions = body,
+            "env" => self.env = body,
+            _ => {}
+        }
+    }
+}
+
+/// Run the collector script in an interactive `ctx.shell` and return the
+/// raw output.
+///
+/// stdin is redirected from /dev/null so an rc file that prompts for input
+/// reads EOF instead of blocking; anything that still hangs is killed once
+/// `opts.timeout` elapses.
+pub fn collect_raw_aliases(ctx: &Context, opts: &CollectOptions) -> Result<String, CollectError> {
+    let shell = ctx.shell;
+    let mut cmd = ctx.shell_command();
+    cmd.args(["-ic", script(shell)])
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    if opts.clean_env {
+        cmd.env_clear();
+        for key in KEPT_ENV {
+            if let Some(value) = std::env::var_os(key) {
+                cmd.env(key, value);
+            }
+        }
+        if !ctx.home().as_os_str().is_empty() {
+            cmd.env("HOME", ctx.home());
+        }
+    }
+
+    let mut child = cmd
+        .spawn()
+        .map_err(|e| CollectError::SpawnFailed { shell, source: e })?;
+    let stdout = Drain::spawn(child.stdout.take());
+    let stderr = Drain::spawn(child.stderr.take());
+
+    let status = match wait_with_timeout(&mut child, opts.timeout) {
+        Ok(Some(status)) => status,
+        Ok(None) => {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(CollectError::Timeout {
+                shell,
+                seconds: opts.timeout.as_secs(),
+                stderr: lossy(&stderr.finish()),
+            });
+        }
+        Err(e) => return Err(CollectError::SpawnFailed { shell, source: e }),
+    };
+
+    let stdout = stdout.finish();
+    if !status.success() && stdout.is_empty() {
         return Err(CollectError::ShellFailed {
-            status: output.status,
-            stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+            shell,
+            status,
+            stderr: lossy(&stderr.finish()),
         });
     }
 
-    String::from_utf8(output.stdout).map_err(|e| CollectError::InvalidUtf8 { source: e })
+    String::from_utf8(stdout).map_err(|e| CollectError::InvalidUtf8 { shell, source: e })
+}
+
+/// Wait for `child` to exit, or `None` once `timeout` has passed.
+pub fn wait_with_timeout(
+    child: &mut Child,
+    timeout: Duration,
+) -> std::io::Result<Option<std::process::ExitStatus>> {
+    let deadline = Instant::now() + timeout;
+    loop {
+        if let Some(status) = child.try_wait()? {
+            return Ok(Some(status));
+        }
+        if Instant::now() >= deadline {
+            return Ok(None);
+        }
+        thread::sleep(Duration::from_millis(20));
+    }
+}
+
+/// Output captured from one of the shell's pipes by a reader thread.
+pub struct Drain {
+    buf: Arc<Mutex<Vec<u8>>>,
+    done: mpsc::Receiver<()>,
+}
+
+impl Drain {
+    /// Read a pipe to EOF on its own thread so a full pipe buffer can't
+    /// stall the shell, keeping partial output available if it has to be
+    /// killed.
+    pub fn spawn<R: Read + Send + 'static>(pipe: Option<R>) -> Self {
+        let buf = Arc::new(Mutex::new(Vec::new()));
+        let (tx, done) = mpsc::channel();
+        let shared = Arc::clone(&buf);
+        thread::spawn(move || {
+            if let Some(mut pipe) = pipe {
+                let mut chunk = [0u8; 4096];
+                while let Ok(n @ 1..) = pipe.read(&mut chunk) {
+                    shared.lock().unwrap().extend_from_slice(&chunk[..n]);
+                }
+            }
+            let _ = tx.send(());
+        });
+        Drain { buf, done }
+    }
+
+    /// Background jobs started by the rc file can keep a pipe open after
+    /// the shell exits, so wait only briefly for EOF before taking what's there.
+    pub fn finish(self) -> Vec<u8> {
+        let _ = self.done.recv_timeout(Duration::from_millis(500));
+        std::mem::take(&mut *self.buf.lock().unwrap())
+    }
+}
+
+fn lossy(bytes: &[u8]) -> String {
+    String::from_utf8_lossy(bytes).trim_end().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_sections() {
+        let raw = "alias ll='ls -la'\n#zam-section named-dirs\nproj=/src/proj\n";
+        let sections = split_sections(raw);
+        assert_eq!(sections.aliases, "alias ll='ls -la'\n");
+        assert_eq!(sections.named_dirs, "proj=/src/proj\n");
+        assert_eq!(sections.functions, "");
+    }
+
+    #[test]
+    fn test_split_sections_without_markers() {
+        let sections = split_sections("ll='ls -la'\n");
+        assert_eq!(sections.aliases, "ll='ls -la'\n");
+        assert_eq!(sections.named_dirs, "");
+    }
 }
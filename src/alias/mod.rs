@@ -1,15 +1,98 @@
 // SPDX-License-Identifier: Apache-2.0
 
+pub mod cache;
 pub mod categorizer;
 pub mod collector;
 pub mod parser;
+pub mod plugins;
 pub mod types;
 
-use types::{AliasGroup, CollectError};
+use crate::context::Context;
+use crate::managed;
+use crate::shell::{Shell, ShellPaths};
+use collector::CollectOptions;
+use types::{AliasGroup, AliasKind, CollectError};
 
-/// Collect all aliases from zsh.
-pub fn load_aliases() -> Result<Vec<AliasGroup>, CollectError> {
-    let raw_output = collector::collect_raw_aliases()?;
-    let parsed = parser::parse_alias_lines(&raw_output);
-    Ok(categorizer::categorize_aliases(parsed))
+/// Everything one run of the collector produces: the categorized aliases
+/// and the configuration paths the shell reported.
+#[derive(Debug, Clone)]
+pub struct Collected {
+    pub groups: Vec<AliasGroup>,
+    pub paths: ShellPaths,
+}
+
+/// Collect all aliases from the shell and refresh the on-disk cache.
+pub fn load_aliases(ctx: &Context, opts: &CollectOptions) -> Result<Collected, CollectError> {
+    // Fingerprint before collecting so edits made while the shell runs
+    // invalidate the cache on the next launch. `ctx.paths` is only a guess
+    // until the shell reports its own.
+    let mut fingerprint = cache::Fingerprint::of(&categorizer::watched_files(ctx));
+    let raw_output = collector::collect_raw_aliases(ctx, opts)?;
+    let resolved = resolve(ctx, &raw_output);
+    if resolved.paths != ctx.paths {
+        fingerprint = cache::Fingerprint::of(&categorizer::watched_files(&resolved));
+    }
+    fingerprint.add(&categorizer::function_files(&raw_output));
+    cache::write(ctx, &fingerprint, &raw_output);
+    Ok(categorize(&resolved, &raw_output))
+}
+
+/// Load aliases from the cache if no watched file changed since it was written.
+pub fn load_cached_aliases(ctx: &Context) -> Option<Collected> {
+    let raw_output = cache::read(ctx, |raw| {
+        let mut fingerprint = cache::Fingerprint::of(&categorizer::watched_files(&resolve(ctx, raw)));
+        fingerprint.add(&categorizer::function_files(raw));
+        fingerprint
+    })?;
+    Some(categorize(&resolve(ctx, &raw_output), &raw_output))
+}
+
+/// The shell's configuration paths, from the cache when it's fresh and
+/// otherwise by running the collector. Falls back to `ctx.paths` if the
+/// shell can't be run.
+pub fn shell_paths(ctx: &Context, opts: &CollectOptions) -> ShellPaths {
+    load_cached_aliases(ctx)
+        .map(|c| c.paths)
+        .or_else(|| load_aliases(ctx, opts).ok().map(|c| c.paths))
+        .unwrap_or_else(|| ctx.paths.clone())
+}
+
+/// `ctx` with the paths the collector's `env` section reports.
+fn resolve(ctx: &Context, raw_output: &str) -> Context {
+    let env = collector::split_sections(raw_output).env;
+    Context {
+        paths: ctx.paths.clone().with_env_section(env),
+        ..ctx.clone()
+    }
+}
+
+fn categorize(ctx: &Context, raw_output: &str) -> Collected {
+    let sections = collector::split_sections(raw_output);
+    let mut parsed = match ctx.shell {
+        Shell::Fish => parser::parse_fish_lines(sections.aliases),
+        Shell::Zsh | Shell::Bash => parser::parse_alias_lines(sections.aliases),
+    };
+    parsed.extend(
+        parser::parse_named_dir_lines(sections.named_dirs)
+            .into_iter()
+            .map(|(name, path)| (name, path, AliasKind::NamedDir)),
+    );
+    let mut groups = categorizer::categorize_aliases(ctx, parsed);
+    groups.extend(categorizer::categorize_functions(
+        ctx,
+        parser::parse_function_records(sections.functions),
+    ));
+    groups.sort_by_key(AliasGroup::sort_key);
+
+    let tags = managed::read_tags(ctx);
+    for alias in groups.iter_mut().flat_map(|g| &mut g.aliases) {
+        if let Some(tags) = tags.get(&alias.name) {
+            alias.tags.clone_from(tags);
+        }
+        alias.check_target();
+    }
+    Collected {
+        groups,
+        paths: ctx.paths.clone(),
+    }
 }
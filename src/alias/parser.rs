@@ -1,9 +1,21 @@
 // SPDX-License-Identifier: Apache-2.0
 
-/// Parse zsh alias output into (name, command) pairs.
+use super::types::AliasKind;
+
+/// Prefix zam comments a disabled definition out with, so it stays in
+/// its file without being sourced.
+pub const DISABLED_MARKER: &str = "#zam-disabled ";
+
+/// The definition a line disabled by zam holds.
+pub fn disabled_definition(line: &str) -> Option<&str> {
+    line.trim_start().strip_prefix(DISABLED_MARKER)
+}
+
+/// Parse zsh `alias -L` or bash `alias -p` output into (name, command, kind) triples.
 ///
-/// Zsh outputs:  name='value'
-pub fn parse_alias_lines(output: &str) -> Vec<(String, String)> {
+/// Accepts both `alias` output (`name='value'`) and `alias -L` output
+/// (`alias -g name='value'`, `alias -s ext=value`, `alias -- -='cd -'`).
+pub fn parse_alias_lines(output: &str) -> Vec<(String, String, AliasKind)> {
     let mut results = Vec::new();
     for line in output.lines() {
         let line = line.trim();
@@ -17,15 +29,204 @@ pub fn parse_alias_lines(output: &str) -> Vec<(String, String)> {
     results
 }
 
-fn parse_one_alias(line: &str) -> Option<(String, String)> {
-    let eq_pos = line.find('=')?;
-    let name = line[..eq_pos].trim().to_string();
+/// Parse `hash -d` output into (name, path) pairs.
+///
+/// Zsh outputs:  name=/path/to/dir
+pub fn parse_named_dir_lines(output: &str) -> Vec<(String, String)> {
+    let mut results = Vec::new();
+    for line in output.lines() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let Some(eq_pos) = name_end(line) else {
+            continue;
+        };
+        let name = unquote(&line[..eq_pos]);
+        if !name.is_empty() {
+            results.push((name, unquote(&line[eq_pos + 1..])));
+        }
+    }
+    results
+}
+
+/// Parse the collector's function records into (name, body, file) triples.
+///
+/// Each record is a `#zam-function name<TAB>file` header followed by the
+/// function body as printed by `${functions[name]}`. `file` is empty when
+/// the shell doesn't know where the function was defined.
+pub fn parse_function_records(output: &str) -> Vec<(String, String, Option<String>)> {
+    let mut results: Vec<(String, String, Option<String>)> = Vec::new();
+    for line in output.lines() {
+        if let Some(header) = line.strip_prefix("#zam-function ") {
+            let (name, file) = header.split_once('\t').unwrap_or((header, ""));
+            let file = (!file.is_empty()).then(|| file.to_string());
+            results.push((name.to_string(), String::new(), file));
+        } else if let Some((_, body, _)) = results.last_mut() {
+            if !body.is_empty() {
+                body.push('\n');
+            }
+            body.push_str(line);
+        }
+    }
+    for (_, body, _) in &mut results {
+        body.truncate(body.trim_end().len());
+    }
+    results
+}
+
+/// Parse fish `abbr --show` and `alias` output into (name, command, kind)
+/// triples. Lines that don't define anything are skipped.
+pub fn parse_fish_lines(output: &str) -> Vec<(String, String, AliasKind)> {
+    output.lines().filter_map(parse_fish_definition).collect()
+}
+
+/// Parse one fish `abbr` or `alias` command, as printed by `abbr --show`
+/// and `alias` or written in a config file. Abbreviations expanded anywhere
+/// on the line (`--position anywhere`) are global.
+pub fn parse_fish_definition(line: &str) -> Option<(String, String, AliasKind)> {
+    let words = fish_words(line);
+    let (command, args) = words.split_first()?;
+    let mut kind = AliasKind::Regular;
+    let mut function = None;
+    let mut positional: Vec<&str> = Vec::new();
+    let mut i = 0;
+    while i < args.len() {
+        let arg = args[i].as_str();
+        match arg {
+            "--" => {
+                positional.extend(args[i + 1..].iter().map(String::as_str));
+                break;
+            }
+            "-p" | "--position" => {
+                i += 1;
+                if args.get(i).is_some_and(|p| p == "anywhere") {
+                    kind = AliasKind::Global;
+                }
+            }
+            "--position=anywhere" => kind = AliasKind::Global,
+            "-f" | "--function" => {
+                i += 1;
+                function = args.get(i).cloned();
+            }
+            "-r" | "--regex" => i += 1,
+            // Anything that lists, queries or removes instead of defining
+            "-e" | "--erase" | "-s" | "--show" | "-l" | "--list" | "-q" | "--query"
+            | "--rename" | "-h" | "--help" => return None,
+            _ if arg.starts_with('-') && positional.is_empty() => {}
+            _ => positional.push(arg),
+        }
+        i += 1;
+    }
+
+    let (name, value) = match command.as_str() {
+        "abbr" => {
+            let (name, rest) = positional.split_first()?;
+            let value = if rest.is_empty() {
+                function?
+            } else {
+                rest.join(" ")
+            };
+            (name.to_string(), value)
+        }
+        "alias" => {
+            kind = AliasKind::Regular;
+            match positional.as_slice() {
+                [single] => {
+                    let (name, value) = single.split_once('=')?;
+                    (name.to_string(), value.to_string())
+                }
+                [name, rest @ ..] => (name.to_string(), rest.join(" ")),
+                [] => return None,
+            }
+        }
+        _ => return None,
+    };
+    (!name.is_empty()).then_some((name, value, kind))
+}
+
+/// Split a fish command line into words, applying fish quoting: `'...'`
+/// (only `\'` and `\\` are escapes), `"..."` and backslash escapes outside
+/// quotes. Stops at an unquoted `;` or a word starting with `#`.
+fn fish_words(line: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut chars = line.chars().peekable();
+    loop {
+        while chars.next_if(|c| c.is_whitespace()).is_some() {}
+        if matches!(chars.peek(), None | Some('#' | ';')) {
+            break;
+        }
+        let mut word = String::new();
+        while let Some(c) = chars.next_if(|c| !c.is_whitespace() && *c != ';') {
+            match c {
+                '\'' | '"' => {
+                    while let Some(q) = chars.next() {
+                        if q == c {
+                            break;
+                        }
+                        let escapable = if c == '\'' { "'\\" } else { "\"\\$" };
+                        match chars.peek() {
+                            Some(&next) if q == '\\' && escapable.contains(next) => {
+                                word.push(next);
+                                chars.next();
+                            }
+                            _ => word.push(q),
+                        }
+                    }
+                }
+                '\\' => match chars.next() {
+                    Some('n') => word.push('\n'),
+                    Some('t') => word.push('\t'),
+                    Some(other) => word.push(other),
+                    None => {}
+                },
+                _ => word.push(c),
+            }
+        }
+        words.push(word);
+    }
+    words
+}
+
+fn parse_one_alias(line: &str) -> Option<(String, String, AliasKind)> {
+    let mut line = line;
+    let mut kind = AliasKind::Regular;
+    if let Some(rest) = line.strip_prefix("alias ") {
+        line = rest.trim_start();
+        loop {
+            if let Some(rest) = line.strip_prefix("-g ") {
+                kind = AliasKind::Global;
+                line = rest.trim_start();
+            } else if let Some(rest) = line.strip_prefix("-s ") {
+                kind = AliasKind::Suffix;
+                line = rest.trim_start();
+            } else {
+                if let Some(rest) = line.strip_prefix("-- ") {
+                    line = rest.trim_start();
+                }
+                break;
+            }
+        }
+    }
+
+    let eq_pos = name_end(line)?;
+    let name = unquote(&line[..eq_pos]);
     if name.is_empty() {
         return None;
     }
     let rest = &line[eq_pos + 1..];
     let command = unquote(rest);
-    Some((name, command))
+    Some((name, command, kind))
+}
+
+/// Find the `=` separating name from value. zsh quotes names containing
+/// special characters, so skip over a leading single-quoted name.
+fn name_end(line: &str) -> Option<usize> {
+    if let Some(inner) = line.strip_prefix('\'') {
+        let close = inner.find('\'')? + 1;
+        return line[close..].find('=').map(|p| close + p);
+    }
+    line.find('=')
 }
 
 /// Remove surrounding quotes and handle escape sequences.
@@ -117,35 +318,47 @@ mod tests {
     fn test_simple_alias() {
         let input = "ll=ls -la";
         let result = parse_alias_lines(input);
-        assert_eq!(result, vec![("ll".into(), "ls -la".into())]);
+        assert_eq!(result, vec![("ll".into(), "ls -la".into(), AliasKind::Regular)]);
     }
 
     #[test]
     fn test_single_quoted() {
         let input = "gst='git status'";
         let result = parse_alias_lines(input);
-        assert_eq!(result, vec![("gst".into(), "git status".into())]);
+        assert_eq!(
+            result,
+            vec![("gst".into(), "git status".into(), AliasKind::Regular)]
+        );
     }
 
     #[test]
     fn test_ansi_c_quoting() {
         let input = "test=$'hello\\nworld'";
         let result = parse_alias_lines(input);
-        assert_eq!(result, vec![("test".into(), "hello\nworld".into())]);
+        assert_eq!(
+            result,
+            vec![("test".into(), "hello\nworld".into(), AliasKind::Regular)]
+        );
     }
 
     #[test]
     fn test_double_quoted() {
         let input = r#"foo="bar \"baz\"""#;
         let result = parse_alias_lines(input);
-        assert_eq!(result, vec![("foo".into(), "bar \"baz\"".into())]);
+        assert_eq!(
+            result,
+            vec![("foo".into(), "bar \"baz\"".into(), AliasKind::Regular)]
+        );
     }
 
     #[test]
     fn test_embedded_equals() {
         let input = "gcmsg='git commit -m'";
         let result = parse_alias_lines(input);
-        assert_eq!(result, vec![("gcmsg".into(), "git commit -m".into())]);
+        assert_eq!(
+            result,
+            vec![("gcmsg".into(), "git commit -m".into(), AliasKind::Regular)]
+        );
     }
 
     #[test]
@@ -168,6 +381,121 @@ mod tests {
     fn test_single_quote_escape() {
         let input = "test='it'\\''s working'";
         let result = parse_alias_lines(input);
-        assert_eq!(result, vec![("test".into(), "it's working".into())]);
+        assert_eq!(
+            result,
+            vec![("test".into(), "it's working".into(), AliasKind::Regular)]
+        );
+    }
+
+    #[test]
+    fn test_list_format_kinds() {
+        let input = "alias gst='git status'\nalias -g G='| grep'\nalias -s pdf=zathura";
+        let result = parse_alias_lines(input);
+        assert_eq!(
+            result,
+            vec![
+                ("gst".into(), "git status".into(), AliasKind::Regular),
+                ("G".into(), "| grep".into(), AliasKind::Global),
+                ("pdf".into(), "zathura".into(), AliasKind::Suffix),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_list_format_dash_name() {
+        let input = "alias -- -='cd -'";
+        let result = parse_alias_lines(input);
+        assert_eq!(result, vec![("-".into(), "cd -".into(), AliasKind::Regular)]);
+    }
+
+    #[test]
+    fn test_named_dirs() {
+        let input = "proj=/home/me/src/proj\nnotes='/home/me/My Notes'\n";
+        let result = parse_named_dir_lines(input);
+        assert_eq!(
+            result,
+            vec![
+                ("proj".into(), "/home/me/src/proj".into()),
+                ("notes".into(), "/home/me/My Notes".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_function_records() {
+        let input = "#zam-function mkcd\t/home/me/.zshrc\n\tmkdir -p \"$1\" && cd \"$1\"\n\
+                     #zam-function take\t\n\tbuiltin autoload -X\n";
+        let result = parse_function_records(input);
+        assert_eq!(
+            result,
+            vec![
+                (
+                    "mkcd".into(),
+                    "\tmkdir -p \"$1\" && cd \"$1\"".into(),
+                    Some("/home/me/.zshrc".into())
+                ),
+                ("take".into(), "\tbuiltin autoload -X".into(), None),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_bash_alias_p() {
+        let input = "alias ll='ls -la'\nalias q='echo '\\''hi'\\'''\n";
+        let result = parse_alias_lines(input);
+        assert_eq!(
+            result,
+            vec![
+                ("ll".into(), "ls -la".into(), AliasKind::Regular),
+                ("q".into(), "echo 'hi'".into(), AliasKind::Regular),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_fish_abbr_show() {
+        let input = "abbr -a -- gco 'git checkout'\n\
+                     abbr -a -U -- gs git\\ status\n\
+                     abbr -a --position anywhere -- L '| less'\n\
+                     abbr -a -- q 'echo \\'hi\\''\n";
+        let result = parse_fish_lines(input);
+        assert_eq!(
+            result,
+            vec![
+                ("gco".into(), "git checkout".into(), AliasKind::Regular),
+                ("gs".into(), "git status".into(), AliasKind::Regular),
+                ("L".into(), "| less".into(), AliasKind::Global),
+                ("q".into(), "echo 'hi'".into(), AliasKind::Regular),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_fish_alias_forms() {
+        let input = "alias ll 'ls -la'\nalias la=\"ls -A\"\nalias --save lt ls -lt\n";
+        let result = parse_fish_lines(input);
+        assert_eq!(
+            result,
+            vec![
+                ("ll".into(), "ls -la".into(), AliasKind::Regular),
+                ("la".into(), "ls -A".into(), AliasKind::Regular),
+                ("lt".into(), "ls -lt".into(), AliasKind::Regular),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_fish_non_definitions() {
+        assert_eq!(parse_fish_definition("abbr --erase gco"), None);
+        assert_eq!(parse_fish_definition("abbr -q gco; and echo yes"), None);
+        assert_eq!(parse_fish_definition("set -g fish_greeting"), None);
+        assert_eq!(parse_fish_definition("# abbr -a x y"), None);
+    }
+
+    #[test]
+    fn test_quoted_name() {
+        let input = "alias 'a=b'=c";
+        let result = parse_alias_lines(input);
+        assert_eq!(result, vec![("a=b".into(), "c".into(), AliasKind::Regular)]);
     }
 }
@@ -0,0 +1,305 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use std::fs;
+use std::path::PathBuf;
+
+use super::types::PluginManager;
+use crate::shell::{Shell, ShellPaths};
+
+/// An enabled plugin and the directory it was installed to.
+pub struct Plugin {
+    pub manager: PluginManager,
+    /// Prezto module name, or `user/repo[/subdir]` for the other managers.
+    pub name: String,
+    pub dir: PathBuf,
+}
+
+impl Plugin {
+    /// The plugin's top-level `.zsh` files in name order; aliases in
+    /// subdirectories are not attributed.
+    pub fn files(&self) -> Vec<PathBuf> {
+        let Ok(entries) = fs::read_dir(&self.dir) else {
+            return Vec::new();
+        };
+        let mut files: Vec<PathBuf> = entries
+            .flatten()
+            .map(|e| e.path())
+            .filter(|p| p.is_file() && p.extension().and_then(|e| e.to_str()) == Some("zsh"))
+            .collect();
+        files.sort();
+        files
+    }
+}
+
+/// Every installed, enabled plugin of every supported manager.
+pub fn detect_all(paths: &ShellPaths) -> Vec<Plugin> {
+    PluginManager::ALL
+        .into_iter()
+        .flat_map(|manager| detect(manager, paths))
+        .collect()
+}
+
+/// Installed, enabled plugins of one manager, in the order they're listed.
+pub fn detect(manager: PluginManager, paths: &ShellPaths) -> Vec<Plugin> {
+    let read = |path: PathBuf| fs::read_to_string(path).unwrap_or_default();
+    let zshrc = || read(paths.rc_file(Shell::Zsh));
+    let names = match manager {
+        PluginManager::Prezto => parse_prezto_modules(&read(paths.zdotdir.join(".zpreztorc"))),
+        PluginManager::Zinit => parse_zinit_plugins(&zshrc()),
+        PluginManager::Antidote => {
+            parse_antidote_bundles(&read(paths.zdotdir.join(".zsh_plugins.txt")))
+        }
+        PluginManager::Zplug => parse_zplug_plugins(&zshrc()),
+    };
+    names
+        .into_iter()
+        .map(|name| Plugin {
+            manager,
+            dir: plugin_dir(manager, paths, &name),
+            name,
+        })
+        .filter(|p| p.dir.is_dir())
+        .collect()
+}
+
+/// Files listing enabled plugins, besides .zshrc. Like .zshrc, prezto and
+/// antidote keep theirs in `$ZDOTDIR`.
+pub fn config_files(paths: &ShellPaths) -> Vec<PathBuf> {
+    vec![
+        paths.zdotdir.join(".zpreztorc"),
+        paths.zdotdir.join(".zsh_plugins.txt"),
+    ]
+}
+
+/// Where `manager` installs the plugin called `name`.
+pub fn plugin_dir(manager: PluginManager, paths: &ShellPaths, name: &str) -> PathBuf {
+    let home = &paths.home;
+    match manager {
+        PluginManager::Prezto => paths.zdotdir.join(".zprezto/modules").join(name),
+        PluginManager::Zinit => {
+            let root = [".local/share/zinit", ".zinit"]
+                .into_iter()
+                .map(|d| home.join(d))
+                .find(|d| d.is_dir())
+                .unwrap_or_else(|| home.join(".local/share/zinit"));
+            if name.contains("::") {
+                root.join("snippets").join(name)
+            } else {
+                root.join("plugins").join(name.replacen('/', "---", 1))
+            }
+        }
+        PluginManager::Antidote => {
+            let (repo, subdir) = split_repo(name);
+            let cache = home.join(".cache/antidote");
+            // antidote 1.9 clones to github.com/user/repo; older versions
+            // used an escaped URL as the directory name.
+            let dir = cache.join("github.com").join(repo);
+            let dir = if dir.is_dir() {
+                dir
+            } else {
+                cache.join(format!(
+                    "https-COLON--SLASH--SLASH-github.com-SLASH-{}",
+                    repo.replace('/', "-SLASH-")
+                ))
+            };
+            dir.join(subdir)
+        }
+        PluginManager::Zplug => {
+            let (repo, subdir) = split_repo(name);
+            home.join(".zplug/repos").join(repo).join(subdir)
+        }
+    }
+}
+
+/// Split `user/repo/sub/dir` into `user/repo` and `sub/dir`.
+fn split_repo(name: &str) -> (&str, &str) {
+    match name.match_indices('/').nth(1) {
+        Some((i, _)) => (&name[..i], &name[i + 1..]),
+        None => (name, ""),
+    }
+}
+
+fn unquote(word: &str) -> &str {
+    word.trim_matches(|c| c == '\'' || c == '"')
+}
+
+/// Modules from `zstyle ':prezto:load' pmodule 'a' 'b' \ ...`, which
+/// usually spans several lines joined with backslashes.
+fn parse_prezto_modules(zpreztorc: &str) -> Vec<String> {
+    let mut modules = Vec::new();
+    let mut inside = false;
+    for line in zpreztorc.lines() {
+        let line = line.trim();
+        if line.starts_with('#') {
+            continue;
+        }
+        let rest = if inside {
+            line
+        } else if let Some(pos) = line.find("':prezto:load' pmodule") {
+            &line[pos + "':prezto:load' pmodule".len()..]
+        } else {
+            continue;
+        };
+        let continued = rest.ends_with('\\');
+        modules.extend(
+            rest.trim_end_matches('\\')
+                .split_whitespace()
+                .map(unquote)
+                .filter(|w| !w.is_empty())
+                .map(String::from),
+        );
+        inside = continued;
+    }
+    modules
+}
+
+/// Plugins from `zinit light user/repo`, `zinit load user/repo` and
+/// `zinit snippet OMZP::git` (also spelled `zi`), one command per
+/// `;`/`&&`-separated statement.
+fn parse_zinit_plugins(zshrc: &str) -> Vec<String> {
+    let mut plugins = Vec::new();
+    for line in zshrc.lines() {
+        if line.trim_start().starts_with('#') {
+            continue;
+        }
+        for statement in line.split(';').flat_map(|s| s.split("&&")) {
+            let words: Vec<&str> = statement.split_whitespace().map(unquote).collect();
+            let [cmd, sub, args @ ..] = words.as_slice() else {
+                continue;
+            };
+            if !matches!(*cmd, "zinit" | "zi") || !matches!(*sub, "light" | "load" | "snippet") {
+                continue;
+            }
+            if let Some(arg) = args.iter().find(|a| !a.starts_with('-')) {
+                if *sub == "snippet" && !arg.contains("::") {
+                    continue; // URL snippets are stored under an escaped name
+                }
+                plugins.push(arg.to_string());
+            }
+        }
+    }
+    plugins
+}
+
+/// Bundles from antidote's ~/.zsh_plugins.txt: `user/repo`, optionally
+/// with a `path:sub/dir` annotation. Local paths are skipped.
+fn parse_antidote_bundles(plugins_txt: &str) -> Vec<String> {
+    let mut bundles = Vec::new();
+    for line in plugins_txt.lines() {
+        let line = line.split('#').next().unwrap_or("").trim();
+        let mut words = line.split_whitespace();
+        let Some(repo) = words.next() else {
+            continue;
+        };
+        let repo = repo.trim_start_matches("https://github.com/");
+        let repo = repo.trim_end_matches(".git");
+        if repo.starts_with(['/', '~', '$']) || repo.split('/').count() != 2 {
+            continue;
+        }
+        match words.find_map(|w| w.strip_prefix("path:")) {
+            Some(path) => bundles.push(format!("{repo}/{}", path.trim_matches('/'))),
+            None => bundles.push(repo.to_string()),
+        }
+    }
+    bundles
+}
+
+/// Plugins from `zplug "user/repo"` lines. `from:oh-my-zsh` plugins live
+/// inside zplug's oh-my-zsh checkout.
+fn parse_zplug_plugins(zshrc: &str) -> Vec<String> {
+    let mut plugins = Vec::new();
+    for line in zshrc.lines() {
+        let line = line.trim();
+        let Some(rest) = line.strip_prefix("zplug ") else {
+            continue;
+        };
+        let mut words = rest.split_whitespace().map(|w| unquote(w.trim_end_matches(',')));
+        let Some(name) = words.next() else {
+            continue;
+        };
+        if !name.contains('/') {
+            continue; // zplug install, zplug load, ...
+        }
+        if words.any(|w| w == "from:oh-my-zsh") {
+            plugins.push(format!("robbyrussell/oh-my-zsh/{name}"));
+        } else {
+            plugins.push(name.to_string());
+        }
+    }
+    plugins
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prezto_modules() {
+        let rc = "zstyle ':prezto:module:editor' key-bindings 'emacs'\n\
+                  zstyle ':prezto:load' pmodule \\\n  'environment' \\\n  'git' \\\n  'prompt'\n\
+                  zstyle ':prezto:module:prompt' theme 'sorin'\n";
+        assert_eq!(parse_prezto_modules(rc), vec!["environment", "git", "prompt"]);
+    }
+
+    #[test]
+    fn test_zinit_plugins() {
+        let rc = "zinit ice wait lucid; zinit light zsh-users/zsh-autosuggestions\n\
+                  zi load 'romkatv/powerlevel10k'\n\
+                  zinit snippet OMZP::git\n\
+                  zinit snippet https://example.com/x.zsh\n\
+                  # zinit light commented/out\n";
+        assert_eq!(
+            parse_zinit_plugins(rc),
+            vec!["zsh-users/zsh-autosuggestions", "romkatv/powerlevel10k", "OMZP::git"]
+        );
+    }
+
+    #[test]
+    fn test_antidote_bundles() {
+        let txt = "# comment\nzsh-users/zsh-completions\n\
+                   ohmyzsh/ohmyzsh path:plugins/git\n\
+                   https://github.com/rupa/z.git\n\
+                   ~/.zsh/local kind:fpath\n";
+        assert_eq!(
+            parse_antidote_bundles(txt),
+            vec!["zsh-users/zsh-completions", "ohmyzsh/ohmyzsh/plugins/git", "rupa/z"]
+        );
+    }
+
+    #[test]
+    fn test_zplug_plugins() {
+        let rc = "zplug \"zsh-users/zsh-history-substring-search\"\n\
+                  zplug \"plugins/git\", from:oh-my-zsh\n\
+                  zplug load --verbose\n";
+        assert_eq!(
+            parse_zplug_plugins(rc),
+            vec![
+                "zsh-users/zsh-history-substring-search",
+                "robbyrussell/oh-my-zsh/plugins/git"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_plugin_dirs() {
+        let home = std::path::Path::new("/nonexistent");
+        let mut paths = ShellPaths::defaults(home.to_path_buf());
+        paths.zdotdir = home.join(".config/zsh");
+        assert_eq!(
+            plugin_dir(PluginManager::Prezto, &paths, "git"),
+            home.join(".config/zsh/.zprezto/modules/git")
+        );
+        assert_eq!(
+            plugin_dir(PluginManager::Zinit, &paths, "zsh-users/zsh-autosuggestions"),
+            home.join(".local/share/zinit/plugins/zsh-users---zsh-autosuggestions")
+        );
+        assert_eq!(
+            plugin_dir(PluginManager::Zplug, &paths, "robbyrussell/oh-my-zsh/plugins/git"),
+            home.join(".zplug/repos/robbyrussell/oh-my-zsh/plugins/git")
+        );
+        assert_eq!(
+            plugin_dir(PluginManager::Antidote, &paths, "rupa/z"),
+            home.join(".cache/antidote/https-COLON--SLASH--SLASH-github.com-SLASH-rupa-SLASH-z")
+        );
+    }
+}
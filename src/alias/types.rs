@@ -2,23 +2,66 @@
 
 use std::fmt;
 
+use super::plugins;
+use crate::context::Context;
+use crate::shell::Shell;
+
 #[derive(Debug, thiserror::Error)]
 pub enum CollectError {
-    #[error("failed to run zsh: {source}")]
-    SpawnFailed { source: std::io::Error },
+    #[error("failed to run {shell}: {source}")]
+    SpawnFailed {
+        shell: Shell,
+        source: std::io::Error,
+    },
 
-    #[error("zsh exited with {status}: {stderr}")]
+    #[error("{shell} exited with {status}: {stderr}")]
     ShellFailed {
+        shell: Shell,
         status: std::process::ExitStatus,
         stderr: String,
     },
 
-    #[error("invalid UTF-8 from zsh: {source}")]
+    #[error("{shell} did not finish within {seconds}s (is an rc file waiting for input?)\n{shell} stderr:\n{stderr}")]
+    Timeout {
+        shell: Shell,
+        seconds: u64,
+        stderr: String,
+    },
+
+    #[error("invalid UTF-8 from {shell}: {source}")]
     InvalidUtf8 {
+        shell: Shell,
         source: std::string::FromUtf8Error,
     },
 }
 
+/// zsh plugin managers besides oh-my-zsh whose plugins zam can attribute.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PluginManager {
+    Prezto,
+    Zinit,
+    Antidote,
+    Zplug,
+}
+
+impl PluginManager {
+    pub const ALL: [PluginManager; 4] = [
+        PluginManager::Prezto,
+        PluginManager::Zinit,
+        PluginManager::Antidote,
+        PluginManager::Zplug,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            PluginManager::Prezto => "prezto",
+            PluginManager::Zinit => "zinit",
+            PluginManager::Antidote => "antidote",
+            PluginManager::Zplug => "zplug",
+        }
+    }
+}
+
 /// Represents where an alias was defined.
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[allow(dead_code)]
@@ -26,10 +69,28 @@ pub enum AliasSource {
     OhMyZshPlugin(String),
     OhMyZshLib(String),
     OhMyZshCustom(String),
+    /// A plugin (or Prezto module) loaded by another plugin manager.
+    Plugin {
+        manager: PluginManager,
+        name: String,
+    },
     Zshrc,
+    Bashrc,
+    BashAliases,
+    /// The system-wide /etc/bash.bashrc.
+    SystemBashrc,
+    FishConfig,
+    /// A snippet in ~/.config/fish/conf.d/, by file name.
+    FishConfD(String),
+    /// Universal abbreviations stored in ~/.config/fish/fish_variables.
+    FishUniversal,
     ZamExtension(String),
     ZamCustom,
     ZamOverride(String),
+    /// Named directories (`hash -d`) not managed by zam.
+    NamedDirs,
+    /// Named directories in ~/.config/zam/dirs/custom.zsh.
+    ZamDirs,
     Unknown,
 }
 
@@ -39,30 +100,56 @@ impl AliasSource {
             AliasSource::OhMyZshPlugin(name) => format!("oh-my-zsh plugin: {name}"),
             AliasSource::OhMyZshLib(name) => format!("oh-my-zsh lib: {name}"),
             AliasSource::OhMyZshCustom(name) => format!("oh-my-zsh custom: {name}"),
+            AliasSource::Plugin { manager, name } => match manager {
+                PluginManager::Prezto => format!("prezto module: {name}"),
+                _ => format!("{} plugin: {name}", manager.name()),
+            },
             AliasSource::Zshrc => "~/.zshrc".to_string(),
+            AliasSource::Bashrc => "~/.bashrc".to_string(),
+            AliasSource::BashAliases => "~/.bash_aliases".to_string(),
+            AliasSource::SystemBashrc => "/etc/bash.bashrc".to_string(),
+            AliasSource::FishConfig => "~/.config/fish/config.fish".to_string(),
+            AliasSource::FishConfD(name) => format!("fish conf.d: {name}"),
+            AliasSource::FishUniversal => "fish universal variables".to_string(),
             AliasSource::ZamExtension(name) => format!("zam ext: {name}"),
             AliasSource::ZamCustom => "zam custom".to_string(),
             AliasSource::ZamOverride(slug) => format!("zam override: {slug}"),
+            AliasSource::NamedDirs => "named directories".to_string(),
+            AliasSource::ZamDirs => "zam named directories".to_string(),
             AliasSource::Unknown => "Unknown".to_string(),
         }
     }
 
-    pub fn file_path(&self) -> Option<String> {
+    pub fn file_path(&self, ctx: &Context) -> Option<String> {
+        let paths = &ctx.paths;
+        let shell = ctx.shell;
+        let ext = shell.file_ext();
+        let zam = |rel: String| Some(ctx.display(&ctx.config_dir.join(rel)));
         match self {
-            AliasSource::OhMyZshPlugin(name) => {
-                Some(format!("~/.oh-my-zsh/plugins/{name}/{name}.plugin.zsh"))
+            AliasSource::OhMyZshPlugin(name) => Some(paths.display(&paths.omz_plugin_file(name))),
+            AliasSource::OhMyZshLib(name) => {
+                Some(paths.display(&paths.omz.join("lib").join(name)))
             }
-            AliasSource::OhMyZshLib(name) => Some(format!("~/.oh-my-zsh/lib/{name}")),
-            AliasSource::OhMyZshCustom(name) => Some(format!("~/.oh-my-zsh/custom/{name}")),
-            AliasSource::Zshrc => Some("~/.zshrc".to_string()),
-            AliasSource::ZamExtension(name) => {
-                Some(format!("~/.config/zam/aliases/{name}.zsh"))
+            AliasSource::OhMyZshCustom(name) => Some(paths.display(&paths.omz_custom.join(name))),
+            AliasSource::Plugin { manager, name } => {
+                let dir = plugins::plugin_dir(*manager, paths, name);
+                Some(format!("{}/", paths.display(&dir)))
             }
-            AliasSource::ZamCustom => Some("~/.config/zam/aliases/custom.zsh".to_string()),
-            AliasSource::ZamOverride(slug) => {
-                Some(format!("~/.config/zam/overrides/{slug}.zsh"))
+            AliasSource::Zshrc => Some(paths.display(&paths.rc_file(Shell::Zsh))),
+            AliasSource::Bashrc => Some("~/.bashrc".to_string()),
+            AliasSource::BashAliases => Some("~/.bash_aliases".to_string()),
+            AliasSource::SystemBashrc => Some("/etc/bash.bashrc".to_string()),
+            AliasSource::FishConfig => Some("~/.config/fish/config.fish".to_string()),
+            AliasSource::FishConfD(name) => Some(format!("~/.config/fish/conf.d/{name}")),
+            AliasSource::FishUniversal => Some("~/.config/fish/fish_variables".to_string()),
+            AliasSource::ZamExtension(name) => zam(format!("aliases/{name}.{ext}")),
+            AliasSource::ZamCustom if shell == Shell::Fish => {
+                Some(paths.display(&paths.rc_file(shell)))
             }
-            AliasSource::Unknown => None,
+            AliasSource::ZamCustom => zam(format!("aliases/custom.{ext}")),
+            AliasSource::ZamOverride(slug) => zam(format!("overrides/{slug}.{ext}")),
+            AliasSource::ZamDirs => zam("dirs/custom.zsh".to_string()),
+            AliasSource::NamedDirs | AliasSource::Unknown => None,
         }
     }
 
@@ -72,7 +159,16 @@ impl AliasSource {
             AliasSource::OhMyZshLib(n) => (0, n.clone()),
             AliasSource::OhMyZshPlugin(n) => (1, n.clone()),
             AliasSource::OhMyZshCustom(n) => (2, n.clone()),
+            AliasSource::Plugin { manager, name } => (2, format!("{} {name}", manager.name())),
             AliasSource::Zshrc => (3, String::new()),
+            AliasSource::SystemBashrc => (3, "0".to_string()),
+            AliasSource::Bashrc => (3, "1".to_string()),
+            AliasSource::BashAliases => (3, "2".to_string()),
+            AliasSource::FishUniversal => (3, "0".to_string()),
+            AliasSource::FishConfD(n) => (3, format!("1{n}")),
+            AliasSource::FishConfig => (3, "2".to_string()),
+            AliasSource::NamedDirs => (5, String::new()),
+            AliasSource::ZamDirs => (6, String::new()),
             AliasSource::ZamExtension(n) => (7, n.clone()),
             AliasSource::ZamCustom => (8, String::new()),
             AliasSource::ZamOverride(slug) => (9, slug.clone()),
@@ -91,6 +187,11 @@ pub enum ManagedError {
     NotInitialized,
     EmptyName,
     InvalidName(String),
+    /// The selected shell has no equivalent of this kind.
+    UnsupportedKind(AliasKind, Shell),
+    /// The alias isn't defined in a file zam writes, so it can't be moved.
+    NotManaged(String),
+    InvalidTag(String),
 }
 
 impl fmt::Display for ManagedError {
@@ -106,23 +207,183 @@ impl fmt::Display for ManagedError {
             ManagedError::InvalidName(name) => {
                 write!(f, "invalid alias name: {name}")
             }
+            ManagedError::UnsupportedKind(kind, shell) => {
+                write!(f, "{shell} has no {} aliases", kind.label())
+            }
+            ManagedError::NotManaged(name) => {
+                write!(f, "{name} is not in a zam alias file")
+            }
+            ManagedError::InvalidTag(tag) => write!(f, "invalid tag: {tag:?}"),
         }
     }
 }
 
+/// The three zsh alias tables, plus named directories (`hash -d`), which
+/// zam manages the same way.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AliasKind {
+    /// `alias name=value` — expanded in command position.
+    #[default]
+    Regular,
+    /// `alias -g name=value` — expanded anywhere on the line.
+    Global,
+    /// `alias -s ext=value` — runs `value file.ext` for `file.ext`.
+    Suffix,
+    /// `hash -d name=path` — makes `~name` expand to `path`.
+    NamedDir,
+    /// A shell function; `command` holds its body. Read-only.
+    Function,
+}
+
+impl AliasKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            AliasKind::Regular => "regular",
+            AliasKind::Global => "global",
+            AliasKind::Suffix => "suffix",
+            AliasKind::NamedDir => "named dir",
+            AliasKind::Function => "function",
+        }
+    }
+
+    /// The `alias`/`unalias` flag selecting this table, if any.
+    pub fn flag(self) -> Option<&'static str> {
+        match self {
+            AliasKind::Regular | AliasKind::NamedDir | AliasKind::Function => None,
+            AliasKind::Global => Some("-g"),
+            AliasKind::Suffix => Some("-s"),
+        }
+    }
+
+    /// The next kind the edit form can create in `shell`. Functions can't
+    /// be created.
+    pub fn next(self, shell: Shell) -> Self {
+        let next = match self {
+            AliasKind::Regular => AliasKind::Global,
+            AliasKind::Global => AliasKind::Suffix,
+            AliasKind::Suffix => AliasKind::NamedDir,
+            AliasKind::NamedDir | AliasKind::Function => AliasKind::Regular,
+        };
+        if next.supported_by(shell) {
+            next
+        } else {
+            next.next(shell)
+        }
+    }
+
+    /// Whether `shell` can define this kind. bash only has regular aliases;
+    /// fish abbreviations can also expand anywhere (`--position anywhere`),
+    /// which zam treats as global.
+    pub fn supported_by(self, shell: Shell) -> bool {
+        match shell {
+            Shell::Zsh => true,
+            Shell::Bash => matches!(self, AliasKind::Regular | AliasKind::Function),
+            Shell::Fish => matches!(
+                self,
+                AliasKind::Regular | AliasKind::Global | AliasKind::Function
+            ),
+        }
+    }
+
+    pub fn section(self) -> Section {
+        match self {
+            AliasKind::Function => Section::Functions,
+            _ => Section::Aliases,
+        }
+    }
+
+    /// Whether two kinds share a zsh name table, so that defining one
+    /// replaces the other. Regular and global aliases share a table.
+    pub fn same_table(self, other: AliasKind) -> bool {
+        let table = |k| match k {
+            AliasKind::Regular | AliasKind::Global => 0,
+            AliasKind::Suffix => 1,
+            AliasKind::NamedDir => 2,
+            AliasKind::Function => 3,
+        };
+        table(self) == table(other)
+    }
+}
+
+/// Top-level sections of the alias list.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Section {
+    Aliases,
+    Functions,
+}
+
+impl Section {
+    pub fn title(self) -> &'static str {
+        match self {
+            Section::Aliases => "Aliases",
+            Section::Functions => "Functions",
+        }
+    }
+}
+
+/// Whether an alias is live in the shell, or kept in its file by zam but
+/// not sourced.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AliasState {
+    #[default]
+    Enabled,
+    Disabled,
+}
+
 /// A single alias definition.
 #[derive(Debug, Clone)]
 pub struct Alias {
     pub name: String,
     pub command: String,
+    pub kind: AliasKind,
     pub source: AliasSource,
     pub description: Option<String>,
+    pub state: AliasState,
+    /// From zam's tags file, not the shell.
+    pub tags: Vec<String>,
+    /// A named directory whose target didn't exist when it was collected
+    /// or last written; see `check_target()`.
+    pub missing: bool,
+}
+
+impl Alias {
+    /// Look up whether a named directory's target exists, once, so the
+    /// list and detail panel don't stat it on every frame.
+    pub fn check_target(&mut self) {
+        self.missing = self.kind == AliasKind::NamedDir && !std::path::Path::new(&self.command).is_dir();
+    }
+
+    /// One-line form of the command: functions show the first line of
+    /// their body.
+    pub fn summary(&self) -> &str {
+        if self.kind == AliasKind::Function {
+            self.command
+                .lines()
+                .map(str::trim)
+                .find(|l| !l.is_empty())
+                .unwrap_or("")
+        } else {
+            &self.command
+        }
+    }
 }
 
-/// A group of aliases from the same source.
+/// A group of aliases from the same source. Groups never mix sections.
 #[derive(Debug, Clone)]
 pub struct AliasGroup {
     pub source: AliasSource,
     pub aliases: Vec<Alias>,
-    pub collapsed: bool,
+}
+
+impl AliasGroup {
+    pub fn section(&self) -> Section {
+        self.aliases
+            .first()
+            .map_or(Section::Aliases, |a| a.kind.section())
+    }
+
+    /// Sort key so sections, then groups, appear in a consistent order.
+    pub fn sort_key(&self) -> (Section, (u8, String)) {
+        (self.section(), self.source.sort_key())
+    }
 }
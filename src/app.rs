@@ -1,14 +1,122 @@
 // SPDX-License-Identifier: Apache-2.0
 
+use std::collections::HashMap;
+
+use ratatui::layout::{Margin, Position};
+
 use crate::action::Action;
-use crate::alias::types::{Alias, AliasGroup, AliasSource};
+use crate::alias::types::{Alias, AliasGroup, AliasKind, AliasSource, AliasState, ManagedError, Section};
 use crate::collision;
+use crate::context::Context;
+use crate::event::{Mouse, MouseKind};
+use crate::extensions;
+use crate::history;
+use crate::keymap::Keymap;
+use crate::line_editor::LineEditor;
 use crate::managed;
+use crate::run;
+use crate::session::Session;
+use crate::shell::Shell;
+use crate::ui::help_bar;
+use crate::ui::layout::AppLayout;
+use crate::ui::theme::Theme;
+use crate::validate::{self, Problem};
 
 enum SearchField {
     Name,
     Command,
     Group,
+    Tag,
+}
+
+/// How the alias list is grouped and sorted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ListMode {
+    #[default]
+    BySource,
+    ByTag,
+    /// One alphabetical list per section.
+    Flat,
+    /// Flat, sorted by command, so near-duplicates sit together.
+    ByCommand,
+    /// Flat, shortest name first.
+    ByLength,
+    /// Flat, most used first, from the shell's history.
+    ByUsage,
+}
+
+impl ListMode {
+    const ALL: [ListMode; 6] = [
+        ListMode::BySource,
+        ListMode::ByTag,
+        ListMode::Flat,
+        ListMode::ByCommand,
+        ListMode::ByLength,
+        ListMode::ByUsage,
+    ];
+
+    fn next(self) -> Self {
+        let i = ListMode::ALL.iter().position(|&m| m == self).unwrap_or(0);
+        ListMode::ALL[(i + 1) % ListMode::ALL.len()]
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ListMode::BySource => "by source",
+            ListMode::ByTag => "by tag",
+            ListMode::Flat => "flat",
+            ListMode::ByCommand => "by command",
+            ListMode::ByLength => "by length",
+            ListMode::ByUsage => "by usage",
+        }
+    }
+
+    /// The name the session file uses.
+    pub fn name(self) -> &'static str {
+        match self {
+            ListMode::BySource => "source",
+            ListMode::ByTag => "tag",
+            ListMode::Flat => "flat",
+            ListMode::ByCommand => "command",
+            ListMode::ByLength => "length",
+            ListMode::ByUsage => "usage",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        ListMode::ALL.into_iter().find(|m| m.name() == name)
+    }
+}
+
+/// What a group in the list gathers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GroupKey {
+    Source(AliasSource),
+    Tag(String),
+    Untagged,
+    /// A whole section, in flat mode; it has no header.
+    All,
+}
+
+impl GroupKey {
+    /// How the session file names it.
+    pub fn name(&self) -> String {
+        match self {
+            GroupKey::Source(source) => format!("source {}", source.display_name()),
+            GroupKey::Tag(tag) => format!("tag {tag}"),
+            GroupKey::Untagged => "untagged".to_string(),
+            GroupKey::All => "all".to_string(),
+        }
+    }
+}
+
+/// A group as the list shows it: one source's aliases, or one tag's.
+#[derive(Debug, Clone)]
+pub struct ListGroup {
+    pub key: GroupKey,
+    pub section: Section,
+    pub title: String,
+    pub aliases: Vec<Alias>,
 }
 
 /// Which panel has focus when in normal mode.
@@ -23,6 +131,28 @@ pub enum PanelFocus {
 pub enum EditField {
     Name,
     Command,
+    Description,
+    Kind,
+}
+
+impl EditField {
+    fn next(self) -> Self {
+        match self {
+            EditField::Name => EditField::Command,
+            EditField::Command => EditField::Description,
+            EditField::Description => EditField::Kind,
+            EditField::Kind => EditField::Name,
+        }
+    }
+
+    fn prev(self) -> Self {
+        match self {
+            EditField::Name => EditField::Kind,
+            EditField::Command => EditField::Name,
+            EditField::Description => EditField::Command,
+            EditField::Kind => EditField::Description,
+        }
+    }
 }
 
 /// The current input mode.
@@ -34,6 +164,9 @@ pub enum InputMode {
     Confirm,
     ResultPopup,
     Help,
+    /// Typing the arguments to run an alias with, then reading its output.
+    RunArgs,
+    RunOutput,
 }
 
 impl InputMode {
@@ -52,31 +185,142 @@ impl InputMode {
 /// State for the edit form (create or edit).
 #[derive(Debug, Clone)]
 pub struct EditState {
-    pub name: String,
-    pub command: String,
+    pub name: LineEditor,
+    pub command: LineEditor,
+    /// Written as a comment above the alias; empty for none.
+    pub description: LineEditor,
+    pub kind: AliasKind,
     pub active_field: EditField,
     pub is_new: bool,
-    /// For edits: the original name and source.
+    /// For edits: the original name, kind and source.
     pub original_name: Option<String>,
+    pub original_kind: Option<AliasKind>,
     pub original_source: Option<AliasSource>,
+    /// What the last live check found, and the fields it was run on.
+    pub problems: Vec<Problem>,
+    pub checked: Option<validate::Input>,
+}
+
+impl EditState {
+    fn new(name: &str, command: &str, kind: AliasKind, field: EditField) -> Self {
+        EditState {
+            name: LineEditor::new(name),
+            command: LineEditor::new(command),
+            description: LineEditor::default(),
+            kind,
+            active_field: field,
+            is_new: true,
+            original_name: None,
+            original_kind: None,
+            original_source: None,
+            problems: Vec::new(),
+            checked: None,
+        }
+    }
+
+    /// The fields as the live check sees them.
+    pub fn check_input(&self) -> validate::Input {
+        validate::Input {
+            name: self.name.text().to_string(),
+            command: self.command.text().to_string(),
+            kind: self.kind,
+            original: self.original_name.clone().zip(self.original_kind),
+        }
+    }
+
+    /// The text field with focus; none on the kind selector.
+    pub fn active_line(&mut self) -> Option<&mut LineEditor> {
+        match self.active_field {
+            EditField::Name => Some(&mut self.name),
+            EditField::Command => Some(&mut self.command),
+            EditField::Description => Some(&mut self.description),
+            EditField::Kind => None,
+        }
+    }
+}
+
+/// An alias run in place: the arguments being typed, then what it printed.
+#[derive(Debug, Clone)]
+pub struct RunState {
+    pub alias: Alias,
+    pub args: LineEditor,
+    /// The command line once it has been started.
+    pub command_line: Option<String>,
+    /// None until the command finishes.
+    pub output: Option<run::Output>,
+    /// Lines of output scrolled off the top; clamped when drawn.
+    pub scroll: u16,
+}
+
+/// Whether an alias is in a zam alias file it can move out of. Disabled
+/// definitions are commented out, so there is no line to move.
+fn movable(alias: &Alias) -> bool {
+    matches!(alias.source, AliasSource::ZamCustom | AliasSource::ZamExtension(_))
+        && !matches!(alias.kind, AliasKind::NamedDir | AliasKind::Function)
+        && alias.state != AliasState::Disabled
+}
+
+/// Which alias an entry is, across list rebuilds and refreshes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AliasKey {
+    pub name: String,
+    pub kind: AliasKind,
+    pub source: AliasSource,
+}
+
+impl AliasKey {
+    pub fn of(alias: &Alias) -> Self {
+        AliasKey {
+            name: alias.name.clone(),
+            kind: alias.kind,
+            source: alias.source.clone(),
+        }
+    }
+
+    pub fn matches(&self, alias: &Alias) -> bool {
+        self.name == alias.name && self.kind == alias.kind && self.source == alias.source
+    }
+}
+
+/// An action on every marked alias.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BulkOp {
+    Delete,
+    Disable,
+    Enable,
+    Export,
+    /// Into one of `targets`, the custom file and installed extensions.
+    Move { targets: Vec<AliasSource>, target: usize },
 }
 
 /// Pending action that needs confirmation.
 #[derive(Debug, Clone)]
 pub enum PendingAction {
     Delete {
-        alias_name: String,
-        alias_source: AliasSource,
+        alias: Alias,
     },
     CollisionOverride {
-        edit_state: EditState,
+        edit_state: Box<EditState>,
         collision_desc: String,
     },
+    /// `aliases` are the ones the action applies to; `skipped` were
+    /// marked but can't take part.
+    Bulk {
+        op: BulkOp,
+        aliases: Vec<Alias>,
+        skipped: usize,
+    },
 }
 
 /// Represents a visible item in the left-panel list.
 #[derive(Debug, Clone)]
 pub enum ListItem {
+    /// Divider above each section when the list has more than one.
+    SectionHeader {
+        section: Section,
+        alias_count: usize,
+    },
+    /// `group_index` is into `AppState::list_groups`.
     GroupHeader {
         group_index: usize,
         display_name: String,
@@ -86,28 +330,71 @@ pub enum ListItem {
     AliasEntry {
         group_index: usize,
         alias: Alias,
+        marked: bool,
     },
 }
 
 pub struct AppState {
     pub groups: Vec<AliasGroup>,
+    /// Shell and file locations; `paths` is refreshed from each collection.
+    pub ctx: Context,
+    /// Key bindings and colours; the built-in ones unless main loads a
+    /// config file.
+    pub keymap: Keymap,
+    pub theme: Theme,
+    pub list_mode: ListMode,
+    /// Times each command was run, read from history the first time the
+    /// list is sorted by usage.
+    pub usage: Option<HashMap<String, usize>>,
+    /// The groups the list shows in `list_mode`, and which are collapsed.
+    pub list_groups: Vec<ListGroup>,
+    pub collapsed: Vec<GroupKey>,
     pub visible_items: Vec<ListItem>,
     pub selected_index: usize,
     pub search_query: String,
     pub mode: InputMode,
     pub should_quit: bool,
     pub list_scroll_offset: usize,
+    /// Lines scrolled off the top of the detail panel, with the mouse
+    /// wheel; clamped when drawn.
+    pub detail_scroll: u16,
+    /// Lines scrolled off the top of the help screen; clamped when drawn.
+    pub help_scroll: u16,
+    /// Counts the changes zam has made to alias files, so a collection
+    /// started before the latest one can be told to be stale.
+    pub writes: u64,
     pub edit_state: Option<EditState>,
+    /// Aliases marked for a bulk action, and the last one marked, where
+    /// a range starts.
+    pub marked: Vec<AliasKey>,
+    pub mark_anchor: Option<AliasKey>,
     pub pending_action: Option<PendingAction>,
     pub result_message: Option<String>,
     pub status_message: Option<String>,
+    /// Text for the event loop to put on the clipboard, since that means
+    /// writing to the terminal.
+    pub copy_request: Option<String>,
+    pub run: Option<RunState>,
+    /// A command line for the event loop to run in the background and
+    /// hand to `finish_run`.
+    pub run_request: Option<String>,
+    /// The alias chosen with `PickAlias`, printed on exit for the shell to
+    /// insert.
+    pub picked: Option<String>,
     pub status_tick: u8,
 }
 
 impl AppState {
-    pub fn new(groups: Vec<AliasGroup>) -> Self {
+    pub fn new(groups: Vec<AliasGroup>, ctx: Context) -> Self {
         let mut state = AppState {
             groups,
+            ctx,
+            keymap: Keymap::default(),
+            theme: Theme::default(),
+            list_mode: ListMode::default(),
+            usage: None,
+            list_groups: Vec::new(),
+            collapsed: Vec::new(),
             visible_items: Vec::new(),
             selected_index: 0,
             search_query: String::new(),
@@ -116,10 +403,19 @@ impl AppState {
             },
             should_quit: false,
             list_scroll_offset: 0,
+            detail_scroll: 0,
+            help_scroll: 0,
+            writes: 0,
             edit_state: None,
+            marked: Vec::new(),
+            mark_anchor: None,
             pending_action: None,
             result_message: None,
             status_message: None,
+            copy_request: None,
+            run: None,
+            run_request: None,
+            picked: None,
             status_tick: 0,
         };
         state.rebuild_visible();
@@ -127,19 +423,33 @@ impl AppState {
     }
 
     pub fn handle_action(&mut self, action: Action) {
+        match self.mode {
+            InputMode::RunArgs => return self.run_args_action(action),
+            InputMode::RunOutput => return self.run_output_action(action),
+            InputMode::Help => return self.help_action(action),
+            _ => {}
+        }
         match action {
             Action::Quit => self.should_quit = true,
             Action::ScrollUp => self.move_selection(-1),
             Action::ScrollDown => self.move_selection(1),
             Action::PageUp => self.move_selection(-10),
             Action::PageDown => self.move_selection(10),
-            Action::GoToTop => self.selected_index = 0,
+            Action::GoToTop => {
+                self.selected_index = 0;
+                self.detail_scroll = 0;
+            }
             Action::GoToBottom => {
                 if !self.visible_items.is_empty() {
                     self.selected_index = self.visible_items.len() - 1;
+                    self.detail_scroll = 0;
                 }
             }
             Action::ToggleCollapse => self.toggle_selected_group(),
+            Action::CycleListMode => {
+                self.set_list_mode(self.list_mode.next());
+                self.selected_index = 0;
+            }
             Action::FocusSearch => self.mode = InputMode::Search,
             Action::ExitSearch => {
                 self.mode = InputMode::Normal {
@@ -172,19 +482,58 @@ impl AppState {
             // CRUD
             Action::CreateAlias => self.start_create(),
             Action::EditAlias => self.start_edit(),
+            Action::DeleteAlias if !self.marked.is_empty() => self.start_bulk(BulkOp::Delete),
             Action::DeleteAlias => self.start_delete(),
+            Action::ToggleDisabled => self.toggle_disabled(),
+            Action::CopyName | Action::CopyCommand | Action::CopyLine => self.copy(action),
+            Action::PickAlias => {
+                if let Some(alias) = self.selected_alias() {
+                    self.picked = Some(alias.name.clone());
+                    self.should_quit = true;
+                }
+            }
+            Action::RunAlias => self.start_run(),
+
+            // Marks and bulk actions
+            Action::ToggleMark => self.toggle_mark(),
+            Action::MarkRange => self.mark_range(),
+            Action::ClearMarks => {
+                self.marked.clear();
+                self.mark_anchor = None;
+                self.rebuild_visible();
+            }
+            Action::ExportAliases => self.start_bulk(BulkOp::Export),
+            Action::MoveAliases => self.start_move(),
 
             // Edit mode
             Action::EditInput(c) => self.edit_input(c),
             Action::EditBackspace => self.edit_backspace(),
             Action::EditClear => self.edit_clear(),
-            Action::EditNextField | Action::EditPrevField => self.edit_toggle_field(),
+            Action::EditDelete => self.edit_line(LineEditor::delete),
+            Action::EditDeleteWord => self.edit_line(LineEditor::delete_word),
+            Action::EditLeft => self.edit_line(LineEditor::left),
+            Action::EditRight => self.edit_line(LineEditor::right),
+            Action::EditHome => self.edit_line(LineEditor::home),
+            Action::EditEnd => self.edit_line(LineEditor::end),
+            Action::EditWordLeft => self.edit_line(LineEditor::word_left),
+            Action::EditWordRight => self.edit_line(LineEditor::word_right),
+            Action::EditNextField => self.edit_move_field(EditField::next),
+            Action::EditPrevField => self.edit_move_field(EditField::prev),
             Action::SaveEdit => self.save_edit(),
             Action::CancelEdit => self.cancel_edit(),
 
             // Confirm mode
             Action::ConfirmYes => self.execute_pending(),
             Action::ConfirmNo => self.cancel_confirm(),
+            Action::ConfirmNext => {
+                if let Some(PendingAction::Bulk {
+                    op: BulkOp::Move { targets, target },
+                    ..
+                }) = &mut self.pending_action
+                {
+                    *target = (*target + 1) % targets.len();
+                }
+            }
 
             // Result popup
             Action::DismissResult => {
@@ -195,12 +544,16 @@ impl AppState {
             }
 
             // Help
-            Action::ShowHelp => self.mode = InputMode::Help,
+            Action::ShowHelp => {
+                self.mode = InputMode::Help;
+                self.help_scroll = 0;
+            }
             Action::CloseHelp => {
                 self.mode = InputMode::Normal {
                     focus: PanelFocus::List,
                 };
             }
+            Action::CloseRun => self.close_run(),
         }
         self.clamp_selection();
     }
@@ -208,38 +561,39 @@ impl AppState {
     // ── CRUD helpers ──────────────────────────────────────────────────
 
     fn start_create(&mut self) {
-        if !managed::is_initialized() {
+        if !managed::is_initialized(&self.ctx) {
             self.set_status("Not initialized — run `zam --init` first");
             return;
         }
-        self.edit_state = Some(EditState {
-            name: String::new(),
-            command: String::new(),
-            active_field: EditField::Name,
-            is_new: true,
-            original_name: None,
-            original_source: None,
-        });
+        self.edit_state = Some(EditState::new("", "", AliasKind::Regular, EditField::Name));
         self.mode = InputMode::Editing {
             field: EditField::Name,
         };
     }
 
     fn start_edit(&mut self) {
-        if !managed::is_initialized() {
+        if !managed::is_initialized(&self.ctx) {
             self.set_status("Not initialized — run `zam --init` first");
             return;
         }
         let Some(alias) = self.selected_alias().cloned() else {
             return;
         };
+        if alias.kind == AliasKind::Function {
+            self.set_status("Functions are read-only");
+            return;
+        }
+        if alias.state == AliasState::Disabled {
+            self.set_status("Enable the alias to edit it");
+            return;
+        }
         self.edit_state = Some(EditState {
-            name: alias.name.clone(),
-            command: alias.command.clone(),
-            active_field: EditField::Command,
+            description: LineEditor::new(alias.description.as_deref().unwrap_or("")),
             is_new: false,
-            original_name: Some(alias.name),
+            original_name: Some(alias.name.clone()),
+            original_kind: Some(alias.kind),
             original_source: Some(alias.source),
+            ..EditState::new(&alias.name, &alias.command, alias.kind, EditField::Command)
         });
         self.mode = InputMode::Editing {
             field: EditField::Command,
@@ -247,27 +601,460 @@ impl AppState {
     }
 
     fn start_delete(&mut self) {
-        if !managed::is_initialized() {
+        if !managed::is_initialized(&self.ctx) {
+            self.set_status("Not initialized — run `zam --init` first");
+            return;
+        }
+        let Some(alias) = self.selected_alias().cloned() else {
+            return;
+        };
+        if alias.kind == AliasKind::Function {
+            self.set_status("Functions are read-only");
+            return;
+        }
+        self.pending_action = Some(PendingAction::Delete { alias });
+        self.mode = InputMode::Confirm;
+    }
+
+    /// Copy the selected alias's name, command, or definition as the
+    /// shell would read it.
+    fn copy(&mut self, action: Action) {
+        let Some(alias) = self.selected_alias() else {
+            return;
+        };
+        let (text, what) = match action {
+            Action::CopyName => (alias.name.clone(), "name"),
+            Action::CopyCommand => (alias.command.clone(), "command"),
+            _ => {
+                let line = match (alias.kind, self.ctx.shell) {
+                    (AliasKind::Function, Shell::Fish) => {
+                        format!("function {}\n{}\nend", alias.name, alias.command)
+                    }
+                    (AliasKind::Function, _) => format!("{} () {{\n{}\n}}", alias.name, alias.command),
+                    (kind, _) => managed::definition_line(&self.ctx, &alias.name, &alias.command, kind),
+                };
+                (line, "definition")
+            }
+        };
+        let msg = format!("Copied the {what} of {}", alias.name);
+        self.copy_request = Some(text);
+        self.set_status(&msg);
+    }
+
+    /// Disable the selected alias, or enable it again. With marks, ask
+    /// first: enable them if all are disabled, else disable the rest.
+    fn toggle_disabled(&mut self) {
+        if !managed::is_initialized(&self.ctx) {
             self.set_status("Not initialized — run `zam --init` first");
             return;
         }
+        if !self.marked.is_empty() {
+            let all_disabled = self
+                .bulk_aliases()
+                .iter()
+                .filter(|a| a.kind != AliasKind::Function)
+                .all(|a| a.state == AliasState::Disabled);
+            self.start_bulk(if all_disabled { BulkOp::Enable } else { BulkOp::Disable });
+            return;
+        }
         let Some(alias) = self.selected_alias().cloned() else {
             return;
         };
-        self.pending_action = Some(PendingAction::Delete {
-            alias_name: alias.name,
-            alias_source: alias.source,
+        if alias.kind == AliasKind::Function {
+            self.set_status("Functions are read-only");
+            return;
+        }
+        let state = match alias.state {
+            AliasState::Enabled => AliasState::Disabled,
+            AliasState::Disabled => AliasState::Enabled,
+        };
+        match self.set_alias_state(&alias, state) {
+            Ok(()) if state == AliasState::Disabled => self.set_status(&format!("Disabled {}", alias.name)),
+            Ok(()) => self.set_status(&format!("Enabled {}", alias.name)),
+            Err(e) => self.set_status(&e.to_string()),
+        }
+        self.rebuild_visible();
+    }
+
+    /// Disable or enable an alias on disk and in the list.
+    fn set_alias_state(&mut self, alias: &Alias, state: AliasState) -> Result<(), ManagedError> {
+        self.writes += 1;
+        match state {
+            AliasState::Disabled => managed::disable_alias(&self.ctx, alias)?,
+            AliasState::Enabled => managed::enable_alias(&self.ctx, alias)?,
+        }
+        let key = AliasKey::of(alias);
+        for a in self.groups.iter_mut().flat_map(|g| &mut g.aliases) {
+            if key.matches(a) {
+                a.state = state;
+            }
+        }
+        Ok(())
+    }
+
+    // ── Running aliases ───────────────────────────────────────────────
+
+    fn start_run(&mut self) {
+        let Some(alias) = self.selected_alias().cloned() else {
+            return;
+        };
+        if matches!(alias.kind, AliasKind::NamedDir | AliasKind::Suffix) {
+            self.set_status("Only aliases and functions can be run");
+            return;
+        }
+        if alias.state == AliasState::Disabled {
+            self.set_status("Enable the alias to run it");
+            return;
+        }
+        self.run = Some(RunState {
+            alias,
+            args: LineEditor::new(""),
+            command_line: None,
+            output: None,
+            scroll: 0,
+        });
+        self.mode = InputMode::RunArgs;
+    }
+
+    /// The edit table's actions, applied to the arguments line.
+    fn run_args_action(&mut self, action: Action) {
+        let Some(run) = &mut self.run else {
+            return;
+        };
+        match action {
+            Action::EditInput(c) => run.args.insert(c),
+            Action::EditBackspace => run.args.backspace(),
+            Action::EditClear => run.args.clear(),
+            Action::EditDelete => run.args.delete(),
+            Action::EditDeleteWord => run.args.delete_word(),
+            Action::EditLeft => run.args.left(),
+            Action::EditRight => run.args.right(),
+            Action::EditHome => run.args.home(),
+            Action::EditEnd => run.args.end(),
+            Action::EditWordLeft => run.args.word_left(),
+            Action::EditWordRight => run.args.word_right(),
+            Action::SaveEdit => {
+                let command_line = run::command_line(&self.ctx, &run.alias, run.args.text());
+                run.command_line = Some(command_line.clone());
+                self.run_request = Some(command_line);
+                self.mode = InputMode::RunOutput;
+            }
+            Action::CancelEdit => self.close_run(),
+            Action::Quit => self.should_quit = true,
+            _ => {}
+        }
+    }
+
+    /// The normal table's motions scroll the output; Esc or Enter closes it.
+    fn run_output_action(&mut self, action: Action) {
+        let Some(run) = &mut self.run else {
+            return;
+        };
+        match action {
+            Action::ScrollUp => run.scroll = run.scroll.saturating_sub(1),
+            Action::ScrollDown => run.scroll = run.scroll.saturating_add(1),
+            Action::PageUp => run.scroll = run.scroll.saturating_sub(10),
+            Action::PageDown => run.scroll = run.scroll.saturating_add(10),
+            Action::GoToTop => run.scroll = 0,
+            Action::GoToBottom => run.scroll = u16::MAX,
+            Action::CloseRun => self.close_run(),
+            Action::Quit => self.should_quit = true,
+            _ => {}
+        }
+    }
+
+    /// The help screen scrolls with the movement keys; anything else
+    /// closes it.
+    fn help_action(&mut self, action: Action) {
+        match action {
+            Action::ScrollUp => self.help_scroll = self.help_scroll.saturating_sub(1),
+            Action::ScrollDown => self.help_scroll = self.help_scroll.saturating_add(1),
+            Action::PageUp => self.help_scroll = self.help_scroll.saturating_sub(10),
+            Action::PageDown => self.help_scroll = self.help_scroll.saturating_add(10),
+            Action::GoToTop => self.help_scroll = 0,
+            Action::GoToBottom => self.help_scroll = u16::MAX,
+            Action::Quit => self.should_quit = true,
+            _ => {
+                self.mode = InputMode::Normal {
+                    focus: PanelFocus::List,
+                }
+            }
+        }
+    }
+
+    /// Show what a run started with `run_request` printed, unless its
+    /// popup was closed meanwhile.
+    pub fn finish_run(&mut self, command_line: &str, output: run::Output) {
+        if let Some(run) = &mut self.run {
+            if run.output.is_none() && run.command_line.as_deref() == Some(command_line) {
+                run.output = Some(output);
+            }
+        }
+    }
+
+    fn close_run(&mut self) {
+        self.run = None;
+        self.mode = InputMode::Normal {
+            focus: PanelFocus::List,
+        };
+    }
+
+    // ── Marks and bulk actions ────────────────────────────────────────
+
+    /// Mark or unmark the selected alias and move down, or on a group
+    /// header, mark the group's aliases (unmark them if all are marked).
+    fn toggle_mark(&mut self) {
+        match self.visible_items.get(self.selected_index) {
+            Some(ListItem::AliasEntry { alias, .. }) => {
+                if alias.kind == AliasKind::Function {
+                    self.set_status("Functions are read-only");
+                    return;
+                }
+                let key = AliasKey::of(alias);
+                if let Some(i) = self.marked.iter().position(|k| *k == key) {
+                    self.marked.remove(i);
+                } else {
+                    self.marked.push(key.clone());
+                }
+                self.mark_anchor = Some(key);
+                self.move_selection(1);
+            }
+            Some(ListItem::GroupHeader { group_index, .. }) => {
+                let group_index = *group_index;
+                // A search expands every group to its matches
+                let aliases: Vec<Alias> = if self.search_query.is_empty() {
+                    self.list_groups[group_index].aliases.clone()
+                } else {
+                    self.visible_items
+                        .iter()
+                        .filter_map(|item| match item {
+                            ListItem::AliasEntry { group_index: g, alias, .. } if *g == group_index => {
+                                Some(alias.clone())
+                            }
+                            _ => None,
+                        })
+                        .collect()
+                };
+                let keys: Vec<AliasKey> = aliases
+                    .iter()
+                    .filter(|a| a.kind != AliasKind::Function)
+                    .map(AliasKey::of)
+                    .collect();
+                if keys.iter().all(|k| self.marked.contains(k)) {
+                    self.marked.retain(|k| !keys.contains(k));
+                } else {
+                    for key in keys {
+                        if !self.marked.contains(&key) {
+                            self.marked.push(key);
+                        }
+                    }
+                }
+            }
+            _ => return,
+        }
+        self.rebuild_visible();
+    }
+
+    /// Mark every alias from the last one marked to the selection.
+    fn mark_range(&mut self) {
+        let anchor = self.mark_anchor.as_ref().and_then(|key| {
+            self.visible_items.iter().position(
+                |item| matches!(item, ListItem::AliasEntry { alias, .. } if key.matches(alias)),
+            )
+        });
+        let Some(anchor) = anchor else {
+            self.toggle_mark();
+            return;
+        };
+        let (from, to) = if anchor <= self.selected_index {
+            (anchor, self.selected_index)
+        } else {
+            (self.selected_index, anchor)
+        };
+        for item in &self.visible_items[from..=to] {
+            if let ListItem::AliasEntry { alias, .. } = item {
+                let key = AliasKey::of(alias);
+                if alias.kind != AliasKind::Function && !self.marked.contains(&key) {
+                    self.marked.push(key);
+                }
+            }
+        }
+        if let Some(ListItem::AliasEntry { alias, .. }) = self.visible_items.get(self.selected_index) {
+            self.mark_anchor = Some(AliasKey::of(alias));
+        }
+        self.rebuild_visible();
+    }
+
+    /// The marked aliases in list order, or the selected one when none
+    /// are marked.
+    fn bulk_aliases(&self) -> Vec<Alias> {
+        if self.marked.is_empty() {
+            return self.selected_alias().cloned().into_iter().collect();
+        }
+        self.groups
+            .iter()
+            .flat_map(|g| &g.aliases)
+            .filter(|a| self.marked.iter().any(|k| k.matches(a)))
+            .cloned()
+            .collect()
+    }
+
+    fn start_bulk(&mut self, op: BulkOp) {
+        if !managed::is_initialized(&self.ctx) {
+            self.set_status("Not initialized — run `zam --init` first");
+            return;
+        }
+        // Aliases already in the state asked for are left out quietly
+        let (aliases, skipped): (Vec<Alias>, Vec<Alias>) = self
+            .bulk_aliases()
+            .into_iter()
+            .filter(|a| match op {
+                BulkOp::Disable => a.state == AliasState::Enabled,
+                BulkOp::Enable => a.state == AliasState::Disabled,
+                _ => true,
+            })
+            .partition(|a| match op {
+                BulkOp::Move { .. } => movable(a),
+                _ => a.kind != AliasKind::Function,
+            });
+        if aliases.is_empty() {
+            self.set_status(match op {
+                BulkOp::Move { .. } => "Only enabled aliases in zam's own files can be moved",
+                _ => "Functions are read-only",
+            });
+            return;
+        }
+        self.pending_action = Some(PendingAction::Bulk {
+            op,
+            aliases,
+            skipped: skipped.len(),
         });
         self.mode = InputMode::Confirm;
     }
 
+    /// Move into the custom file or an installed extension, starting with
+    /// the first file the aliases aren't all in already.
+    fn start_move(&mut self) {
+        let mut targets = vec![AliasSource::ZamCustom];
+        targets.extend(
+            extensions::EXTENSIONS
+                .iter()
+                .filter(|ext| managed::is_extension_installed(&self.ctx, ext.name))
+                .map(|ext| AliasSource::ZamExtension(ext.name.to_string())),
+        );
+        let aliases: Vec<Alias> = self.bulk_aliases().into_iter().filter(movable).collect();
+        let target = targets
+            .iter()
+            .position(|t| aliases.iter().any(|a| a.source != *t))
+            .unwrap_or(0);
+        self.start_bulk(BulkOp::Move { targets, target });
+    }
+
+    fn execute_bulk(&mut self, op: BulkOp, aliases: Vec<Alias>) {
+        self.writes += 1;
+        let mut done = 0;
+        let mut failed = Vec::new();
+        let message = match &op {
+            BulkOp::Delete => {
+                for alias in &aliases {
+                    match self.delete_from_files(alias) {
+                        Ok(()) => {
+                            self.remove_alias_from_groups(&alias.name, alias.kind);
+                            done += 1;
+                        }
+                        Err(e) => failed.push(format!("{}: {e}", alias.name)),
+                    }
+                }
+                format!("Deleted {done} of {} aliases", aliases.len())
+            }
+            BulkOp::Disable | BulkOp::Enable => {
+                let (state, verb) = match op {
+                    BulkOp::Disable => (AliasState::Disabled, "Disabled"),
+                    _ => (AliasState::Enabled, "Enabled"),
+                };
+                for alias in &aliases {
+                    match self.set_alias_state(alias, state) {
+                        Ok(()) => done += 1,
+                        Err(e) => failed.push(format!("{}: {e}", alias.name)),
+                    }
+                }
+                format!("{verb} {done} of {} aliases", aliases.len())
+            }
+            BulkOp::Export => match managed::export_aliases(&self.ctx, &aliases) {
+                Ok(path) => format!("Exported {} aliases to {}", aliases.len(), self.ctx.display(&path)),
+                Err(e) => format!("Export failed: {e}"),
+            },
+            BulkOp::Move { targets, target } => {
+                let to = &targets[*target];
+                for alias in &aliases {
+                    if alias.source == *to {
+                        continue;
+                    }
+                    match managed::move_alias(&self.ctx, alias, to) {
+                        Ok(()) => {
+                            let key = AliasKey::of(alias);
+                            for group in &mut self.groups {
+                                group.aliases.retain(|a| !key.matches(a));
+                            }
+                            self.groups.retain(|g| !g.aliases.is_empty());
+                            self.insert_alias(Alias {
+                                source: to.clone(),
+                                ..alias.clone()
+                            });
+                            done += 1;
+                        }
+                        Err(e) => failed.push(format!("{}: {e}", alias.name)),
+                    }
+                }
+                format!("Moved {done} aliases to {}", to.display_name())
+            }
+        };
+
+        self.marked.clear();
+        self.mark_anchor = None;
+        self.rebuild_visible();
+        self.clamp_selection();
+        if failed.is_empty() {
+            self.show_result(&message);
+        } else {
+            self.show_result(&format!("{message}; {}", failed.join("; ")));
+        }
+    }
+
+    /// Remove an alias from the custom file, or unalias it in an override.
+    /// A disabled alias is enabled first, so its kept definition goes too.
+    fn delete_from_files(&self, alias: &Alias) -> Result<(), ManagedError> {
+        if alias.state == AliasState::Disabled {
+            managed::enable_alias(&self.ctx, alias)?;
+        }
+        if matches!(alias.source, AliasSource::ZamCustom | AliasSource::ZamDirs) {
+            managed::delete_custom_alias(&self.ctx, &alias.name, alias.kind)
+        } else {
+            managed::delete_override(&self.ctx, &alias.name, alias.kind, &alias.source)
+        }
+    }
+
     fn edit_input(&mut self, c: char) {
         let Some(state) = &mut self.edit_state else {
             return;
         };
         match state.active_field {
-            EditField::Name => state.name.push(c),
-            EditField::Command => state.command.push(c),
+            EditField::Name => state.name.insert(c),
+            EditField::Command => state.command.insert(c),
+            EditField::Description => state.description.insert(c),
+            EditField::Kind => {
+                let shell = self.ctx.shell;
+                let kind = match c {
+                    'r' => AliasKind::Regular,
+                    'g' => AliasKind::Global,
+                    's' => AliasKind::Suffix,
+                    ' ' => state.kind.next(shell),
+                    _ => return,
+                };
+                if kind.supported_by(shell) {
+                    state.kind = kind;
+                }
+            }
         }
     }
 
@@ -276,12 +1063,10 @@ impl AppState {
             return;
         };
         match state.active_field {
-            EditField::Name => {
-                state.name.pop();
-            }
-            EditField::Command => {
-                state.command.pop();
-            }
+            EditField::Name => state.name.backspace(),
+            EditField::Command => state.command.backspace(),
+            EditField::Description => state.description.backspace(),
+            EditField::Kind => state.kind = AliasKind::Regular,
         }
     }
 
@@ -292,17 +1077,70 @@ impl AppState {
         match state.active_field {
             EditField::Name => state.name.clear(),
             EditField::Command => state.command.clear(),
+            EditField::Description => state.description.clear(),
+            EditField::Kind => state.kind = AliasKind::Regular,
+        }
+    }
+
+    /// Apply a cursor motion or deletion to the active text field.
+    fn edit_line(&mut self, op: fn(&mut LineEditor)) {
+        if let Some(line) = self.edit_state.as_mut().and_then(EditState::active_line) {
+            op(line);
+        }
+    }
+
+    /// Insert bracketed-paste text into the search box or the active
+    /// text field.
+    pub fn paste(&mut self, text: &str) {
+        match self.mode {
+            InputMode::Search => {
+                let line = text.lines().next().unwrap_or("");
+                self.search_query.extend(line.chars().filter(|c| !c.is_control()));
+                self.rebuild_visible();
+                self.selected_index = 0;
+            }
+            InputMode::Editing { .. } => {
+                if let Some(line) = self.edit_state.as_mut().and_then(EditState::active_line) {
+                    line.insert_str(text);
+                }
+            }
+            InputMode::RunArgs => {
+                if let Some(run) = &mut self.run {
+                    run.args.insert_str(text.lines().next().unwrap_or(""));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// The edit form's fields, if they changed since the last call, for
+    /// the caller to run `validate::check` on and hand back to
+    /// `finish_check`. Until then the form shows the previous problems.
+    pub fn next_check(&mut self) -> Option<validate::Input> {
+        let state = self.edit_state.as_mut()?;
+        let input = state.check_input();
+        if state.checked.as_ref() == Some(&input) {
+            return None;
+        }
+        state.checked = Some(input.clone());
+        Some(input)
+    }
+
+    /// Show the problems found for `input`, unless the form has moved on
+    /// to a different alias since.
+    pub fn finish_check(&mut self, input: &validate::Input, problems: Vec<Problem>) {
+        if let Some(state) = &mut self.edit_state {
+            if state.checked.as_ref() == Some(input) {
+                state.problems = problems;
+            }
         }
     }
 
-    fn edit_toggle_field(&mut self) {
+    fn edit_move_field(&mut self, step: fn(EditField) -> EditField) {
         let Some(state) = &mut self.edit_state else {
             return;
         };
-        state.active_field = match state.active_field {
-            EditField::Name => EditField::Command,
-            EditField::Command => EditField::Name,
-        };
+        state.active_field = step(state.active_field);
         self.mode = InputMode::Editing {
             field: state.active_field,
         };
@@ -312,8 +1150,8 @@ impl AppState {
         let Some(state) = self.edit_state.take() else {
             return;
         };
-        let name = state.name.trim().to_string();
-        let command = state.command.trim().to_string();
+        let name = state.name.text().trim().to_string();
+        let command = state.command.text().trim().to_string();
 
         if let Err(e) = managed::validate_alias_name(&name) {
             self.set_status(&e.to_string());
@@ -326,16 +1164,20 @@ impl AppState {
             return;
         }
 
-        // Check for collisions when name is new or changed
+        // Check for collisions when name is new or changed, or when the
+        // alias moves between the regular and suffix tables
         let name_changed = state.original_name.as_ref() != Some(&name);
-        if name_changed {
-            if let Some(c) = collision::check_name_collision(&name, &self.groups) {
+        let table_changed = state
+            .original_kind
+            .is_some_and(|k| !k.same_table(state.kind));
+        if name_changed || table_changed {
+            if let Some(c) = collision::check_name_collision(&self.ctx, &name, state.kind, &self.groups) {
                 self.pending_action = Some(PendingAction::CollisionOverride {
-                    edit_state: EditState {
-                        name,
-                        command,
+                    edit_state: Box::new(EditState {
+                        name: LineEditor::new(&name),
+                        command: LineEditor::new(&command),
                         ..state
-                    },
+                    }),
                     collision_desc: c.description(),
                 });
                 self.mode = InputMode::Confirm;
@@ -344,37 +1186,67 @@ impl AppState {
         }
 
         let state = EditState {
-            name,
-            command,
+            name: LineEditor::new(&name),
+            command: LineEditor::new(&command),
             ..state
         };
         self.do_write(state);
     }
 
     fn do_write(&mut self, state: EditState) {
-        let name = &state.name;
-        let command = &state.command;
+        self.writes += 1;
+        let name = state.name.text();
+        let command = state.command.text();
+        let kind = state.kind;
+        let description = Some(state.description.text().trim()).filter(|d| !d.is_empty());
 
         if state.is_new {
-            if let Err(e) = managed::write_custom_alias(name, command) {
+            if let Err(e) = managed::write_custom_alias(&self.ctx, name, command, kind, description) {
                 self.set_status(&e.to_string());
                 return;
             }
-            self.apply_new_alias(name, command);
+            self.apply_new_alias(name, command, kind, description);
             self.show_result(&format!("Created alias: {name}={command}"));
         } else {
             let source = state.original_source.as_ref().unwrap();
-            if matches!(source, AliasSource::ZamCustom) {
-                if let Err(e) = managed::write_custom_alias(name, command) {
-                    self.set_status(&e.to_string());
-                    return;
-                }
-            } else if let Err(e) = managed::write_override(name, command, source) {
+            let orig_name = state.original_name.as_deref().unwrap();
+            let orig_kind = state.original_kind.unwrap_or_default();
+            let is_custom = matches!(source, AliasSource::ZamCustom | AliasSource::ZamDirs);
+            // Entries in different tables can share a name, so remove the
+            // old one when it's renamed or the kind moves it to another
+            // table; otherwise the write below replaces it in place.
+            let table_changed = !orig_kind.same_table(kind);
+            let mut result = Ok(());
+            if table_changed || orig_name != name {
+                result = if is_custom {
+                    managed::delete_custom_alias(&self.ctx, orig_name, orig_kind)
+                } else {
+                    managed::delete_override(&self.ctx, orig_name, orig_kind, source)
+                };
+            }
+            if result.is_ok() {
+                result = if is_custom {
+                    managed::write_custom_alias(&self.ctx, name, command, kind, description)
+                } else {
+                    managed::write_override(&self.ctx, name, command, kind, source, description)
+                };
+            }
+            if let Err(e) = result {
                 self.set_status(&e.to_string());
                 return;
             }
-            let orig_name = state.original_name.as_deref().unwrap();
-            self.apply_edit_to_groups(orig_name, name, command);
+            if is_custom && table_changed {
+                // Named directories and aliases live in different files
+                self.remove_alias_from_groups(orig_name, orig_kind);
+                self.apply_new_alias(name, command, kind, description);
+            } else {
+                self.apply_edit_to_groups(orig_name, orig_kind, name, command, kind, description);
+            }
+            if orig_name != name {
+                if let Err(e) = managed::rename_tags(&self.ctx, orig_name, name) {
+                    self.set_status(&e.to_string());
+                }
+            }
             self.show_result(&format!("Updated alias: {name}={command}"));
         }
 
@@ -394,31 +1266,24 @@ impl AppState {
         };
 
         match pending {
-            PendingAction::Delete {
-                alias_name,
-                alias_source,
-            } => {
-                let result = if matches!(alias_source, AliasSource::ZamCustom) {
-                    managed::delete_custom_alias(&alias_name)
-                } else {
-                    managed::delete_override(&alias_name, &alias_source)
-                };
-
-                if let Err(e) = result {
+            PendingAction::Delete { alias } => {
+                self.writes += 1;
+                if let Err(e) = self.delete_from_files(&alias) {
                     self.set_status(&e.to_string());
                     self.mode = InputMode::Normal {
                         focus: PanelFocus::List,
                     };
                 } else {
-                    self.remove_alias_from_groups(&alias_name);
-                    self.show_result(&format!("Deleted alias: {alias_name}"));
+                    self.remove_alias_from_groups(&alias.name, alias.kind);
+                    self.show_result(&format!("Deleted alias: {}", alias.name));
                 }
 
                 self.rebuild_visible();
             }
             PendingAction::CollisionOverride { edit_state, .. } => {
-                self.do_write(edit_state);
+                self.do_write(*edit_state);
             }
+            PendingAction::Bulk { op, aliases, .. } => self.execute_bulk(op, aliases),
         }
     }
 
@@ -427,7 +1292,7 @@ impl AppState {
         match pending {
             Some(PendingAction::CollisionOverride { edit_state, .. }) => {
                 let field = edit_state.active_field;
-                self.edit_state = Some(edit_state);
+                self.edit_state = Some(*edit_state);
                 self.mode = InputMode::Editing { field };
             }
             _ => {
@@ -440,49 +1305,84 @@ impl AppState {
 
     // ── In-memory group mutations ─────────────────────────────────────
 
-    fn apply_new_alias(&mut self, name: &str, command: &str) {
-        let alias = Alias {
+    fn apply_new_alias(&mut self, name: &str, command: &str, kind: AliasKind, description: Option<&str>) {
+        let mut alias = Alias {
             name: name.to_string(),
             command: command.to_string(),
-            source: AliasSource::ZamCustom,
-            description: None,
+            kind,
+            source: managed::custom_source(kind),
+            description: description.map(str::to_string),
+            state: AliasState::Enabled,
+            tags: Vec::new(),
+            missing: false,
         };
+        alias.check_target();
+        self.insert_alias(alias);
+    }
 
-        // Find existing ZamCustom group or create one
-        if let Some(group) = self.groups.iter_mut().find(|g| g.source == AliasSource::ZamCustom) {
+    /// Add an alias to its source's group, creating the group if needed.
+    fn insert_alias(&mut self, alias: Alias) {
+        let source = alias.source.clone();
+        if let Some(group) = self.groups.iter_mut().find(|g| g.source == source) {
             group.aliases.push(alias);
             group.aliases.sort_by(|a, b| a.name.cmp(&b.name));
         } else {
             self.groups.push(AliasGroup {
-                source: AliasSource::ZamCustom,
+                source,
                 aliases: vec![alias],
-                collapsed: false,
             });
-            self.groups
-                .sort_by(|a, b| a.source.sort_key().cmp(&b.source.sort_key()));
+            self.groups.sort_by_key(AliasGroup::sort_key);
+        }
+    }
+
+    /// Swap in a freshly collected alias set, keeping the selected alias
+    /// where it still exists.
+    pub fn replace_groups(&mut self, groups: Vec<AliasGroup>) {
+        let selected = self.selected_alias().map(|a| a.name.clone());
+        self.groups = groups;
+        self.rebuild_visible();
+
+        if let Some(name) = selected {
+            if let Some(i) = self.visible_items.iter().position(
+                |item| matches!(item, ListItem::AliasEntry { alias, .. } if alias.name == name),
+            ) {
+                self.selected_index = i;
+            }
         }
+        self.clamp_selection();
     }
 
-    fn apply_edit_to_groups(&mut self, old_name: &str, new_name: &str, new_command: &str) {
+    fn apply_edit_to_groups(
+        &mut self,
+        old_name: &str,
+        old_kind: AliasKind,
+        new_name: &str,
+        new_command: &str,
+        new_kind: AliasKind,
+        new_description: Option<&str>,
+    ) {
         for group in &mut self.groups {
             for alias in &mut group.aliases {
-                if alias.name == old_name {
+                if alias.name == old_name && alias.kind == old_kind {
                     alias.name = new_name.to_string();
                     alias.command = new_command.to_string();
+                    alias.kind = new_kind;
+                    alias.description = new_description.map(str::to_string);
+                    alias.check_target();
                     return;
                 }
             }
         }
     }
 
-    fn remove_alias_from_groups(&mut self, name: &str) {
+    fn remove_alias_from_groups(&mut self, name: &str, kind: AliasKind) {
         for group in &mut self.groups {
-            group.aliases.retain(|a| a.name != name);
+            group.aliases.retain(|a| a.name != name || a.kind != kind);
         }
         self.groups.retain(|g| !g.aliases.is_empty());
     }
 
-    fn set_status(&mut self, msg: &str) {
+    pub fn set_status(&mut self, msg: &str) {
         self.status_message = Some(msg.to_string());
         self.status_tick = 30; // ~3 seconds at 100ms poll
     }
@@ -508,26 +1408,230 @@ impl AppState {
         match self.visible_items.get(self.selected_index)? {
             ListItem::AliasEntry { alias, .. } => Some(alias),
             ListItem::GroupHeader { group_index, .. } => {
-                self.groups.get(*group_index)?.aliases.first()
+                self.list_groups.get(*group_index)?.aliases.first()
+            }
+            ListItem::SectionHeader { .. } => None,
+        }
+    }
+
+    /// Handle the mouse, given where the last frame put each panel. While
+    /// browsing, clicks focus the list or detail panel, select an alias
+    /// (or toggle the group whose header was clicked) and open the search
+    /// bar, and the wheel scrolls either panel, the help screen or the run
+    /// output. A help bar hint runs its action in any mode.
+    pub fn mouse(&mut self, mouse: Mouse, layout: &AppLayout) {
+        let at = Position::new(mouse.column, mouse.row);
+        if mouse.kind == MouseKind::Click && layout.help_bar.contains(at) {
+            if let Some(action) = help_bar::hint_at(layout.help_bar, self.mode, &self.keymap, at) {
+                self.handle_action(action);
+            }
+            return;
+        }
+        if matches!(self.mode, InputMode::RunOutput | InputMode::Help) {
+            match mouse.kind {
+                MouseKind::ScrollUp => self.handle_action(Action::ScrollUp),
+                MouseKind::ScrollDown => self.handle_action(Action::ScrollDown),
+                MouseKind::Click => {}
+            }
+            return;
+        }
+        if !matches!(self.mode, InputMode::Normal { .. } | InputMode::Search) {
+            return;
+        }
+
+        if layout.alias_list.contains(at) {
+            match mouse.kind {
+                MouseKind::ScrollUp => self.move_selection(-3),
+                MouseKind::ScrollDown => self.move_selection(3),
+                MouseKind::Click => {
+                    self.mode = InputMode::Normal {
+                        focus: PanelFocus::List,
+                    };
+                    let inner = layout.alias_list.inner(Margin::new(1, 1));
+                    if !inner.contains(at) {
+                        return;
+                    }
+                    let index = self.list_scroll_offset + usize::from(at.y - inner.y);
+                    let Some(item) = self.visible_items.get(index) else {
+                        return;
+                    };
+                    let header = !matches!(item, ListItem::AliasEntry { .. });
+                    self.selected_index = index;
+                    self.detail_scroll = 0;
+                    if header {
+                        self.toggle_selected_group();
+                    }
+                }
+            }
+        } else if layout.detail_panel.contains(at) {
+            match mouse.kind {
+                MouseKind::ScrollUp => self.detail_scroll = self.detail_scroll.saturating_sub(3),
+                MouseKind::ScrollDown => self.detail_scroll = self.detail_scroll.saturating_add(3),
+                MouseKind::Click => {
+                    self.mode = InputMode::Normal {
+                        focus: PanelFocus::Detail,
+                    }
+                }
+            }
+        } else if mouse.kind == MouseKind::Click && layout.search_bar.contains(at) {
+            self.mode = InputMode::Search;
+        }
+    }
+
+    /// What to save for the next run.
+    pub fn session(&self) -> Session {
+        let selected = match self.visible_items.get(self.selected_index) {
+            Some(ListItem::AliasEntry { alias, .. }) => {
+                Some((alias.name.clone(), alias.source.display_name()))
+            }
+            _ => None,
+        };
+        Session {
+            list_mode: self.list_mode,
+            search: self.search_query.clone(),
+            collapsed: self.collapsed.iter().map(GroupKey::name).collect(),
+            selected,
+        }
+    }
+
+    /// Pick up where the last session left off. Groups that are gone are
+    /// dropped; if the selected alias is gone, the selection stays at the
+    /// top.
+    pub fn restore(&mut self, session: Session) {
+        self.collapsed = session
+            .collapsed
+            .iter()
+            .filter_map(|name| self.group_key(name))
+            .collect();
+        self.search_query = session.search;
+        self.set_list_mode(session.list_mode);
+
+        let Some((name, source)) = session.selected else {
+            return;
+        };
+        let find = |same_source: bool| {
+            self.visible_items.iter().position(|item| {
+                matches!(item, ListItem::AliasEntry { alias, .. }
+                    if alias.name == name && (!same_source || alias.source.display_name() == source))
+            })
+        };
+        self.selected_index = find(true).or_else(|| find(false)).unwrap_or(0);
+    }
+
+    /// The group a session file names, if it still exists.
+    fn group_key(&self, name: &str) -> Option<GroupKey> {
+        if name == "untagged" {
+            return Some(GroupKey::Untagged);
+        }
+        if let Some(tag) = name.strip_prefix("tag ") {
+            let tagged = self.groups.iter().flat_map(|g| &g.aliases).any(|a| a.tags.iter().any(|t| t == tag));
+            return tagged.then(|| GroupKey::Tag(tag.to_string()));
+        }
+        let source = name.strip_prefix("source ")?;
+        self.groups
+            .iter()
+            .find(|g| g.source.display_name() == source)
+            .map(|g| GroupKey::Source(g.source.clone()))
+    }
+
+    pub fn set_list_mode(&mut self, mode: ListMode) {
+        if mode == ListMode::ByUsage && self.usage.is_none() {
+            self.usage = Some(history::command_counts(&self.ctx));
+        }
+        self.list_mode = mode;
+        self.rebuild_visible();
+    }
+
+    /// How many times `alias` was run, once history has been read.
+    pub fn uses(&self, alias: &Alias) -> Option<usize> {
+        let usage = self.usage.as_ref()?;
+        Some(usage.get(&alias.name).copied().unwrap_or(0))
+    }
+
+    /// The groups `list_mode` shows: each source's, each tag's (an alias
+    /// with several tags is in each), or one per section.
+    fn build_list_groups(&self) -> Vec<ListGroup> {
+        if self.list_mode == ListMode::BySource {
+            return self
+                .groups
+                .iter()
+                .map(|g| ListGroup {
+                    key: GroupKey::Source(g.source.clone()),
+                    section: g.section(),
+                    title: g.source.display_name(),
+                    aliases: g.aliases.clone(),
+                })
+                .collect();
+        }
+
+        let mut groups: Vec<ListGroup> = Vec::new();
+        for alias in self.groups.iter().flat_map(|g| &g.aliases) {
+            let section = alias.kind.section();
+            let keys = match self.list_mode {
+                ListMode::ByTag if alias.tags.is_empty() => vec![GroupKey::Untagged],
+                ListMode::ByTag => alias.tags.iter().cloned().map(GroupKey::Tag).collect(),
+                _ => vec![GroupKey::All],
+            };
+            for key in keys {
+                match groups.iter_mut().find(|g| g.key == key && g.section == section) {
+                    Some(group) => group.aliases.push(alias.clone()),
+                    None => groups.push(ListGroup {
+                        title: match &key {
+                            GroupKey::Tag(tag) => format!("#{tag}"),
+                            GroupKey::Untagged => "untagged".to_string(),
+                            _ => section.title().to_string(),
+                        },
+                        key,
+                        section,
+                        aliases: vec![alias.clone()],
+                    }),
+                }
+            }
+        }
+        for group in &mut groups {
+            match self.list_mode {
+                ListMode::ByCommand => group
+                    .aliases
+                    .sort_by(|a, b| (&a.command, &a.name).cmp(&(&b.command, &b.name))),
+                ListMode::ByLength => group
+                    .aliases
+                    .sort_by(|a, b| (a.name.chars().count(), &a.name).cmp(&(b.name.chars().count(), &b.name))),
+                ListMode::ByUsage => group.aliases.sort_by(|a, b| {
+                    self.uses(b).cmp(&self.uses(a)).then_with(|| a.name.cmp(&b.name))
+                }),
+                _ => group.aliases.sort_by(|a, b| a.name.cmp(&b.name)),
             }
         }
+        groups.sort_by(|a, b| {
+            (a.section, a.key == GroupKey::Untagged, &a.title).cmp(&(b.section, b.key == GroupKey::Untagged, &b.title))
+        });
+        groups
     }
 
     pub fn rebuild_visible(&mut self) {
+        self.list_groups = self.build_list_groups();
         self.visible_items.clear();
         let (search_field, query) = if self.search_query.starts_with('=') {
             (SearchField::Command, self.search_query[1..].trim().to_lowercase())
         } else if self.search_query.starts_with('@') {
             (SearchField::Group, self.search_query[1..].trim().to_lowercase())
+        } else if self.search_query.starts_with('#') {
+            (SearchField::Tag, self.search_query[1..].trim().to_lowercase())
         } else {
             (SearchField::Name, self.search_query.trim().to_lowercase())
         };
         let filtering = !query.is_empty();
+        let multiple_sections = self
+            .list_groups
+            .windows(2)
+            .any(|w| w[0].section != w[1].section);
+        let mut current_section = None;
+        let mut section_header = 0;
 
-        for (gi, group) in self.groups.iter().enumerate() {
+        for (gi, group) in self.list_groups.iter().enumerate() {
             if filtering
                 && matches!(search_field, SearchField::Group)
-                && !group.source.display_name().to_lowercase().contains(&query)
+                && !group.title.to_lowercase().contains(&query)
             {
                 continue;
             }
@@ -542,6 +1646,7 @@ impl AppState {
                     match search_field {
                         SearchField::Name => a.name.to_lowercase().contains(&query),
                         SearchField::Command => a.command.to_lowercase().contains(&query),
+                        SearchField::Tag => a.tags.iter().any(|t| t.to_lowercase().contains(&query)),
                         SearchField::Group => true,
                     }
                 })
@@ -551,18 +1656,37 @@ impl AppState {
                 continue;
             }
 
-            self.visible_items.push(ListItem::GroupHeader {
-                group_index: gi,
-                display_name: group.source.display_name(),
-                alias_count: matching.len(),
-                collapsed: group.collapsed,
-            });
+            if multiple_sections && current_section != Some(group.section) {
+                current_section = Some(group.section);
+                section_header = self.visible_items.len();
+                self.visible_items.push(ListItem::SectionHeader {
+                    section: group.section,
+                    alias_count: 0,
+                });
+            }
+            if let Some(ListItem::SectionHeader { alias_count, .. }) =
+                self.visible_items.get_mut(section_header)
+            {
+                *alias_count += matching.len();
+            }
+
+            let headed = group.key != GroupKey::All;
+            let collapsed = headed && self.collapsed.contains(&group.key);
+            if headed {
+                self.visible_items.push(ListItem::GroupHeader {
+                    group_index: gi,
+                    display_name: group.title.clone(),
+                    alias_count: matching.len(),
+                    collapsed,
+                });
+            }
 
-            if !group.collapsed || filtering {
+            if !collapsed || filtering {
                 for alias in matching {
                     self.visible_items.push(ListItem::AliasEntry {
                         group_index: gi,
                         alias: alias.clone(),
+                        marked: self.marked.iter().any(|k| k.matches(alias)),
                     });
                 }
             }
@@ -586,10 +1710,23 @@ impl AppState {
         self.groups.iter().map(|g| g.aliases.len()).sum()
     }
 
+    /// Aliases in the list, counting one listed under several tags once.
     pub fn visible_alias_count(&self) -> usize {
+        let mut seen: Vec<AliasKey> = Vec::new();
         self.visible_items
             .iter()
-            .filter(|item| matches!(item, ListItem::AliasEntry { .. }))
+            .filter(|item| match item {
+                ListItem::AliasEntry { alias, .. } if alias.tags.len() > 1 => {
+                    if seen.iter().any(|key| key.matches(alias)) {
+                        false
+                    } else {
+                        seen.push(AliasKey::of(alias));
+                        true
+                    }
+                }
+                ListItem::AliasEntry { .. } => true,
+                _ => false,
+            })
             .count()
     }
 
@@ -598,6 +1735,8 @@ impl AppState {
             "Command"
         } else if self.search_query.starts_with('@') {
             "Group"
+        } else if self.search_query.starts_with('#') {
+            "Tag"
         } else {
             "Search"
         }
@@ -611,6 +1750,7 @@ impl AppState {
         if self.visible_items.is_empty() {
             return;
         }
+        self.detail_scroll = 0;
         let max = self.visible_items.len() - 1;
         if delta < 0 {
             self.selected_index = self.selected_index.saturating_sub(delta.unsigned_abs());
@@ -633,10 +1773,43 @@ impl AppState {
                 ListItem::GroupHeader { group_index, .. }
                 | ListItem::AliasEntry { group_index, .. },
             ) => *group_index,
+            Some(ListItem::SectionHeader { section, .. }) => {
+                self.toggle_section(*section);
+                return;
+            }
             None => return,
         };
-        if let Some(group) = self.groups.get_mut(group_index) {
-            group.collapsed = !group.collapsed;
+        let Some(group) = self.list_groups.get(group_index) else {
+            return;
+        };
+        if group.key == GroupKey::All {
+            return;
+        }
+        if let Some(i) = self.collapsed.iter().position(|k| *k == group.key) {
+            self.collapsed.remove(i);
+        } else {
+            self.collapsed.push(group.key.clone());
+        }
+        self.rebuild_visible();
+    }
+
+    /// Collapse every group in a section, or expand them all if they
+    /// already are.
+    fn toggle_section(&mut self, section: Section) {
+        let keys: Vec<GroupKey> = self
+            .list_groups
+            .iter()
+            .filter(|g| g.section == section && g.key != GroupKey::All)
+            .map(|g| g.key.clone())
+            .collect();
+        if keys.iter().any(|k| !self.collapsed.contains(k)) {
+            for key in keys {
+                if !self.collapsed.contains(&key) {
+                    self.collapsed.push(key);
+                }
+            }
+        } else {
+            self.collapsed.retain(|k| !keys.contains(k));
         }
         self.rebuild_visible();
     }
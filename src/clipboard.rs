@@ -0,0 +1,95 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Copying to the system clipboard from inside the TUI.
+
+use std::env;
+use std::io::{self, Write};
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+/// Put `text` on the clipboard with an OSC 52 escape sequence written to
+/// `tty`, which the terminal handles, so it works over SSH and inside
+/// tmux. When a Wayland or X display is reachable and `wl-copy` or
+/// `xclip` is installed, it is given the text too, for terminals that
+/// ignore OSC 52.
+pub fn copy(tty: &mut impl Write, text: &str) -> io::Result<()> {
+    tty.write_all(osc52(text, env::var_os("TMUX").is_some()).as_bytes())?;
+    tty.flush()?;
+
+    if let Some(mut cmd) = fallback() {
+        let mut child = cmd
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()?;
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(text.as_bytes())?;
+        }
+        // Both fork to serve the selection, so this returns promptly
+        child.wait()?;
+    }
+    Ok(())
+}
+
+/// The OSC 52 sequence setting the clipboard to `text`. Inside tmux it is
+/// sent twice: as is, for `set-clipboard on`, and wrapped in a DCS
+/// passthrough, for `allow-passthrough on`.
+fn osc52(text: &str, tmux: bool) -> String {
+    let seq = format!("\x1b]52;c;{}\x07", base64(text.as_bytes()));
+    if tmux {
+        format!("{seq}\x1bPtmux;{}\x1b\\", seq.replace('\x1b', "\x1b\x1b"))
+    } else {
+        seq
+    }
+}
+
+fn fallback() -> Option<Command> {
+    if env::var_os("WAYLAND_DISPLAY").is_some() && on_path("wl-copy") {
+        Some(Command::new("wl-copy"))
+    } else if env::var_os("DISPLAY").is_some() && on_path("xclip") {
+        let mut cmd = Command::new("xclip");
+        cmd.args(["-selection", "clipboard"]);
+        Some(cmd)
+    } else {
+        None
+    }
+}
+
+fn on_path(program: &str) -> bool {
+    env::var_os("PATH")
+        .is_some_and(|path| env::split_paths(&path).any(|dir| Path::new(&dir).join(program).is_file()))
+}
+
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let n = chunk.iter().enumerate().fold(0u32, |n, (i, &b)| n | u32::from(b) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(ALPHABET[(n >> (18 - 6 * i) & 63) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_osc52() {
+        assert_eq!(base64(b""), "");
+        assert_eq!(base64(b"f"), "Zg==");
+        assert_eq!(base64(b"fo"), "Zm8=");
+        assert_eq!(base64(b"git status"), "Z2l0IHN0YXR1cw==");
+        assert_eq!(osc52("ls", false), "\x1b]52;c;bHM=\x07");
+        assert_eq!(
+            osc52("ls", true),
+            "\x1b]52;c;bHM=\x07\x1bPtmux;\x1b\x1b]52;c;bHM=\x07\x1b\\"
+        );
+    }
+}
@@ -1,8 +1,8 @@
 // SPDX-License-Identifier: Apache-2.0
 
-use std::process::Command;
-
-use crate::alias::types::AliasGroup;
+use crate::alias::types::{AliasGroup, AliasKind};
+use crate::context::Context;
+use crate::managed::shell_quote;
 
 #[derive(Debug, Clone)]
 pub enum Collision {
@@ -24,11 +24,19 @@ impl Collision {
 }
 
 /// Check a single name for collisions against in-memory aliases and the shell.
-pub fn check_name_collision(name: &str, groups: &[AliasGroup]) -> Option<Collision> {
+///
+/// Suffix aliases and named directories live in their own tables and never
+/// appear in command position, so they only collide within their table.
+pub fn check_name_collision(
+    ctx: &Context,
+    name: &str,
+    kind: AliasKind,
+    groups: &[AliasGroup],
+) -> Option<Collision> {
     // Check in-memory aliases first
     for group in groups {
         for alias in &group.aliases {
-            if alias.name == name {
+            if alias.name == name && alias.kind.same_table(kind) {
                 return Some(Collision::Alias {
                     source: group.source.display_name(),
                 });
@@ -36,40 +44,55 @@ pub fn check_name_collision(name: &str, groups: &[AliasGroup]) -> Option<Collisi
         }
     }
 
+    if !kind.same_table(AliasKind::Regular) {
+        return None;
+    }
+
     // Check shell (command, builtin, function)
-    check_shell(name)
+    check_shell(ctx, name)
 }
 
 /// Check a batch of alias names for collisions (for extension install).
 pub fn check_batch_collisions(
+    ctx: &Context,
     aliases: &[(&str, &str, &str)],
     groups: &[AliasGroup],
 ) -> Vec<(String, Collision)> {
     let mut collisions = Vec::new();
     for &(name, _, _) in aliases {
-        if let Some(c) = check_name_collision(name, groups) {
+        if let Some(c) = check_name_collision(ctx, name, AliasKind::Regular, groups) {
             collisions.push((name.to_string(), c));
         }
     }
     collisions
 }
 
-fn check_shell(name: &str) -> Option<Collision> {
-    let output = Command::new("zsh")
+fn check_shell(ctx: &Context, name: &str) -> Option<Collision> {
+    match word_type(ctx, name)?.as_str() {
+        "command" | "file" => Some(Collision::Command),
+        "builtin" => Some(Collision::Builtin),
+        "function" => Some(Collision::Function),
+        _ => None,
+    }
+}
+
+/// What the shell says `word` is: `command` (zsh) or `file` (bash, fish),
+/// `builtin`, `function`, `alias`, `reserved` or `keyword`. None when the
+/// shell doesn't know it.
+pub fn word_type(ctx: &Context, word: &str) -> Option<String> {
+    let output = ctx
+        .shell_command()
         .arg("-c")
-        .arg(format!("whence -w {name}"))
+        .arg(ctx.shell.type_query(&shell_quote(word)))
         .output()
         .ok()?;
 
     let stdout = String::from_utf8_lossy(&output.stdout);
     let line = stdout.trim();
 
-    // Format: "name: type"
-    let word_type = line.rsplit(": ").next()?;
-    match word_type {
-        "command" => Some(Collision::Command),
-        "builtin" => Some(Collision::Builtin),
-        "function" => Some(Collision::Function),
-        _ => None,
+    // zsh prints "name: type", bash just "type"
+    match line.rsplit(": ").next()? {
+        "" | "none" => None,
+        word_type => Some(word_type.to_string()),
     }
 }
@@ -0,0 +1,200 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::keymap::{KeyTable, Keymap};
+use crate::ui::theme::{ColorSupport, Theme};
+
+/// The one section of `~/.config/zam/config` that isn't a key table.
+const THEME_SECTION: &str = "theme";
+
+#[derive(Debug, thiserror::Error)]
+pub enum ConfigError {
+    #[error("{0}")]
+    Read(#[from] io::Error),
+    #[error("line {line}: expected `[section]` or `key = value`, found `{text}`")]
+    Syntax { line: usize, text: String },
+    #[error("line {line}: unknown section [{name}]; expected [normal], [search], [edit], [confirm], [output] or [theme]")]
+    UnknownSection { line: usize, name: String },
+    #[error("line {line}: `{text}` is not in a section; add e.g. [normal] above it")]
+    NoSection { line: usize, text: String },
+    #[error("line {line}: unknown key `{key}`; use e.g. `x`, `ctrl-d`, `alt-b`, `enter`, `shift-tab`, `f2`")]
+    UnknownKey { line: usize, key: String },
+    #[error("line {line}: [{table}] has no action `{name}`; expected one of: {expected}")]
+    UnknownAction {
+        line: usize,
+        table: &'static str,
+        name: String,
+        expected: String,
+    },
+    #[error("line {line}: `{key}` is already set in [{section}] on line {first}")]
+    Duplicate {
+        line: usize,
+        section: String,
+        key: String,
+        first: usize,
+    },
+    #[error("line {line}: [theme] has no setting `{name}`; expected one of: {expected}")]
+    UnknownSetting {
+        line: usize,
+        name: String,
+        expected: String,
+    },
+    #[error("line {line}: unknown preset `{name}`; expected dark, light or ansi16")]
+    UnknownPreset { line: usize, name: String },
+    #[error("line {line}: `{value}` is not a color; use a name like `blue` or `darkgray`, `#rrggbb` or 0-255")]
+    BadColor { line: usize, value: String },
+}
+
+/// One `key = value` line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    pub line: usize,
+    pub key: String,
+    pub value: String,
+}
+
+/// A `[name]` header and the entries under it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Section {
+    pub name: String,
+    pub line: usize,
+    pub entries: Vec<Entry>,
+}
+
+/// Split config text into sections. `#` starts a comment at the start of
+/// a line, or after a space when followed by a space, so `#rrggbb` colours
+/// are values.
+pub fn sections(text: &str) -> Result<Vec<Section>, ConfigError> {
+    let mut sections: Vec<Section> = Vec::new();
+    for (i, raw) in text.lines().enumerate() {
+        let line = i + 1;
+        let text = strip_comment(raw).trim();
+        if text.is_empty() {
+            continue;
+        }
+        if let Some(name) = text.strip_prefix('[').and_then(|t| t.strip_suffix(']')) {
+            sections.push(Section {
+                name: name.trim().to_string(),
+                line,
+                entries: Vec::new(),
+            });
+            continue;
+        }
+        let Some((key, value)) = text.split_once('=') else {
+            return Err(ConfigError::Syntax {
+                line,
+                text: text.to_string(),
+            });
+        };
+        let Some(section) = sections.last_mut() else {
+            return Err(ConfigError::NoSection {
+                line,
+                text: text.to_string(),
+            });
+        };
+        let key = key.trim().to_string();
+        if let Some(first) = section.entries.iter().find(|e| e.key == key) {
+            return Err(ConfigError::Duplicate {
+                line,
+                section: section.name.clone(),
+                key,
+                first: first.line,
+            });
+        }
+        section.entries.push(Entry {
+            line,
+            key,
+            value: value.trim().to_string(),
+        });
+    }
+    Ok(sections)
+}
+
+fn strip_comment(line: &str) -> &str {
+    if line.trim_start().starts_with('#') {
+        return "";
+    }
+    let end = line
+        .match_indices(" #")
+        .map(|(i, _)| i)
+        .find(|&i| matches!(line[i + 2..].chars().next(), None | Some(' ')))
+        .unwrap_or(line.len());
+    &line[..end]
+}
+
+/// Everything read from `~/.config/zam/config`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Config {
+    pub keymap: Keymap,
+    pub theme: Theme,
+}
+
+impl Config {
+    /// The config file at `path`; a missing file gives the defaults.
+    pub fn load(path: &Path, colors: ColorSupport) -> Result<Config, ConfigError> {
+        match fs::read_to_string(path) {
+            Ok(text) => Config::parse(&text, colors),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Config::parse("", colors),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    pub fn parse(text: &str, colors: ColorSupport) -> Result<Config, ConfigError> {
+        let sections = sections(text)?;
+        if let Some(section) = sections.iter().find(|s| {
+            s.name != THEME_SECTION && !KeyTable::ALL.iter().any(|t| t.name() == s.name)
+        }) {
+            return Err(ConfigError::UnknownSection {
+                line: section.line,
+                name: section.name.clone(),
+            });
+        }
+        let theme = sections.iter().filter(|s| s.name == THEME_SECTION).collect::<Vec<_>>();
+        Ok(Config {
+            keymap: Keymap::from_sections(&sections)?,
+            theme: Theme::from_config(&theme, colors)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sections() {
+        let sections = sections("# bindings\n[normal]\nx = quit # leave\n#y = help\n\n[theme]\nsearch-fg = #ffcc00 #\n").unwrap();
+        let entries: Vec<(&str, &str, &str)> = sections
+            .iter()
+            .flat_map(|s| s.entries.iter().map(move |e| (s.name.as_str(), e.key.as_str(), e.value.as_str())))
+            .collect();
+        assert_eq!(
+            entries,
+            vec![("normal", "x", "quit"), ("theme", "search-fg", "#ffcc00")]
+        );
+    }
+
+    #[test]
+    fn test_errors() {
+        let err = |text| Config::parse(text, ColorSupport::TrueColor).unwrap_err().to_string();
+        assert_eq!(err("x = quit"), "line 1: `x = quit` is not in a section; add e.g. [normal] above it");
+        assert_eq!(
+            err("[insert]"),
+            "line 1: unknown section [insert]; expected [normal], [search], [edit], [confirm], [output] or [theme]"
+        );
+        assert_eq!(err("[normal]\nquit"), "line 2: expected `[section]` or `key = value`, found `quit`");
+        assert_eq!(
+            err("[normal]\nx = quit\n\nx = help"),
+            "line 4: `x` is already set in [normal] on line 2"
+        );
+        assert_eq!(
+            err("[theme]\npreset = solarized"),
+            "line 2: unknown preset `solarized`; expected dark, light or ansi16"
+        );
+        assert!(err("[theme]\nsearch-fg = #12345").starts_with("line 2: `#12345` is not a color"));
+        assert!(err("[theme]\nsearch = red").starts_with("line 2: [theme] has no setting `search`"));
+    }
+}
@@ -0,0 +1,77 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::shell::{Shell, ShellPaths};
+
+/// Everything zam needs from its environment: which shell it manages,
+/// the program it runs to ask that shell questions, and where files live.
+/// `main` builds one from the process environment; tests build one around
+/// a fixture home and a scripted fake shell.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Context {
+    pub shell: Shell,
+    /// Executable spawned to collect aliases and check collisions,
+    /// normally the shell's name looked up on `PATH`.
+    pub shell_program: PathBuf,
+    /// zam's managed directory, ~/.config/zam/.
+    pub config_dir: PathBuf,
+    /// Where the alias cache lives, ~/.cache/zam/.
+    pub cache_dir: PathBuf,
+    /// Where the TUI keeps its session between runs, ~/.local/state/zam/.
+    pub state_dir: PathBuf,
+    /// Shell configuration locations. A guess from the environment until
+    /// aliases are collected and the shell reports its own.
+    pub paths: ShellPaths,
+}
+
+impl Context {
+    /// The default layout under `home`, ignoring the process environment.
+    pub fn for_home(shell: Shell, home: PathBuf) -> Self {
+        // Without a home there is nowhere to write; leave the dirs empty
+        let under_home = |dir: &str| {
+            if home.as_os_str().is_empty() {
+                PathBuf::new()
+            } else {
+                home.join(dir)
+            }
+        };
+        Context {
+            shell,
+            shell_program: PathBuf::from(shell.name()),
+            config_dir: under_home(".config/zam"),
+            cache_dir: under_home(".cache/zam"),
+            state_dir: under_home(".local/state/zam"),
+            paths: ShellPaths::defaults(home),
+        }
+    }
+
+    /// The layout for the real user: `$HOME`, plus `ZDOTDIR`, `ZSH` and
+    /// `ZSH_CUSTOM` from zam's own environment.
+    pub fn from_env(shell: Shell) -> Self {
+        let home = dirs::home_dir().unwrap_or_default();
+        let mut ctx = Context::for_home(shell, home.clone());
+        ctx.paths = ShellPaths::from_env(home);
+        ctx
+    }
+
+    /// The home directory, empty if it couldn't be determined.
+    pub fn home(&self) -> &Path {
+        &self.paths.home
+    }
+
+    /// A command running `shell_program` with `HOME` pointing at our home.
+    pub fn shell_command(&self) -> Command {
+        let mut cmd = Command::new(&self.shell_program);
+        if !self.home().as_os_str().is_empty() {
+            cmd.env("HOME", self.home());
+        }
+        cmd
+    }
+
+    /// `path` with the home directory shortened to `~`, for display.
+    pub fn display(&self, path: &Path) -> String {
+        self.paths.display(path)
+    }
+}
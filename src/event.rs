@@ -1,104 +1,104 @@
 // SPDX-License-Identifier: Apache-2.0
 
-use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyModifiers};
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
 use std::time::Duration;
 
 use crate::action::Action;
 use crate::app::InputMode;
+use crate::keymap::{KeyTable, Keymap};
 
-/// Poll for the next action, returning None on timeout or non-key events.
-pub fn next_action(mode: InputMode) -> std::io::Result<Option<Action>> {
+/// What the user did: a mapped key, text pasted with bracketed paste
+/// (kept out of `Action` so that stays `Copy`), or the mouse, which needs
+/// the layout to mean anything.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Input {
+    Action(Action),
+    Paste(String),
+    Mouse(Mouse),
+}
+
+/// A mouse event zam reacts to, and the cell it happened at.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Mouse {
+    pub kind: MouseKind,
+    pub column: u16,
+    pub row: u16,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MouseKind {
+    Click,
+    ScrollUp,
+    ScrollDown,
+}
+
+/// Poll for the next input, returning None on timeout, unmapped keys and
+/// other events.
+pub fn next_input(keymap: &Keymap, mode: InputMode) -> std::io::Result<Option<Input>> {
     if !event::poll(Duration::from_millis(100))? {
         return Ok(None);
     }
 
     match event::read()? {
-        Event::Key(key) => Ok(map_key(key, mode)),
+        Event::Key(key) => Ok(map_key(keymap, key, mode).map(Input::Action)),
+        Event::Paste(text) => Ok(Some(Input::Paste(text))),
+        Event::Mouse(mouse) => Ok(map_mouse(mouse).map(Input::Mouse)),
         _ => Ok(None),
     }
 }
 
-fn map_key(key: KeyEvent, mode: InputMode) -> Option<Action> {
-    if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
-        return Some(Action::Quit);
-    }
-
-    match mode {
-        InputMode::Search => map_search_key(key),
-        InputMode::Normal { .. } => map_normal_key(key),
-        InputMode::Editing { .. } => map_editing_key(key),
-        InputMode::Confirm => map_confirm_key(key),
-        InputMode::ResultPopup => Some(Action::DismissResult),
-        InputMode::Help => map_help_key(key),
-    }
-}
-
-fn map_search_key(key: KeyEvent) -> Option<Action> {
-    match key.code {
-        KeyCode::Esc | KeyCode::Enter => Some(Action::ExitSearch),
-        KeyCode::Backspace => Some(Action::SearchBackspace),
-        KeyCode::Tab => Some(Action::SwitchFocus),
-        KeyCode::Char(c) => {
-            if key.modifiers.contains(KeyModifiers::CONTROL) && c == 'u' {
-                Some(Action::SearchClear)
-            } else {
-                Some(Action::SearchInput(c))
-            }
-        }
-        _ => None,
-    }
+/// Left clicks and the wheel; drags, releases and other buttons are
+/// ignored.
+pub fn map_mouse(event: MouseEvent) -> Option<Mouse> {
+    let kind = match event.kind {
+        MouseEventKind::Down(MouseButton::Left) => MouseKind::Click,
+        MouseEventKind::ScrollUp => MouseKind::ScrollUp,
+        MouseEventKind::ScrollDown => MouseKind::ScrollDown,
+        _ => return None,
+    };
+    Some(Mouse {
+        kind,
+        column: event.column,
+        row: event.row,
+    })
 }
 
-fn map_normal_key(key: KeyEvent) -> Option<Action> {
-    match key.code {
-        KeyCode::Char('q') => Some(Action::Quit),
-        KeyCode::Char('/') => Some(Action::FocusSearch),
-        KeyCode::Char('j') | KeyCode::Down => Some(Action::ScrollDown),
-        KeyCode::Char('k') | KeyCode::Up => Some(Action::ScrollUp),
-        KeyCode::Enter | KeyCode::Char(' ') => Some(Action::ToggleCollapse),
-        KeyCode::Tab => Some(Action::SwitchFocus),
-        KeyCode::Char('g') => Some(Action::GoToTop),
-        KeyCode::Char('G') => Some(Action::GoToBottom),
-        KeyCode::Char('d') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-            Some(Action::PageDown)
-        }
-        KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-            Some(Action::PageUp)
-        }
-        KeyCode::Char('a') => Some(Action::CreateAlias),
-        KeyCode::Char('e') => Some(Action::EditAlias),
-        KeyCode::Char('d') => Some(Action::DeleteAlias),
-        KeyCode::Char('?') => Some(Action::ShowHelp),
-        _ => None,
+/// The action `key` triggers in `mode`, if any.
+pub fn map_key(keymap: &Keymap, key: KeyEvent, mode: InputMode) -> Option<Action> {
+    if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+        return Some(Action::Quit);
     }
-}
 
-fn map_editing_key(key: KeyEvent) -> Option<Action> {
-    match key.code {
-        KeyCode::Esc => Some(Action::CancelEdit),
-        KeyCode::Enter => Some(Action::SaveEdit),
-        KeyCode::Tab => Some(Action::EditNextField),
-        KeyCode::BackTab => Some(Action::EditPrevField),
-        KeyCode::Backspace => Some(Action::EditBackspace),
-        KeyCode::Char(c) => {
-            if key.modifiers.contains(KeyModifiers::CONTROL) && c == 'u' {
-                Some(Action::EditClear)
-            } else {
-                Some(Action::EditInput(c))
-            }
-        }
-        _ => None,
+    let Some(table) = KeyTable::for_mode(mode) else {
+        // The result popup closes on any key, and so does the help screen
+        // apart from the keys that scroll it
+        return Some(match mode {
+            InputMode::Help => match keymap.lookup(KeyTable::Normal, key) {
+                Some(
+                    action @ (Action::ScrollDown
+                    | Action::ScrollUp
+                    | Action::PageDown
+                    | Action::PageUp
+                    | Action::GoToTop
+                    | Action::GoToBottom),
+                ) => action,
+                _ => Action::CloseHelp,
+            },
+            _ => Action::DismissResult,
+        });
+    };
+    if let Some(action) = keymap.lookup(table, key) {
+        return Some(action);
     }
-}
 
-fn map_confirm_key(key: KeyEvent) -> Option<Action> {
-    match key.code {
-        KeyCode::Char('y') | KeyCode::Char('Y') => Some(Action::ConfirmYes),
-        KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc => Some(Action::ConfirmNo),
-        _ => None,
+    // Unbound characters are text in the search box and edit form
+    let typed = match key.code {
+        KeyCode::Char(c) if !key.modifiers.intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) => c,
+        _ => return None,
+    };
+    match table {
+        KeyTable::Search => Some(Action::SearchInput(typed)),
+        KeyTable::Edit => Some(Action::EditInput(typed)),
+        KeyTable::Normal | KeyTable::Confirm | KeyTable::Output => None,
     }
 }
-
-fn map_help_key(_key: KeyEvent) -> Option<Action> {
-    Some(Action::CloseHelp)
-}
@@ -0,0 +1,73 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! How often each command was run, from the shell's history file.
+
+use std::collections::HashMap;
+use std::fs;
+
+use crate::context::Context;
+use crate::shell::Shell;
+
+/// How many times each word starts a command in `ctx`'s history file,
+/// counting every command of a pipeline or list. Empty if there is no
+/// history.
+pub fn command_counts(ctx: &Context) -> HashMap<String, usize> {
+    match fs::read(ctx.paths.history_file(ctx.shell)) {
+        // zsh metafies non-ASCII bytes, so the file needn't be UTF-8
+        Ok(bytes) => count_commands(ctx.shell, &String::from_utf8_lossy(&bytes)),
+        Err(_) => HashMap::new(),
+    }
+}
+
+fn count_commands(shell: Shell, history: &str) -> HashMap<String, usize> {
+    let mut counts = HashMap::new();
+    for line in history.lines() {
+        let command = match shell {
+            // `: <start>:<elapsed>;command` with EXTENDED_HISTORY
+            Shell::Zsh => match line.strip_prefix(": ").and_then(|l| l.split_once(';')) {
+                Some((_, command)) => command,
+                None => line,
+            },
+            // `#<start>` lines precede commands when HISTTIMEFORMAT is set
+            Shell::Bash if line.starts_with('#') => continue,
+            Shell::Bash => line,
+            Shell::Fish => match line.strip_prefix("- cmd: ") {
+                Some(command) => command,
+                None => continue,
+            },
+        };
+        let words = command
+            .split(';')
+            .flat_map(|part| part.split("&&"))
+            .flat_map(|part| part.split('|'))
+            .filter_map(|part| part.split_whitespace().next());
+        for word in words {
+            *counts.entry(word.to_string()).or_insert(0) += 1;
+        }
+    }
+    counts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_count_commands() {
+        let zsh = ": 1700000000:0;gst\n: 1700000001:0;gco main && gst | less\nll 2>&1\n";
+        let counts = count_commands(Shell::Zsh, zsh);
+        assert_eq!(counts["gst"], 2);
+        assert_eq!(counts["gco"], 1);
+        assert_eq!(counts["less"], 1);
+        assert_eq!(counts["ll"], 1);
+        assert!(!counts.contains_key("1"));
+
+        let fish = "- cmd: gst\n  when: 1700000000\n- cmd: gst; ll\n";
+        let counts = count_commands(Shell::Fish, fish);
+        assert_eq!(counts["gst"], 2);
+        assert!(!counts.contains_key("when:"));
+
+        let counts = count_commands(Shell::Bash, "#1700000000\ngst\n");
+        assert_eq!(counts.len(), 1);
+    }
+}
@@ -0,0 +1,546 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use std::fmt;
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+use crate::action::Action;
+use crate::app::InputMode;
+use crate::config::{self, ConfigError, Section};
+
+/// A key plus modifiers, written `q`, `G`, `ctrl-d`, `alt-b`, `enter`,
+/// `shift-tab` in the config file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Chord {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+impl Chord {
+    const fn new(code: KeyCode, modifiers: KeyModifiers) -> Self {
+        Chord { code, modifiers }
+    }
+
+    const fn key(code: KeyCode) -> Self {
+        Chord::new(code, KeyModifiers::NONE)
+    }
+
+    const fn char(c: char) -> Self {
+        Chord::key(KeyCode::Char(c))
+    }
+
+    const fn ctrl(c: char) -> Self {
+        Chord::new(KeyCode::Char(c), KeyModifiers::CONTROL)
+    }
+
+    /// The chord a key event matches. Shift is already part of a
+    /// character (`G`), and terminals disagree on whether they report it.
+    pub fn from_event(key: KeyEvent) -> Self {
+        let mut modifiers = key.modifiers & (KeyModifiers::CONTROL | KeyModifiers::ALT | KeyModifiers::SHIFT);
+        if matches!(key.code, KeyCode::Char(_) | KeyCode::BackTab) {
+            modifiers.remove(KeyModifiers::SHIFT);
+        }
+        Chord::new(key.code, modifiers)
+    }
+
+    /// Parse the config spelling of a chord.
+    pub fn parse(text: &str) -> Option<Chord> {
+        let mut modifiers = KeyModifiers::NONE;
+        let mut rest = text;
+        loop {
+            let lower = rest.to_ascii_lowercase();
+            let (modifier, len) = if lower.starts_with("ctrl-") {
+                (KeyModifiers::CONTROL, 5)
+            } else if lower.starts_with("alt-") {
+                (KeyModifiers::ALT, 4)
+            } else if lower.starts_with("shift-") {
+                (KeyModifiers::SHIFT, 6)
+            } else {
+                break;
+            };
+            modifiers |= modifier;
+            rest = &rest[len..];
+        }
+        let mut chars = rest.chars();
+        let code = match (chars.next(), chars.next()) {
+            (Some(c), None) => KeyCode::Char(c),
+            _ => match rest.to_ascii_lowercase().as_str() {
+                "space" => KeyCode::Char(' '),
+                "enter" | "return" => KeyCode::Enter,
+                "esc" | "escape" => KeyCode::Esc,
+                "tab" if modifiers.contains(KeyModifiers::SHIFT) => {
+                    modifiers.remove(KeyModifiers::SHIFT);
+                    KeyCode::BackTab
+                }
+                "tab" => KeyCode::Tab,
+                "backtab" => KeyCode::BackTab,
+                "backspace" => KeyCode::Backspace,
+                "delete" | "del" => KeyCode::Delete,
+                "up" => KeyCode::Up,
+                "down" => KeyCode::Down,
+                "left" => KeyCode::Left,
+                "right" => KeyCode::Right,
+                "home" => KeyCode::Home,
+                "end" => KeyCode::End,
+                "pageup" => KeyCode::PageUp,
+                "pagedown" => KeyCode::PageDown,
+                name => KeyCode::F(name.strip_prefix('f')?.parse().ok().filter(|n| (1..=12).contains(n))?),
+            },
+        };
+        if let KeyCode::Char(c) = code {
+            // `shift-g` means `G`
+            if modifiers.contains(KeyModifiers::SHIFT) && c.is_ascii_alphabetic() {
+                modifiers.remove(KeyModifiers::SHIFT);
+                return Some(Chord::new(KeyCode::Char(c.to_ascii_uppercase()), modifiers));
+            }
+            modifiers.remove(KeyModifiers::SHIFT);
+        }
+        Some(Chord::new(code, modifiers))
+    }
+}
+
+/// Short form for hints: `j`, `^d`, `M-b`, `Enter`, `S-Tab`.
+impl fmt::Display for Chord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            f.write_str("^")?;
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            f.write_str("M-")?;
+        }
+        if self.modifiers.contains(KeyModifiers::SHIFT) {
+            f.write_str("S-")?;
+        }
+        match self.code {
+            KeyCode::Char(' ') => f.write_str("Space"),
+            KeyCode::Char(c) => write!(f, "{c}"),
+            KeyCode::Enter => f.write_str("Enter"),
+            KeyCode::Esc => f.write_str("Esc"),
+            KeyCode::Tab => f.write_str("Tab"),
+            KeyCode::BackTab => f.write_str("S-Tab"),
+            KeyCode::Backspace => f.write_str("Bksp"),
+            KeyCode::Delete => f.write_str("Del"),
+            KeyCode::Up => f.write_str("Up"),
+            KeyCode::Down => f.write_str("Down"),
+            KeyCode::Left => f.write_str("Left"),
+            KeyCode::Right => f.write_str("Right"),
+            KeyCode::Home => f.write_str("Home"),
+            KeyCode::End => f.write_str("End"),
+            KeyCode::PageUp => f.write_str("PgUp"),
+            KeyCode::PageDown => f.write_str("PgDn"),
+            KeyCode::F(n) => write!(f, "F{n}"),
+            _ => f.write_str("?"),
+        }
+    }
+}
+
+/// The input modes with their own bindings. The result popup and help
+/// screen close on any key, and Ctrl+c always quits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyTable {
+    Normal,
+    Search,
+    Edit,
+    Confirm,
+    Output,
+}
+
+impl KeyTable {
+    pub const ALL: [KeyTable; 5] =
+        [KeyTable::Normal, KeyTable::Search, KeyTable::Edit, KeyTable::Confirm, KeyTable::Output];
+
+    pub fn for_mode(mode: InputMode) -> Option<KeyTable> {
+        match mode {
+            InputMode::Normal { .. } => Some(KeyTable::Normal),
+            InputMode::Search => Some(KeyTable::Search),
+            InputMode::Editing { .. } | InputMode::RunArgs => Some(KeyTable::Edit),
+            InputMode::Confirm => Some(KeyTable::Confirm),
+            InputMode::RunOutput => Some(KeyTable::Output),
+            InputMode::ResultPopup | InputMode::Help => None,
+        }
+    }
+
+    /// Section name in the config file.
+    pub fn name(self) -> &'static str {
+        match self {
+            KeyTable::Normal => "normal",
+            KeyTable::Search => "search",
+            KeyTable::Edit => "edit",
+            KeyTable::Confirm => "confirm",
+            KeyTable::Output => "output",
+        }
+    }
+
+    /// Actions that can be bound in this table, by config name.
+    fn actions(self) -> &'static [(&'static str, Action)] {
+        match self {
+            KeyTable::Normal => &[
+                ("quit", Action::Quit),
+                ("up", Action::ScrollUp),
+                ("down", Action::ScrollDown),
+                ("page-up", Action::PageUp),
+                ("page-down", Action::PageDown),
+                ("top", Action::GoToTop),
+                ("bottom", Action::GoToBottom),
+                ("toggle", Action::ToggleCollapse),
+                ("list-mode", Action::CycleListMode),
+                ("search", Action::FocusSearch),
+                ("focus", Action::SwitchFocus),
+                ("add", Action::CreateAlias),
+                ("edit", Action::EditAlias),
+                ("delete", Action::DeleteAlias),
+                ("disable", Action::ToggleDisabled),
+                ("copy-name", Action::CopyName),
+                ("copy-command", Action::CopyCommand),
+                ("copy-line", Action::CopyLine),
+                ("pick", Action::PickAlias),
+                ("run", Action::RunAlias),
+                ("mark", Action::ToggleMark),
+                ("mark-range", Action::MarkRange),
+                ("unmark", Action::ClearMarks),
+                ("export", Action::ExportAliases),
+                ("move", Action::MoveAliases),
+                ("help", Action::ShowHelp),
+            ],
+            KeyTable::Search => &[
+                ("exit", Action::ExitSearch),
+                ("backspace", Action::SearchBackspace),
+                ("clear", Action::SearchClear),
+                ("focus", Action::SwitchFocus),
+            ],
+            KeyTable::Edit => &[
+                ("cancel", Action::CancelEdit),
+                ("save", Action::SaveEdit),
+                ("next-field", Action::EditNextField),
+                ("prev-field", Action::EditPrevField),
+                ("backspace", Action::EditBackspace),
+                ("clear", Action::EditClear),
+                ("delete", Action::EditDelete),
+                ("delete-word", Action::EditDeleteWord),
+                ("left", Action::EditLeft),
+                ("right", Action::EditRight),
+                ("home", Action::EditHome),
+                ("end", Action::EditEnd),
+                ("word-left", Action::EditWordLeft),
+                ("word-right", Action::EditWordRight),
+            ],
+            KeyTable::Confirm => &[
+                ("yes", Action::ConfirmYes),
+                ("no", Action::ConfirmNo),
+                ("next", Action::ConfirmNext),
+            ],
+            KeyTable::Output => &[
+                ("close", Action::CloseRun),
+                ("up", Action::ScrollUp),
+                ("down", Action::ScrollDown),
+                ("page-up", Action::PageUp),
+                ("page-down", Action::PageDown),
+                ("top", Action::GoToTop),
+                ("bottom", Action::GoToBottom),
+            ],
+        }
+    }
+
+    fn defaults(self) -> Vec<(Chord, Action)> {
+        match self {
+            KeyTable::Normal => vec![
+                (Chord::char('q'), Action::Quit),
+                (Chord::char('/'), Action::FocusSearch),
+                (Chord::char('j'), Action::ScrollDown),
+                (Chord::key(KeyCode::Down), Action::ScrollDown),
+                (Chord::char('k'), Action::ScrollUp),
+                (Chord::key(KeyCode::Up), Action::ScrollUp),
+                (Chord::key(KeyCode::Enter), Action::ToggleCollapse),
+                (Chord::char('o'), Action::CycleListMode),
+                (Chord::char(' '), Action::ToggleMark),
+                (Chord::char('V'), Action::MarkRange),
+                (Chord::key(KeyCode::Esc), Action::ClearMarks),
+                (Chord::char('x'), Action::ExportAliases),
+                (Chord::char('m'), Action::MoveAliases),
+                (Chord::key(KeyCode::Tab), Action::SwitchFocus),
+                (Chord::char('g'), Action::GoToTop),
+                (Chord::char('G'), Action::GoToBottom),
+                (Chord::ctrl('d'), Action::PageDown),
+                (Chord::ctrl('u'), Action::PageUp),
+                (Chord::char('a'), Action::CreateAlias),
+                (Chord::char('e'), Action::EditAlias),
+                (Chord::char('d'), Action::DeleteAlias),
+                (Chord::char('D'), Action::ToggleDisabled),
+                (Chord::char('c'), Action::CopyName),
+                (Chord::char('y'), Action::CopyCommand),
+                (Chord::char('Y'), Action::CopyLine),
+                (Chord::char('p'), Action::PickAlias),
+                (Chord::char('r'), Action::RunAlias),
+                (Chord::char('?'), Action::ShowHelp),
+            ],
+            KeyTable::Search => vec![
+                (Chord::key(KeyCode::Esc), Action::ExitSearch),
+                (Chord::key(KeyCode::Enter), Action::ExitSearch),
+                (Chord::key(KeyCode::Backspace), Action::SearchBackspace),
+                (Chord::key(KeyCode::Tab), Action::SwitchFocus),
+                (Chord::ctrl('u'), Action::SearchClear),
+            ],
+            KeyTable::Edit => vec![
+                (Chord::key(KeyCode::Esc), Action::CancelEdit),
+                (Chord::key(KeyCode::Enter), Action::SaveEdit),
+                (Chord::key(KeyCode::Tab), Action::EditNextField),
+                (Chord::key(KeyCode::BackTab), Action::EditPrevField),
+                (Chord::key(KeyCode::Backspace), Action::EditBackspace),
+                (Chord::ctrl('u'), Action::EditClear),
+                (Chord::key(KeyCode::Delete), Action::EditDelete),
+                (Chord::ctrl('w'), Action::EditDeleteWord),
+                (Chord::new(KeyCode::Backspace, KeyModifiers::ALT), Action::EditDeleteWord),
+                (Chord::key(KeyCode::Left), Action::EditLeft),
+                (Chord::key(KeyCode::Right), Action::EditRight),
+                (Chord::key(KeyCode::Home), Action::EditHome),
+                (Chord::ctrl('a'), Action::EditHome),
+                (Chord::key(KeyCode::End), Action::EditEnd),
+                (Chord::ctrl('e'), Action::EditEnd),
+                (Chord::new(KeyCode::Char('b'), KeyModifiers::ALT), Action::EditWordLeft),
+                (Chord::new(KeyCode::Left, KeyModifiers::CONTROL), Action::EditWordLeft),
+                (Chord::new(KeyCode::Char('f'), KeyModifiers::ALT), Action::EditWordRight),
+                (Chord::new(KeyCode::Right, KeyModifiers::CONTROL), Action::EditWordRight),
+            ],
+            KeyTable::Confirm => vec![
+                (Chord::char('y'), Action::ConfirmYes),
+                (Chord::char('Y'), Action::ConfirmYes),
+                (Chord::char('n'), Action::ConfirmNo),
+                (Chord::char('N'), Action::ConfirmNo),
+                (Chord::key(KeyCode::Esc), Action::ConfirmNo),
+                (Chord::key(KeyCode::Tab), Action::ConfirmNext),
+            ],
+            KeyTable::Output => vec![
+                (Chord::key(KeyCode::Esc), Action::CloseRun),
+                (Chord::char('q'), Action::CloseRun),
+                (Chord::key(KeyCode::Enter), Action::CloseRun),
+                (Chord::char('j'), Action::ScrollDown),
+                (Chord::key(KeyCode::Down), Action::ScrollDown),
+                (Chord::char('k'), Action::ScrollUp),
+                (Chord::key(KeyCode::Up), Action::ScrollUp),
+                (Chord::char('g'), Action::GoToTop),
+                (Chord::char('G'), Action::GoToBottom),
+                (Chord::ctrl('d'), Action::PageDown),
+                (Chord::ctrl('u'), Action::PageUp),
+            ],
+        }
+    }
+
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+/// Which chord triggers which action, per `KeyTable`. Starts from the
+/// built-in bindings; the config file rebinds or unbinds (`= none`)
+/// individual chords.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Keymap {
+    tables: [Vec<(Chord, Action)>; 5],
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Keymap {
+            tables: KeyTable::ALL.map(KeyTable::defaults),
+        }
+    }
+}
+
+impl Keymap {
+    /// Apply config text to the built-in bindings:
+    ///
+    /// ```text
+    /// [normal]
+    /// d = none
+    /// shift-d = delete
+    /// ```
+    pub fn parse(text: &str) -> Result<Keymap, ConfigError> {
+        Keymap::from_sections(&config::sections(text)?)
+    }
+
+    /// Apply the key table sections of a config file; others are skipped.
+    pub fn from_sections(sections: &[Section]) -> Result<Keymap, ConfigError> {
+        let mut keymap = Keymap::default();
+        // (table, chord, line) of every binding so far, to catch `G` and
+        // `shift-g` both being set
+        let mut seen: Vec<(KeyTable, Chord, usize)> = Vec::new();
+        // Bindings from the file go first so hints show the user's choice
+        let mut added = [0usize; 5];
+
+        for section in sections {
+            let Some(table) = KeyTable::ALL.into_iter().find(|t| t.name() == section.name) else {
+                continue;
+            };
+            for entry in &section.entries {
+                let (line, key, action) = (entry.line, entry.key.as_str(), entry.value.as_str());
+                let chord = Chord::parse(key).ok_or_else(|| ConfigError::UnknownKey {
+                    line,
+                    key: key.to_string(),
+                })?;
+                if let Some(&(_, _, first)) = seen.iter().find(|(t, c, _)| *t == table && *c == chord) {
+                    return Err(ConfigError::Duplicate {
+                        line,
+                        section: table.name().to_string(),
+                        key: key.to_string(),
+                        first,
+                    });
+                }
+                seen.push((table, chord, line));
+
+                let bindings = &mut keymap.tables[table.index()];
+                bindings.retain(|(c, _)| *c != chord);
+                if action == "none" {
+                    continue;
+                }
+                let action = table
+                    .actions()
+                    .iter()
+                    .find(|(name, _)| *name == action)
+                    .map(|&(_, a)| a)
+                    .ok_or_else(|| ConfigError::UnknownAction {
+                        line,
+                        table: table.name(),
+                        name: action.to_string(),
+                        expected: table
+                            .actions()
+                            .iter()
+                            .map(|(name, _)| *name)
+                            .chain(["none"])
+                            .collect::<Vec<_>>()
+                            .join(", "),
+                    })?;
+                let slot = &mut added[table.index()];
+                bindings.insert(*slot, (chord, action));
+                *slot += 1;
+            }
+        }
+        Ok(keymap)
+    }
+
+    /// The action bound to `key` in `table`.
+    pub fn lookup(&self, table: KeyTable, key: KeyEvent) -> Option<Action> {
+        let chord = Chord::from_event(key);
+        self.tables[table.index()]
+            .iter()
+            .find(|(c, _)| *c == chord)
+            .map(|&(_, a)| a)
+    }
+
+    /// Every chord bound to `action` in `table`, most preferred first.
+    pub fn chords(&self, table: KeyTable, action: Action) -> impl Iterator<Item = Chord> + '_ {
+        self.tables[table.index()]
+            .iter()
+            .filter(move |(_, a)| *a == action)
+            .map(|&(c, _)| c)
+    }
+
+    /// Hint text for `actions`, or `None` if none are bound. One action
+    /// lists all its chords (`n/Esc`), leaving out the capital of a letter
+    /// already shown; several list the first chord of each (`j/k`).
+    pub fn hint(&self, table: KeyTable, actions: &[Action]) -> Option<String> {
+        let chords: Vec<Chord> = match actions {
+            [action] => {
+                let all: Vec<Chord> = self.chords(table, *action).collect();
+                all.iter()
+                    .filter(|c| match c.code {
+                        KeyCode::Char(ch) if ch.is_ascii_uppercase() => !all.contains(&Chord::new(
+                            KeyCode::Char(ch.to_ascii_lowercase()),
+                            c.modifiers,
+                        )),
+                        _ => true,
+                    })
+                    .copied()
+                    .collect()
+            }
+            _ => actions
+                .iter()
+                .filter_map(|&a| self.chords(table, a).next())
+                .collect(),
+        };
+        join(&chords)
+    }
+
+    /// Like `hint` but only ever the first chord of each action, for the
+    /// one-line help bar.
+    pub fn short_hint(&self, table: KeyTable, actions: &[Action]) -> Option<String> {
+        let chords: Vec<Chord> = actions
+            .iter()
+            .filter_map(|&a| self.chords(table, a).next())
+            .collect();
+        join(&chords)
+    }
+}
+
+fn join(chords: &[Chord]) -> Option<String> {
+    let keys: Vec<String> = chords.iter().map(|c| c.to_string()).collect();
+    (!keys.is_empty()).then(|| keys.join("/"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn press(code: KeyCode, modifiers: KeyModifiers) -> KeyEvent {
+        KeyEvent::new(code, modifiers)
+    }
+
+    #[test]
+    fn test_chord_parse() {
+        assert_eq!(Chord::parse("x"), Some(Chord::char('x')));
+        assert_eq!(Chord::parse("G"), Some(Chord::char('G')));
+        assert_eq!(Chord::parse("shift-g"), Some(Chord::char('G')));
+        assert_eq!(Chord::parse("ctrl-d"), Some(Chord::ctrl('d')));
+        assert_eq!(Chord::parse("Ctrl-Alt-x").map(|c| c.modifiers), Some(KeyModifiers::CONTROL | KeyModifiers::ALT));
+        assert_eq!(Chord::parse("shift-tab"), Some(Chord::key(KeyCode::BackTab)));
+        assert_eq!(Chord::parse("space"), Some(Chord::char(' ')));
+        assert_eq!(Chord::parse("f2"), Some(Chord::key(KeyCode::F(2))));
+        assert_eq!(Chord::parse("hyper-x"), None);
+        assert_eq!(Chord::parse("f13"), None);
+        assert_eq!(Chord::ctrl('d').to_string(), "^d");
+        assert_eq!(Chord::key(KeyCode::BackTab).to_string(), "S-Tab");
+    }
+
+    #[test]
+    fn test_defaults_match_events() {
+        let keymap = Keymap::default();
+        // Terminals report `G` with or without SHIFT
+        let shifted_g = press(KeyCode::Char('G'), KeyModifiers::SHIFT);
+        assert_eq!(keymap.lookup(KeyTable::Normal, shifted_g), Some(Action::GoToBottom));
+        let ctrl_d = press(KeyCode::Char('d'), KeyModifiers::CONTROL);
+        assert_eq!(keymap.lookup(KeyTable::Normal, ctrl_d), Some(Action::PageDown));
+        let d = press(KeyCode::Char('d'), KeyModifiers::NONE);
+        assert_eq!(keymap.lookup(KeyTable::Normal, d), Some(Action::DeleteAlias));
+        assert_eq!(keymap.lookup(KeyTable::Search, d), None);
+        assert_eq!(keymap.hint(KeyTable::Normal, &[Action::ScrollDown, Action::ScrollUp]).as_deref(), Some("j/k"));
+        assert_eq!(keymap.hint(KeyTable::Confirm, &[Action::ConfirmNo]).as_deref(), Some("n/Esc"));
+        assert_eq!(keymap.short_hint(KeyTable::Confirm, &[Action::ConfirmNo]).as_deref(), Some("n"));
+    }
+
+    #[test]
+    fn test_config_rebinds() {
+        let keymap = Keymap::parse(
+            "# keep delete away from d\n[normal]\nd = none\nD = delete  # capital\n\n[confirm]\ny = none\n",
+        )
+        .unwrap();
+        let key = |c| press(KeyCode::Char(c), KeyModifiers::NONE);
+        assert_eq!(keymap.lookup(KeyTable::Normal, key('d')), None);
+        assert_eq!(keymap.lookup(KeyTable::Normal, key('D')), Some(Action::DeleteAlias));
+        assert_eq!(keymap.lookup(KeyTable::Confirm, key('y')), None);
+        assert_eq!(keymap.lookup(KeyTable::Confirm, key('Y')), Some(Action::ConfirmYes));
+        assert_eq!(keymap.hint(KeyTable::Normal, &[Action::DeleteAlias]).as_deref(), Some("D"));
+    }
+
+    #[test]
+    fn test_config_errors() {
+        let err = |text| Keymap::parse(text).unwrap_err().to_string();
+        assert!(err("[normal]\nhyper-x = quit").starts_with("line 2: unknown key `hyper-x`"));
+        assert_eq!(
+            err("[confirm]\nx = delete"),
+            "line 2: [confirm] has no action `delete`; expected one of: yes, no, next, none"
+        );
+        assert_eq!(
+            err("[normal]\nG = bottom\nshift-g = top"),
+            "line 3: `shift-g` is already set in [normal] on line 2"
+        );
+    }
+}
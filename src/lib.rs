@@ -0,0 +1,23 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! zam's modules, shared by the `zam` binary and the integration tests.
+
+pub mod action;
+pub mod alias;
+pub mod app;
+pub mod clipboard;
+pub mod collision;
+pub mod config;
+pub mod context;
+pub mod event;
+pub mod extensions;
+pub mod history;
+pub mod keymap;
+pub mod line_editor;
+pub mod managed;
+pub mod pick;
+pub mod run;
+pub mod session;
+pub mod shell;
+pub mod ui;
+pub mod validate;
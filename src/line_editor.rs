@@ -0,0 +1,231 @@
+// SPDX-License-Identifier: Apache-2.0
+
+/// A single-line text field with a cursor, for the edit form.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LineEditor {
+    text: String,
+    /// Cursor position in chars, 0..=len.
+    cursor: usize,
+    /// First char shown when the text is wider than the field.
+    scroll: usize,
+}
+
+impl LineEditor {
+    /// `text` with the cursor at the end.
+    pub fn new(text: &str) -> Self {
+        LineEditor {
+            text: text.to_string(),
+            cursor: text.chars().count(),
+            scroll: 0,
+        }
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn cursor(&self) -> usize {
+        self.cursor
+    }
+
+    fn len(&self) -> usize {
+        self.text.chars().count()
+    }
+
+    /// Byte offset of char `i`.
+    fn byte(&self, i: usize) -> usize {
+        self.text
+            .char_indices()
+            .nth(i)
+            .map_or(self.text.len(), |(b, _)| b)
+    }
+
+    fn char_at(&self, i: usize) -> Option<char> {
+        self.text.chars().nth(i)
+    }
+
+    pub fn insert(&mut self, c: char) {
+        let at = self.byte(self.cursor);
+        self.text.insert(at, c);
+        self.cursor += 1;
+    }
+
+    /// Insert pasted text. Line breaks and tabs become spaces and a
+    /// trailing newline is dropped, since the field is one line.
+    pub fn insert_str(&mut self, s: &str) {
+        let clean: String = s
+            .trim_end_matches(['\n', '\r'])
+            .chars()
+            .map(|c| if c.is_control() { ' ' } else { c })
+            .collect();
+        let at = self.byte(self.cursor);
+        self.text.insert_str(at, &clean);
+        self.cursor += clean.chars().count();
+    }
+
+    /// Delete the char before the cursor.
+    pub fn backspace(&mut self) {
+        if self.cursor > 0 {
+            self.cursor -= 1;
+            self.delete();
+        }
+    }
+
+    /// Delete the char under the cursor.
+    pub fn delete(&mut self) {
+        if self.cursor < self.len() {
+            let at = self.byte(self.cursor);
+            self.text.remove(at);
+        }
+    }
+
+    /// Delete back to the previous whitespace, like Ctrl-w in a shell.
+    pub fn delete_word(&mut self) {
+        let mut start = self.cursor;
+        while start > 0 && self.char_at(start - 1).is_some_and(char::is_whitespace) {
+            start -= 1;
+        }
+        while start > 0 && self.char_at(start - 1).is_some_and(|c| !c.is_whitespace()) {
+            start -= 1;
+        }
+        let (from, to) = (self.byte(start), self.byte(self.cursor));
+        self.text.replace_range(from..to, "");
+        self.cursor = start;
+    }
+
+    pub fn clear(&mut self) {
+        self.text.clear();
+        self.cursor = 0;
+    }
+
+    pub fn left(&mut self) {
+        self.cursor = self.cursor.saturating_sub(1);
+    }
+
+    pub fn right(&mut self) {
+        self.cursor = (self.cursor + 1).min(self.len());
+    }
+
+    pub fn home(&mut self) {
+        self.cursor = 0;
+    }
+
+    pub fn end(&mut self) {
+        self.cursor = self.len();
+    }
+
+    /// Move to the start of the previous word (letters, digits, `_`).
+    pub fn word_left(&mut self) {
+        while self.cursor > 0 && !self.char_at(self.cursor - 1).is_some_and(is_word) {
+            self.cursor -= 1;
+        }
+        while self.cursor > 0 && self.char_at(self.cursor - 1).is_some_and(is_word) {
+            self.cursor -= 1;
+        }
+    }
+
+    /// Move past the end of the next word.
+    pub fn word_right(&mut self) {
+        let len = self.len();
+        while self.cursor < len && !self.char_at(self.cursor).is_some_and(is_word) {
+            self.cursor += 1;
+        }
+        while self.cursor < len && self.char_at(self.cursor).is_some_and(is_word) {
+            self.cursor += 1;
+        }
+    }
+
+    /// The chars to show in a field `width` cells wide, as a char range.
+    /// Scrolls only as far as needed to keep the cursor (which may sit
+    /// past the last char) in view.
+    pub fn window(&mut self, width: usize) -> (usize, usize) {
+        let width = width.max(1);
+        let len = self.len();
+        if self.cursor < self.scroll {
+            self.scroll = self.cursor;
+        } else if self.cursor >= self.scroll + width {
+            self.scroll = self.cursor + 1 - width;
+        }
+        self.scroll = self.scroll.min((len + 1).saturating_sub(width));
+        (self.scroll, (self.scroll + width).min(len))
+    }
+}
+
+fn is_word(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(text: &str, cursor: usize) -> LineEditor {
+        LineEditor {
+            text: text.into(),
+            cursor,
+            scroll: 0,
+        }
+    }
+
+    #[test]
+    fn test_insert_and_delete_mid_line() {
+        let mut e = at("git stats", 8);
+        e.insert('u');
+        assert_eq!((e.text(), e.cursor()), ("git status", 9));
+        e.delete();
+        e.backspace();
+        assert_eq!((e.text(), e.cursor()), ("git stat", 8));
+        e.home();
+        e.backspace();
+        e.delete();
+        assert_eq!((e.text(), e.cursor()), ("it stat", 0));
+        e.end();
+        e.delete();
+        assert_eq!(e.cursor(), 7);
+    }
+
+    #[test]
+    fn test_word_motions() {
+        let mut e = LineEditor::new("ls -la ~/src/zam");
+        e.word_left();
+        assert_eq!(e.cursor(), 13);
+        e.word_left();
+        e.word_left();
+        assert_eq!(e.cursor(), 4);
+        e.word_right();
+        assert_eq!(e.cursor(), 6);
+
+        let mut e = LineEditor::new("git log --oneline  ");
+        e.delete_word();
+        assert_eq!(e.text(), "git log ");
+        e.home();
+        e.delete_word();
+        assert_eq!(e.text(), "git log ");
+    }
+
+    #[test]
+    fn test_paste_and_unicode() {
+        let mut e = LineEditor::new("echo ");
+        e.insert_str("héllo\tworld\n");
+        assert_eq!((e.text(), e.cursor()), ("echo héllo world", 16));
+        e.word_left();
+        e.left();
+        e.backspace();
+        assert_eq!(e.text(), "echo héll world");
+    }
+
+    #[test]
+    fn test_window_follows_cursor() {
+        let mut e = LineEditor::new("0123456789");
+        // The cursor sits after the last char, so one cell goes to it
+        assert_eq!(e.window(5), (6, 10));
+        assert_eq!(e.window(20), (0, 10));
+        e.home();
+        assert_eq!(e.window(5), (0, 5));
+        e.cursor = 7;
+        assert_eq!(e.window(5), (3, 8));
+        // Moving back inside the window doesn't scroll
+        e.cursor = 4;
+        assert_eq!(e.window(5), (3, 8));
+    }
+}
@@ -1,27 +1,34 @@
 // SPDX-License-Identifier: Apache-2.0
 
-mod action;
-mod alias;
-mod app;
-mod collision;
-mod event;
-mod extensions;
-mod managed;
-mod ui;
-
 use std::env;
-use std::io;
+use std::fs::File;
+use std::io::{self, IsTerminal, Write};
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
 
 use crossterm::{
-    event::{DisableMouseCapture, EnableMouseCapture},
+    cursor::{MoveTo, Show},
+    event::{DisableBracketedPaste, DisableMouseCapture, EnableBracketedPaste, EnableMouseCapture, Event},
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
 use ratatui::backend::CrosstermBackend;
-use ratatui::Terminal;
-
-use alias::types::AliasGroup;
-use app::AppState;
+use ratatui::layout::Rect;
+use ratatui::{Terminal, TerminalOptions, Viewport};
+
+use zam::alias::collector::CollectOptions;
+use zam::alias::types::{AliasGroup, AliasState, CollectError};
+use zam::alias::{self, Collected};
+use zam::app::AppState;
+use zam::config::Config;
+use zam::context::Context;
+use zam::event::Input;
+use zam::pick::{self, Picker};
+use zam::session::Session;
+use zam::shell::Shell;
+use zam::ui::theme::ColorSupport;
+use zam::{clipboard, collision, event, extensions, managed, run, ui, validate};
 
 fn main() -> io::Result<()> {
     let args: Vec<String> = env::args().collect();
@@ -31,51 +38,149 @@ fn main() -> io::Result<()> {
         return Ok(());
     }
 
+    let mut ctx = Context::from_env(select_shell(&args));
+    let opts = collect_options(&args);
+
     if args.iter().any(|a| a == "--init") {
-        return run_init();
+        return run_init(&mut ctx, &opts, &args);
     }
 
     if args.iter().any(|a| a == "--nuke") {
-        return run_nuke();
+        return run_nuke(&mut ctx, &opts);
     }
 
     // ext subcommand: zam ext [list|install|remove] [name]
     if args.get(1).map(|s| s.as_str()) == Some("ext") {
-        return run_ext(&args[2..]);
+        return run_ext(&ctx, &args[2..], &opts);
     }
 
+    // zam tag <name> [tag...], zam untag <name> [tag...]
+    match args.get(1).map(|s| s.as_str()) {
+        Some("tag") => return run_tag(&ctx, &args[2..], true),
+        Some("untag") => return run_tag(&ctx, &args[2..], false),
+        _ => {}
+    }
+
+    let pick_mode = args.get(1).map(|s| s.as_str()) == Some("pick");
     let list_mode = args.iter().any(|a| a == "--list" || a == "-l");
     let mockup_mode = args.iter().any(|a| a == "--mockup");
+    let refresh = args.iter().any(|a| a == "--refresh");
+    // Before collecting, so a config mistake is reported straight away
+    let config = if list_mode {
+        Config::default()
+    } else {
+        load_config(&ctx)
+    };
 
-    eprintln!("Loading aliases from zsh...");
-    let groups = match alias::load_aliases() {
-        Ok(g) => g,
-        Err(e) => {
-            eprintln!("Error: {e}");
-            std::process::exit(1);
+    let cached = if refresh {
+        None
+    } else {
+        alias::load_cached_aliases(&ctx)
+    };
+    let revalidate = cached.is_some();
+    let collected = match cached {
+        Some(c) => c,
+        None => {
+            eprintln!("Loading aliases from {}...", ctx.shell);
+            match alias::load_aliases(&ctx, &opts) {
+                Ok(c) => c,
+                Err(e) => {
+                    eprintln!("Error: {e}");
+                    std::process::exit(1);
+                }
+            }
         }
     };
 
+    ctx.paths = collected.paths;
+
     if list_mode {
-        print_list(&groups);
+        print_list(&ctx, &collected.groups);
         return Ok(());
     }
 
     if mockup_mode {
-        print_mockup(groups);
+        print_mockup(ctx, config, collected.groups);
         return Ok(());
     }
 
-    run_tui(groups)
+    if pick_mode {
+        let query = args.get(2).filter(|a| !a.starts_with('-')).map_or("", |a| a.as_str());
+        return run_pick(ctx, config, collected.groups, query);
+    }
+
+    run_tui(ctx, config, collected.groups, revalidate.then_some(opts))
 }
 
-fn run_init() -> io::Result<()> {
-    match managed::init() {
+/// Key bindings and theme from ~/.config/zam/config; exits with the error
+/// if the file doesn't validate.
+fn load_config(ctx: &Context) -> Config {
+    let path = ctx.config_dir.join("config");
+    match Config::load(&path, ColorSupport::from_env()) {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("Error in {}: {e}", ctx.display(&path));
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Apply `--shell <zsh|bash|fish>`; without it the shell comes from `$SHELL`.
+fn select_shell(args: &[String]) -> Shell {
+    let Some(pos) = args.iter().position(|a| a == "--shell") else {
+        return Shell::detect();
+    };
+    match args.get(pos + 1).and_then(|v| Shell::from_name(v)) {
+        Some(shell) => shell,
+        None => {
+            eprintln!("--shell expects zsh, bash or fish");
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Parse `--timeout <secs>` and `--clean-env` into collector options.
+fn collect_options(args: &[String]) -> CollectOptions {
+    let mut opts = CollectOptions {
+        clean_env: args.iter().any(|a| a == "--clean-env"),
+        ..CollectOptions::default()
+    };
+    if let Some(pos) = args.iter().position(|a| a == "--timeout") {
+        match args.get(pos + 1).and_then(|v| v.parse::<u64>().ok()) {
+            Some(secs) if secs > 0 => opts.timeout = Duration::from_secs(secs),
+            _ => {
+                eprintln!("--timeout expects a positive number of seconds");
+                std::process::exit(1);
+            }
+        }
+    }
+    opts
+}
+
+/// `--pick-key <key>` also binds the zsh picker widget.
+fn run_init(ctx: &mut Context, opts: &CollectOptions, args: &[String]) -> io::Result<()> {
+    let shell = ctx.shell;
+    let pick_key = match args.iter().position(|a| a == "--pick-key") {
+        None => None,
+        Some(pos) => match args.get(pos + 1) {
+            Some(key) if shell == Shell::Zsh && !key.is_empty() => Some(key.as_str()),
+            Some(_) if shell != Shell::Zsh => {
+                eprintln!("--pick-key binds a zsh widget; it has no effect for {shell}");
+                std::process::exit(1);
+            }
+            _ => {
+                eprintln!("--pick-key expects a key sequence, e.g. '^G'");
+                std::process::exit(1);
+            }
+        },
+    };
+    ctx.paths = alias::shell_paths(ctx, opts);
+    match managed::init(ctx, pick_key) {
         Ok(()) => {
-            println!("Initialized ~/.config/zam/");
+            println!("Initialized ~/.config/zam/ for {shell}");
             println!();
             println!("Restart your shell or run:");
-            println!("  source ~/.config/zam/init.zsh");
+            println!("  source ~/.config/zam/init.{}", shell.file_ext());
             Ok(())
         }
         Err(e) => {
@@ -85,8 +190,10 @@ fn run_init() -> io::Result<()> {
     }
 }
 
-fn run_nuke() -> io::Result<()> {
-    eprint!("This will delete ~/.config/zam/ and remove the source line from ~/.zshrc. Continue? [y/N] ");
+fn run_nuke(ctx: &mut Context, opts: &CollectOptions) -> io::Result<()> {
+    ctx.paths = alias::shell_paths(ctx, opts);
+    let zshrc = ctx.display(&ctx.paths.rc_file(Shell::Zsh));
+    eprint!("This will delete ~/.config/zam/ and ~/.config/fish/conf.d/zam.fish, and remove the source lines from {zshrc} and ~/.bashrc. Continue? [y/N] ");
     let mut answer = String::new();
     io::stdin().read_line(&mut answer)?;
     if !answer.trim().eq_ignore_ascii_case("y") {
@@ -94,12 +201,16 @@ fn run_nuke() -> io::Result<()> {
         return Ok(());
     }
 
-    match managed::nuke() {
+    match managed::nuke(ctx) {
         Ok(()) => {
-            println!("Removed ~/.config/zam/ and cleaned ~/.zshrc");
+            println!("Removed ~/.config/zam/ and cleaned your rc files");
             println!();
             println!("Restart your shell or run:");
-            println!("  unfunction zam 2>/dev/null; source ~/.zshrc");
+            match ctx.shell {
+                Shell::Zsh => println!("  unfunction zam 2>/dev/null; source {zshrc}"),
+                Shell::Bash => println!("  unset -f zam; source ~/.bashrc"),
+                Shell::Fish => println!("  functions -e zam"),
+            }
         }
         Err(e) => {
             eprintln!("Nuke failed: {e}");
@@ -109,18 +220,18 @@ fn run_nuke() -> io::Result<()> {
     Ok(())
 }
 
-fn run_ext(args: &[String]) -> io::Result<()> {
+fn run_ext(ctx: &Context, args: &[String], opts: &CollectOptions) -> io::Result<()> {
     let subcmd = args.first().map(|s| s.as_str()).unwrap_or("list");
 
     match subcmd {
-        "list" | "" => ext_list(),
+        "list" | "" => ext_list(ctx),
         "install" => {
             let name = args.get(1).map(|s| s.as_str()).unwrap_or("");
-            ext_install(name)
+            ext_install(ctx, name, opts)
         }
         "remove" => {
             let name = args.get(1).map(|s| s.as_str()).unwrap_or("");
-            ext_remove(name)
+            ext_remove(ctx, name)
         }
         other => {
             eprintln!("Unknown ext command: {other}");
@@ -130,10 +241,42 @@ fn run_ext(args: &[String]) -> io::Result<()> {
     }
 }
 
-fn ext_list() -> io::Result<()> {
+/// Add tags to an alias, or remove them (all of them when none are
+/// named). With only a name, print its tags.
+fn run_tag(ctx: &Context, args: &[String], add: bool) -> io::Result<()> {
+    let Some((name, tags)) = args.split_first() else {
+        eprintln!("Usage: zam tag <alias> <tag>...");
+        eprintln!("       zam untag <alias> [tag...]");
+        std::process::exit(1);
+    };
+    if !managed::is_initialized(ctx) {
+        eprintln!("Not initialized — run `zam --init` first.");
+        std::process::exit(1);
+    }
+
+    let result = match (add, tags.is_empty()) {
+        (true, true) => {
+            let tags = managed::read_tags(ctx).remove(name.as_str()).unwrap_or_default();
+            for tag in tags {
+                println!("#{tag}");
+            }
+            return Ok(());
+        }
+        (true, false) => tags.iter().try_for_each(|tag| managed::tag_alias(ctx, name, tag)),
+        (false, true) => managed::untag_alias(ctx, name, None),
+        (false, false) => tags.iter().try_for_each(|tag| managed::untag_alias(ctx, name, Some(tag))),
+    };
+    if let Err(e) = result {
+        eprintln!("Error: {e}");
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+fn ext_list(ctx: &Context) -> io::Result<()> {
     println!("Available extensions:\n");
     for ext in extensions::EXTENSIONS {
-        let status = if managed::is_extension_installed(ext.name) {
+        let status = if managed::is_extension_installed(ctx, ext.name) {
             "installed"
         } else {
             "not installed"
@@ -146,7 +289,7 @@ fn ext_list() -> io::Result<()> {
     Ok(())
 }
 
-fn ext_install(name: &str) -> io::Result<()> {
+fn ext_install(ctx: &Context, name: &str, opts: &CollectOptions) -> io::Result<()> {
     if name.is_empty() {
         eprintln!("Usage: zam ext install <name>");
         eprintln!("Run `zam ext` to see available extensions.");
@@ -159,20 +302,22 @@ fn ext_install(name: &str) -> io::Result<()> {
         std::process::exit(1);
     };
 
-    if managed::is_extension_installed(name) {
+    if managed::is_extension_installed(ctx, name) {
         println!("Extension '{name}' is already installed.");
         return Ok(());
     }
 
-    if !managed::is_initialized() {
+    if !managed::is_initialized(ctx) {
         eprintln!("Not initialized — run `zam --init` first.");
         std::process::exit(1);
     }
 
     // Load current aliases for collision checking
-    let groups = alias::load_aliases().unwrap_or_default();
+    let groups = alias::load_aliases(ctx, opts)
+        .map(|c| c.groups)
+        .unwrap_or_default();
 
-    let collisions = collision::check_batch_collisions(ext.aliases, &groups);
+    let collisions = collision::check_batch_collisions(ctx, ext.aliases, &groups);
     if !collisions.is_empty() {
         println!("Collisions detected:");
         for (alias_name, c) in &collisions {
@@ -188,7 +333,7 @@ fn ext_install(name: &str) -> io::Result<()> {
         }
     }
 
-    match managed::install_extension(name, ext.aliases) {
+    match managed::install_extension(ctx, name, ext.aliases) {
         Ok(()) => {
             println!(
                 "Installed extension '{name}' ({} aliases)",
@@ -203,7 +348,7 @@ fn ext_install(name: &str) -> io::Result<()> {
     Ok(())
 }
 
-fn ext_remove(name: &str) -> io::Result<()> {
+fn ext_remove(ctx: &Context, name: &str) -> io::Result<()> {
     if name.is_empty() {
         eprintln!("Usage: zam ext remove <name>");
         std::process::exit(1);
@@ -214,12 +359,12 @@ fn ext_remove(name: &str) -> io::Result<()> {
         std::process::exit(1);
     }
 
-    if !managed::is_extension_installed(name) {
+    if !managed::is_extension_installed(ctx, name) {
         println!("Extension '{name}' is not installed.");
         return Ok(());
     }
 
-    match managed::remove_extension(name) {
+    match managed::remove_extension(ctx, name) {
         Ok(()) => {
             println!("Removed extension '{name}'");
         }
@@ -231,36 +376,202 @@ fn ext_remove(name: &str) -> io::Result<()> {
     Ok(())
 }
 
-fn run_tui(groups: Vec<AliasGroup>) -> io::Result<()> {
+fn run_tui(
+    ctx: Context,
+    config: Config,
+    groups: Vec<AliasGroup>,
+    revalidate: Option<CollectOptions>,
+) -> io::Result<()> {
     enable_raw_mode()?;
-    let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
-    let backend = CrosstermBackend::new(stdout);
+    let mut tty = tty()?;
+    execute!(tty, EnterAlternateScreen, EnableMouseCapture, EnableBracketedPaste)?;
+    let backend = CrosstermBackend::new(tty);
     let mut terminal = Terminal::new(backend)?;
 
-    let mut app = AppState::new(groups);
-    let result = run_loop(&mut terminal, &mut app);
+    let session = Session::load(&ctx);
+    let mut app = AppState::new(groups, ctx);
+    app.keymap = config.keymap;
+    app.theme = config.theme;
+    app.restore(session);
+    let result = run_loop(&mut terminal, &mut app, revalidate);
 
     disable_raw_mode()?;
     execute!(
         terminal.backend_mut(),
         LeaveAlternateScreen,
-        DisableMouseCapture
+        DisableMouseCapture,
+        DisableBracketedPaste
     )?;
     terminal.show_cursor()?;
 
+    if let Err(e) = app.session().save(&app.ctx) {
+        eprintln!("Could not save the session: {e}");
+    }
+    if let Some(name) = &app.picked {
+        println!("{name}");
+    }
     result
 }
 
+/// Where the TUI draws: stdout, or the terminal itself when stdout is
+/// captured, as by the zle widget, which only wants the picked alias.
+fn tty() -> io::Result<Box<dyn Write>> {
+    if io::stdout().is_terminal() {
+        Ok(Box::new(io::stdout()))
+    } else {
+        Ok(Box::new(File::options().write(true).open("/dev/tty")?))
+    }
+}
+
+/// `zam pick`: search in a few rows under the prompt, drawn on the
+/// terminal so the zle widget only captures the name printed at the end.
+/// Exits 1 without printing anything when cancelled.
+fn run_pick(ctx: Context, config: Config, groups: Vec<AliasGroup>, query: &str) -> io::Result<()> {
+    let mut picker = Picker::new(groups, ctx, query);
+    picker.app.theme = config.theme;
+
+    enable_raw_mode()?;
+    let result = File::options()
+        .read(true)
+        .write(true)
+        .open("/dev/tty")
+        .and_then(|mut tty| pick_loop(&mut tty, &mut picker));
+    disable_raw_mode()?;
+    result?;
+
+    match picker.app.picked {
+        Some(name) => println!("{name}"),
+        None => std::process::exit(1),
+    }
+    Ok(())
+}
+
+/// Not ratatui's inline viewport: that asks for the cursor position on
+/// stdout, which the widget captures, so ask the terminal directly and
+/// draw in a fixed area below it.
+fn pick_loop(tty: &mut File, picker: &mut Picker) -> io::Result<()> {
+    let (width, rows) = crossterm::terminal::size()?;
+    let (row, col) = cursor_position(tty)?;
+    // Make room under the prompt, scrolling it up if it's near the bottom
+    let height = pick::HEIGHT.min(rows.saturating_sub(1));
+    let scrolled = (row + 1 + height).saturating_sub(rows);
+    tty.write_all("\n".repeat(height as usize).as_bytes())?;
+    let prompt_row = row - scrolled;
+    let area = Rect::new(0, prompt_row + 1, width, height);
+
+    let backend = CrosstermBackend::new(tty.try_clone()?);
+    let mut terminal = Terminal::with_options(backend, TerminalOptions { viewport: Viewport::Fixed(area) })?;
+    while !picker.done {
+        terminal.draw(|frame| ui::picker::render(frame, picker))?;
+        if let Event::Key(key) = crossterm::event::read()? {
+            picker.key(key);
+        }
+    }
+    terminal.clear()?;
+    execute!(tty, MoveTo(col, prompt_row), Show)
+}
+
+/// Where the cursor is, from the terminal's reply to `ESC [ 6 n`.
+fn cursor_position(tty: &mut File) -> io::Result<(u16, u16)> {
+    tty.write_all(b"\x1b[6n")?;
+    tty.flush()?;
+    let mut reply = Vec::new();
+    let mut byte = [0u8];
+    while reply.len() < 32 && io::Read::read(tty, &mut byte)? == 1 {
+        reply.push(byte[0]);
+        if byte[0] == b'R' {
+            break;
+        }
+    }
+    pick::parse_cursor_report(&reply)
+        .ok_or_else(|| io::Error::other("the terminal did not report the cursor position"))
+}
+
 fn run_loop(
-    terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    terminal: &mut Terminal<CrosstermBackend<Box<dyn Write>>>,
     app: &mut AppState,
+    mut revalidate: Option<CollectOptions>,
 ) -> io::Result<()> {
+    let mut refresh_rx = None;
+    let mut check_rx = None;
+    let mut run_rx = None;
     loop {
         terminal.draw(|frame| ui::render(frame, app))?;
 
-        if let Some(action) = event::next_action(app.mode)? {
-            app.handle_action(action);
+        // The cached alias set is on screen; recollect from the shell in the
+        // background and swap the result in when it arrives.
+        if let Some(opts) = revalidate.take() {
+            refresh_rx = Some((spawn_revalidation(app.ctx.clone(), opts), app.writes, opts));
+        }
+        if let Some((rx, writes, opts)) = &refresh_rx {
+            match rx.try_recv() {
+                // zam changed the files meanwhile, so the result may predate
+                // that; collect again rather than undo it on screen
+                Ok(Ok(_)) if *writes != app.writes => {
+                    revalidate = Some(*opts);
+                    refresh_rx = None;
+                }
+                Ok(Ok(collected)) => {
+                    app.ctx.paths = collected.paths;
+                    app.replace_groups(collected.groups);
+                    refresh_rx = None;
+                }
+                Ok(Err(e)) => {
+                    app.set_status(&format!("Refresh failed: {e}"));
+                    refresh_rx = None;
+                }
+                Err(mpsc::TryRecvError::Empty) => {}
+                Err(mpsc::TryRecvError::Disconnected) => refresh_rx = None,
+            }
+        }
+
+        // Check the edit form in the background, one check at a time; the
+        // latest fields are picked up once the running check finishes.
+        if check_rx.is_none() {
+            if let Some(input) = app.next_check() {
+                check_rx = Some(spawn_check(app.ctx.clone(), app.groups.clone(), input));
+            }
+        }
+        if let Some(rx) = &check_rx {
+            match rx.try_recv() {
+                Ok((input, problems)) => {
+                    app.finish_check(&input, problems);
+                    check_rx = None;
+                }
+                Err(mpsc::TryRecvError::Empty) => {}
+                Err(mpsc::TryRecvError::Disconnected) => check_rx = None,
+            }
+        }
+
+        match event::next_input(&app.keymap, app.mode)? {
+            Some(Input::Action(action)) => app.handle_action(action),
+            Some(Input::Paste(text)) => app.paste(&text),
+            Some(Input::Mouse(mouse)) => {
+                let size = terminal.size()?;
+                app.mouse(mouse, &ui::layout::build_layout(Rect::new(0, 0, size.width, size.height)));
+            }
+            None => {}
+        }
+
+        // Run aliases in the background too; the popup says so meanwhile
+        if let Some(command_line) = app.run_request.take() {
+            run_rx = Some(spawn_run(app.ctx.clone(), command_line));
+        }
+        if let Some(rx) = &run_rx {
+            match rx.try_recv() {
+                Ok((command_line, output)) => {
+                    app.finish_run(&command_line, output);
+                    run_rx = None;
+                }
+                Err(mpsc::TryRecvError::Empty) => {}
+                Err(mpsc::TryRecvError::Disconnected) => run_rx = None,
+            }
+        }
+
+        if let Some(text) = app.copy_request.take() {
+            if let Err(e) = clipboard::copy(terminal.backend_mut(), &text) {
+                app.set_status(&format!("Copy failed: {e}"));
+            }
         }
 
         app.tick();
@@ -271,13 +582,57 @@ fn run_loop(
     }
 }
 
-fn print_list(groups: &[AliasGroup]) {
-    let total: usize = groups.iter().map(|g| g.aliases.len()).sum();
-    println!("{} aliases in {} groups (zsh)\n", total, groups.len());
+fn spawn_revalidation(
+    ctx: Context,
+    opts: CollectOptions,
+) -> mpsc::Receiver<Result<Collected, CollectError>> {
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        let _ = tx.send(alias::load_aliases(&ctx, &opts));
+    });
+    rx
+}
+
+fn spawn_check(
+    ctx: Context,
+    groups: Vec<AliasGroup>,
+    input: validate::Input,
+) -> mpsc::Receiver<(validate::Input, Vec<validate::Problem>)> {
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        let problems = validate::check(&ctx, &groups, &input);
+        let _ = tx.send((input, problems));
+    });
+    rx
+}
 
+fn spawn_run(ctx: Context, command_line: String) -> mpsc::Receiver<(String, run::Output)> {
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        let output = run::run(&ctx, &command_line);
+        let _ = tx.send((command_line, output));
+    });
+    rx
+}
+
+fn print_list(ctx: &Context, groups: &[AliasGroup]) {
+    let total: usize = groups.iter().map(|g| g.aliases.len()).sum();
+    println!(
+        "{} aliases in {} groups ({})\n",
+        total,
+        groups.len(),
+        ctx.shell
+    );
+
+    let mut section = None;
     for group in groups {
+        if groups.len() > 1 && section != Some(group.section()) {
+            section = Some(group.section());
+            println!("══ {} ══\n", group.section().title());
+        }
+
         let name = group.source.display_name();
-        let file = group.source.file_path().unwrap_or_default();
+        let file = group.source.file_path(ctx).unwrap_or_default();
 
         println!("── {} ({}) ──", name, group.aliases.len());
         if !file.is_empty() {
@@ -293,57 +648,67 @@ fn print_list(groups: &[AliasGroup]) {
             .unwrap_or(0);
 
         for alias in &group.aliases {
+            let flag = alias.kind.flag().map(|f| format!("{f} ")).unwrap_or_default();
             print!(
-                "  {:<width$}  {}",
+                "  {:<width$}  {flag}{}",
                 alias.name,
-                alias.command,
+                alias.summary(),
                 width = max_name
             );
             if let Some(desc) = &alias.description {
                 print!("  # {desc}");
             }
+            for tag in &alias.tags {
+                print!("  #{tag}");
+            }
+            if alias.state == AliasState::Disabled {
+                print!("  (disabled)");
+            }
             println!();
         }
         println!();
     }
 }
 
-fn print_mockup(groups: Vec<AliasGroup>) {
+fn print_mockup(ctx: Context, config: Config, groups: Vec<AliasGroup>) {
     use ratatui::backend::TestBackend;
 
     let width = 90;
     let height = 18;
     let backend = TestBackend::new(width, height);
     let mut terminal = Terminal::new(backend).expect("failed to create test terminal");
-    let mut app = AppState::new(groups);
+    let mut app = AppState::new(groups, ctx);
+    app.keymap = config.keymap;
+    app.theme = config.theme;
 
     terminal
         .draw(|frame| ui::render(frame, &mut app))
         .expect("failed to render");
 
-    let buf = terminal.backend().buffer().clone();
-    for y in 0..height {
-        let mut line = String::new();
-        for x in 0..width {
-            line.push_str(buf.cell((x, y)).map_or(" ", |c| c.symbol()));
-        }
-        println!("{}", line.trim_end());
-    }
+    print!("{}", ui::buffer_text(terminal.backend().buffer()));
 }
 
 fn print_usage() {
-    println!("zam - zsh alias manager\n");
+    println!("zam - zsh, bash and fish alias manager\n");
     println!("USAGE:");
     println!("  zam                Launch interactive TUI");
     println!("  zam --list         Print all aliases grouped by source");
     println!("  zam --init         Initialize managed alias directory (~/.config/zam/)");
-    println!("  zam --nuke         Delete all zam config and clean ~/.zshrc");
+    println!("  zam --nuke         Delete all zam config and clean shell rc files");
     println!("  zam ext            List available extensions");
     println!("  zam ext install <name>  Install an extension");
     println!("  zam ext remove <name>   Remove an extension");
+    println!("  zam pick [query]   Search under the prompt and print the chosen alias");
+    println!("  zam tag <alias> <tag>   Tag an alias; without a tag, print its tags");
+    println!("  zam untag <alias> [tag] Remove a tag, or all of an alias's tags");
     println!("\nOPTIONS:");
     println!("  -l, --list         Print aliases to stdout instead of launching TUI");
-    println!("  --init             Set up ~/.config/zam/ and add source line to ~/.zshrc");
-    println!("  --nuke             Remove ~/.config/zam/ and source line from ~/.zshrc");
+    println!("  --shell <name>     Shell to manage: zsh, bash or fish (default: from $SHELL)");
+    println!("  --refresh          Ignore the alias cache (~/.cache/zam/) and re-run the shell");
+    println!("  --timeout <secs>   Give up on the shell after this many seconds (default 10)");
+    println!("  --clean-env        Run the shell with a minimal environment (HOME, PATH, TERM, ...)");
+    println!("  --init             Set up ~/.config/zam/ and add source line to the shell's rc file");
+    println!("  --pick-key <key>   With --init, bind the zsh zam-pick widget to a key, e.g. '^G'");
+    println!("  --nuke             Remove ~/.config/zam/ and zam's lines from shell rc files");
     println!("  -h, --help         Show this help message");
 }
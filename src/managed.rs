@@ -1,38 +1,38 @@
 // SPDX-License-Identifier: Apache-2.0
 
+use std::collections::BTreeMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
-use crate::alias::types::{AliasSource, ManagedError};
+use crate::alias::parser;
+use crate::alias::types::{Alias, AliasKind, AliasSource, ManagedError};
+use crate::context::Context;
+use crate::shell::Shell;
 
 /// Root of the managed directory: ~/.config/zam/
-pub fn managed_dir() -> Option<PathBuf> {
-    dirs::home_dir().map(|h| h.join(".config/zam"))
+pub fn managed_dir(ctx: &Context) -> Option<&Path> {
+    let dir = ctx.config_dir.as_path();
+    (!dir.as_os_str().is_empty()).then_some(dir)
 }
 
 /// Create the managed directory tree if it doesn't exist.
-pub fn ensure_dirs() -> Result<(), ManagedError> {
-    let base = managed_dir().ok_or(ManagedError::CreateDir(std::io::Error::new(
+pub fn ensure_dirs(ctx: &Context) -> Result<(), ManagedError> {
+    let base = managed_dir(ctx).ok_or(ManagedError::CreateDir(std::io::Error::new(
         std::io::ErrorKind::NotFound,
         "home directory not found",
     )))?;
     fs::create_dir_all(base.join("aliases")).map_err(ManagedError::CreateDir)?;
     fs::create_dir_all(base.join("overrides")).map_err(ManagedError::CreateDir)?;
+    fs::create_dir_all(base.join("dirs")).map_err(ManagedError::CreateDir)?;
     Ok(())
 }
 
-/// Full init: create dirs, write init.zsh, add source line to ~/.zshrc.
-pub fn init() -> Result<(), ManagedError> {
-    ensure_dirs()?;
-
-    let base = managed_dir().unwrap();
-    let init_path = base.join("init.zsh");
-
-    let init_content = "\
-# zam managed aliases — sourced from ~/.zshrc
+const ZSH_INIT: &str = "\
+# zam managed aliases — sourced from your .zshrc
 # Do not edit this file; it is regenerated by `zam --init`.
 
 for f in ~/.config/zam/aliases/*.zsh(N); do source \"$f\"; done
+for f in ~/.config/zam/dirs/*.zsh(N); do source \"$f\"; done
 for f in ~/.config/zam/overrides/*.zsh(N); do source \"$f\"; done
 
 # Shell wrapper: re-source aliases after zam modifies them
@@ -42,52 +42,159 @@ zam() {
     [ $ret -eq 0 ] && source ~/.config/zam/init.zsh
     return $ret
 }
+
+# Widget: pick an alias in zam (p) and insert it at the cursor.
+# Bind it with e.g. `bindkey '^X^G' zam-insert`.
+zam-insert() {
+    local picked
+    picked=$(command zam </dev/tty) && LBUFFER+=$picked
+    zle reset-prompt
+}
+zle -N zam-insert
+
+# Widget: search the aliases in a few lines under the prompt and insert
+# the pick at the cursor. `zam --init --pick-key <key>` binds it.
+zam-pick() {
+    local picked
+    picked=$(command zam pick </dev/tty) && LBUFFER+=$picked
+    zle reset-prompt
+}
+zle -N zam-pick
 ";
 
+const BASH_INIT: &str = "\
+# zam managed aliases — sourced from ~/.bashrc
+# Do not edit this file; it is regenerated by `zam --init`.
+
+for f in ~/.config/zam/aliases/*.bash ~/.config/zam/overrides/*.bash; do
+    [ -e \"$f\" ] && source \"$f\"
+done
+
+# Shell wrapper: re-source aliases after zam modifies them
+zam() {
+    command zam \"$@\"
+    local ret=$?
+    [ $ret -eq 0 ] && source ~/.config/zam/init.bash
+    return $ret
+}
+";
+
+const FISH_INIT: &str = "\
+# zam managed aliases — sourced from ~/.config/fish/conf.d/zam.fish
+# Do not edit this file; it is regenerated by `zam --init`.
+
+for f in ~/.config/zam/aliases/*.fish ~/.config/zam/overrides/*.fish
+    source $f
+end
+
+# Shell wrapper: re-source aliases after zam modifies them
+function zam
+    command zam $argv
+    set -l ret $status
+    test $ret -eq 0; and source ~/.config/fish/conf.d/zam.fish
+    return $ret
+end
+";
+
+fn init_file_name(shell: Shell) -> String {
+    format!("init.{}", shell.file_ext())
+}
+
+/// The line added to the shell's rc file by `init`.
+fn source_line(shell: Shell) -> String {
+    format!("source ~/.config/zam/{}", init_file_name(shell))
+}
+
+/// Full init for the current shell: create dirs, write init.zsh,
+/// init.bash or init.fish, add a source line to the rc file the shell
+/// reads — for zsh, the `.zshrc` in `$ZDOTDIR`. With `pick_key`, init.zsh
+/// also binds the `zam-pick` widget to that key sequence (e.g. `^G`).
+pub fn init(ctx: &Context, pick_key: Option<&str>) -> Result<(), ManagedError> {
+    ensure_dirs(ctx)?;
+
+    let shell = ctx.shell;
+    let paths = &ctx.paths;
+    let base = managed_dir(ctx).unwrap();
+    let init_path = base.join(init_file_name(shell));
+    let mut init_content = match shell {
+        Shell::Zsh => ZSH_INIT,
+        Shell::Bash => BASH_INIT,
+        Shell::Fish => FISH_INIT,
+    }
+    .to_string();
+    if let (Shell::Zsh, Some(key)) = (shell, pick_key) {
+        init_content.push_str(&format!("bindkey {} zam-pick\n", shell_quote(key)));
+    }
+
     fs::write(&init_path, init_content).map_err(ManagedError::WriteFile)?;
 
-    // Append source line to ~/.zshrc (idempotent)
-    let Some(home) = dirs::home_dir() else {
+    // Append source line to the rc file (idempotent)
+    if paths.home.as_os_str().is_empty() {
         return Err(ManagedError::CreateDir(std::io::Error::new(
             std::io::ErrorKind::NotFound,
             "home directory not found",
         )));
-    };
-    let zshrc_path = home.join(".zshrc");
-    let source_line = "source ~/.config/zam/init.zsh";
+    }
+    let rc_path = paths.rc_file(shell);
+    let source_line = source_line(shell);
+    // fish's conf.d/ may not exist yet
+    if let Some(parent) = rc_path.parent() {
+        fs::create_dir_all(parent).map_err(ManagedError::CreateDir)?;
+    }
 
-    let existing = fs::read_to_string(&zshrc_path).unwrap_or_default();
+    let existing = fs::read_to_string(&rc_path).unwrap_or_default();
     if !existing.lines().any(|l| l.trim() == source_line) {
         let mut content = existing;
         if !content.ends_with('\n') && !content.is_empty() {
             content.push('\n');
         }
-        content.push_str(source_line);
+        content.push_str(&source_line);
         content.push('\n');
-        fs::write(&zshrc_path, content).map_err(ManagedError::WriteFile)?;
+        fs::write(&rc_path, content).map_err(ManagedError::WriteFile)?;
     }
 
     Ok(())
 }
 
-/// Delete ~/.config/zam/ entirely and remove the source line from ~/.zshrc.
-pub fn nuke() -> Result<(), ManagedError> {
-    let Some(base) = managed_dir() else {
+/// Delete ~/.config/zam/ entirely and remove the source lines from every
+/// shell's rc file, since the directory is shared between shells. fish's
+/// conf.d/zam.fish belongs to zam and is deleted outright. A ~/.zshrc
+/// left over from before `$ZDOTDIR` was set is cleaned as well.
+pub fn nuke(ctx: &Context) -> Result<(), ManagedError> {
+    let Some(base) = managed_dir(ctx) else {
         return Ok(());
     };
 
     if base.is_dir() {
-        fs::remove_dir_all(&base).map_err(ManagedError::CreateDir)?;
+        fs::remove_dir_all(base).map_err(ManagedError::CreateDir)?;
     }
+    let paths = &ctx.paths;
 
-    // Remove source line from ~/.zshrc
-    let Some(home) = dirs::home_dir() else {
+    if paths.home.as_os_str().is_empty() {
         return Ok(());
-    };
-    let zshrc_path = home.join(".zshrc");
-    let source_line = "source ~/.config/zam/init.zsh";
+    }
+    let rc_files = [
+        (Shell::Zsh, paths.rc_file(Shell::Zsh)),
+        (Shell::Zsh, paths.home.join(Shell::Zsh.rc_file())),
+        (Shell::Bash, paths.rc_file(Shell::Bash)),
+        (Shell::Fish, paths.rc_file(Shell::Fish)),
+    ];
+    for (shell, rc_path) in rc_files {
+        let source_line = source_line(shell);
+
+        if shell == Shell::Fish {
+            if rc_path.is_file() {
+                fs::remove_file(&rc_path).map_err(ManagedError::WriteFile)?;
+            }
+            continue;
+        }
 
-    if let Ok(content) = fs::read_to_string(&zshrc_path) {
+        let Ok(content) = fs::read_to_string(&rc_path) else {
+            continue;
+        };
+        if !content.lines().any(|l| l.trim() == source_line) {
+            continue;
+        }
         let filtered: Vec<&str> = content
             .lines()
             .filter(|l| l.trim() != source_line)
@@ -96,16 +203,16 @@ pub fn nuke() -> Result<(), ManagedError> {
         if !output.is_empty() && !output.ends_with('\n') {
             output.push('\n');
         }
-        fs::write(&zshrc_path, output).map_err(ManagedError::WriteFile)?;
+        fs::write(&rc_path, output).map_err(ManagedError::WriteFile)?;
     }
 
     Ok(())
 }
 
-/// Check whether the managed directory is initialized.
-pub fn is_initialized() -> bool {
-    managed_dir()
-        .map(|base| base.join("init.zsh").is_file())
+/// Check whether the managed directory is initialized for the current shell.
+pub fn is_initialized(ctx: &Context) -> bool {
+    managed_dir(ctx)
+        .map(|base| base.join(init_file_name(ctx.shell)).is_file())
         .unwrap_or(false)
 }
 
@@ -136,9 +243,179 @@ pub fn shell_quote(s: &str) -> String {
     out
 }
 
-/// Format a single alias line: `alias name='command'`
-pub fn format_alias_line(name: &str, command: &str) -> String {
-    format!("alias {}={}", name, shell_quote(command))
+/// Format a single alias line: `alias name='command'`, with `-g`/`-s` for
+/// global and suffix aliases and `--` before names that look like options.
+/// Named directories become `hash -d name='/path'`.
+pub fn format_alias_line(name: &str, command: &str, kind: AliasKind) -> String {
+    if kind == AliasKind::NamedDir {
+        return format!("hash -d {}={}", name, shell_quote(command));
+    }
+    let mut line = String::from("alias ");
+    if let Some(flag) = kind.flag() {
+        line.push_str(flag);
+        line.push(' ');
+    }
+    if name.starts_with(['-', '+']) {
+        line.push_str("-- ");
+    }
+    line.push_str(name);
+    line.push('=');
+    line.push_str(&shell_quote(command));
+    line
+}
+
+/// Format the line that removes an alias: `unalias name 2>/dev/null`.
+/// Suffix aliases live in their own table and need `unalias -s`.
+pub fn format_unalias_line(name: &str, kind: AliasKind) -> String {
+    match kind {
+        AliasKind::Suffix => format!("unalias -s {name} 2>/dev/null"),
+        AliasKind::NamedDir => format!("unhash -d {name} 2>/dev/null"),
+        AliasKind::Function => format!("unfunction {name} 2>/dev/null"),
+        AliasKind::Regular | AliasKind::Global => format!("unalias {name} 2>/dev/null"),
+    }
+}
+
+/// Format a fish abbreviation: `abbr -a -- name 'command'`, with
+/// `--position anywhere` for global ones.
+pub fn format_abbr_line(name: &str, command: &str, kind: AliasKind) -> String {
+    let mut line = String::from("abbr -a ");
+    if kind == AliasKind::Global {
+        line.push_str("--position anywhere ");
+    }
+    line.push_str("-- ");
+    line.push_str(name);
+    line.push(' ');
+    line.push_str(&fish_quote(command));
+    line
+}
+
+/// Format the fish line that removes an abbreviation and any function
+/// `alias` created under the same name.
+pub fn format_abbr_erase_line(name: &str) -> String {
+    format!("abbr -e -- {name} 2>/dev/null; functions -e -- {name}")
+}
+
+/// fish single-quoting: only `\` and `'` need escaping inside quotes.
+fn fish_quote(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('\'');
+    for c in s.chars() {
+        if c == '\'' || c == '\\' {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out.push('\'');
+    out
+}
+
+/// The definition line for the current shell. A named directory's leading
+/// `~` is expanded since zsh won't expand it after `=` in an argument.
+pub fn definition_line(ctx: &Context, name: &str, command: &str, kind: AliasKind) -> String {
+    match ctx.shell {
+        Shell::Zsh | Shell::Bash if kind == AliasKind::NamedDir => {
+            format_alias_line(name, &expand_home(command, ctx.home()), kind)
+        }
+        Shell::Zsh | Shell::Bash => format_alias_line(name, command, kind),
+        Shell::Fish => format_abbr_line(name, command, kind),
+    }
+}
+
+/// The removal line for the current shell.
+fn removal_line(ctx: &Context, name: &str, kind: AliasKind) -> String {
+    match ctx.shell {
+        Shell::Zsh | Shell::Bash => format_unalias_line(name, kind),
+        Shell::Fish => format_abbr_erase_line(name),
+    }
+}
+
+/// `path` with a leading `~` or `~/` replaced by `home`.
+pub fn expand_home(path: &str, home: &Path) -> String {
+    match path.strip_prefix('~') {
+        Some(rest) if !home.as_os_str().is_empty() && (rest.is_empty() || rest.starts_with('/')) => {
+            format!("{}{rest}", home.display())
+        }
+        _ => path.to_string(),
+    }
+}
+
+/// Whether a line written by `format_alias_line` defines `name` in the same
+/// table as `kind`. Regular and global aliases share a table in zsh, so a
+/// global `foo` replaces a regular `foo`; suffix aliases are separate.
+fn defines_alias(line: &str, name: &str, kind: AliasKind) -> bool {
+    if line.starts_with("abbr ") {
+        return parser::parse_fish_definition(line)
+            .is_some_and(|(n, _, k)| n == name && k.same_table(kind));
+    }
+    if let Some(rest) = line.strip_prefix("hash -d ") {
+        return kind == AliasKind::NamedDir
+            && rest.strip_prefix(name).is_some_and(|r| r.starts_with('='));
+    }
+    let Some(mut rest) = line.strip_prefix("alias ") else {
+        return false;
+    };
+    let mut line_kind = AliasKind::Regular;
+    if let Some(r) = rest.strip_prefix("-g ") {
+        line_kind = AliasKind::Global;
+        rest = r;
+    } else if let Some(r) = rest.strip_prefix("-s ") {
+        line_kind = AliasKind::Suffix;
+        rest = r;
+    }
+    let rest = rest.strip_prefix("-- ").unwrap_or(rest);
+    line_kind.same_table(kind) && rest.strip_prefix(name).is_some_and(|r| r.starts_with('='))
+}
+
+/// `content` with the definition of `name` in `kind`'s table replaced by
+/// `replacement`, and the lines taken out for it, empty if there was no
+/// definition. The comment block right above a definition is its
+/// description, so it goes too; later duplicate definitions are dropped
+/// along with theirs.
+fn replace_definition(
+    content: &str,
+    name: &str,
+    kind: AliasKind,
+    replacement: &[String],
+) -> (Vec<String>, Vec<String>) {
+    let mut lines: Vec<String> = Vec::new();
+    let mut removed: Vec<String> = Vec::new();
+    for line in content.lines() {
+        if !defines_alias(line, name, kind) {
+            lines.push(line.to_string());
+            continue;
+        }
+        let mut block = vec![line.to_string()];
+        while lines.last().is_some_and(|l| is_comment(l)) {
+            block.push(lines.pop().unwrap());
+        }
+        if removed.is_empty() {
+            block.reverse();
+            removed = block;
+            lines.extend(replacement.iter().cloned());
+        }
+    }
+    (lines, removed)
+}
+
+/// A comment line, other than a definition zam disabled.
+fn is_comment(line: &str) -> bool {
+    line.trim_start().starts_with('#') && parser::disabled_definition(line).is_none()
+}
+
+/// The definition line with its description as a comment above it.
+fn described_lines(
+    ctx: &Context,
+    name: &str,
+    command: &str,
+    kind: AliasKind,
+    description: Option<&str>,
+) -> Vec<String> {
+    let mut lines = Vec::new();
+    if let Some(text) = description.map(str::trim).filter(|t| !t.is_empty()) {
+        lines.push(format!("# {text}"));
+    }
+    lines.push(definition_line(ctx, name, command, kind));
+    lines
 }
 
 /// Generate a slug from an AliasSource for override filenames.
@@ -151,52 +428,95 @@ pub fn source_slug(source: &AliasSource) -> String {
         AliasSource::OhMyZshCustom(name) => {
             format!("oh-my-zsh-custom-{}", name.trim_end_matches(".zsh"))
         }
+        AliasSource::Plugin { manager, name } => {
+            format!("{}-{}", manager.name(), name.replace(['/', ':'], "-"))
+        }
         AliasSource::Zshrc => "zshrc".to_string(),
+        AliasSource::Bashrc => "bashrc".to_string(),
+        AliasSource::BashAliases => "bash-aliases".to_string(),
+        AliasSource::SystemBashrc => "system-bashrc".to_string(),
+        AliasSource::FishConfig => "fish-config".to_string(),
+        AliasSource::FishConfD(name) => {
+            format!("fish-conf-d-{}", name.trim_end_matches(".fish"))
+        }
+        AliasSource::FishUniversal => "fish-universal".to_string(),
         AliasSource::ZamExtension(name) => format!("ext-{name}"),
         AliasSource::ZamCustom => "custom".to_string(),
         AliasSource::ZamOverride(slug) => slug.clone(),
+        AliasSource::NamedDirs => "named-dirs".to_string(),
+        AliasSource::ZamDirs => "dirs".to_string(),
         AliasSource::Unknown => "unknown".to_string(),
     }
 }
 
-fn custom_file_path() -> Result<PathBuf, ManagedError> {
-    let base = managed_dir().ok_or(ManagedError::NotInitialized)?;
-    Ok(base.join("aliases/custom.zsh"))
+/// The source of entries created in the TUI: `ZamDirs` for named
+/// directories, `ZamCustom` for everything else.
+pub fn custom_source(kind: AliasKind) -> AliasSource {
+    if kind == AliasKind::NamedDir {
+        AliasSource::ZamDirs
+    } else {
+        AliasSource::ZamCustom
+    }
+}
+
+fn custom_file_path(ctx: &Context, kind: AliasKind) -> Result<PathBuf, ManagedError> {
+    let base = managed_dir(ctx).ok_or(ManagedError::NotInitialized)?;
+    let shell = ctx.shell;
+    if kind == AliasKind::NamedDir {
+        Ok(base.join("dirs/custom.zsh"))
+    } else if shell == Shell::Fish {
+        Ok(ctx.paths.rc_file(shell))
+    } else {
+        Ok(base.join(format!("aliases/custom.{}", shell.file_ext())))
+    }
+}
+
+fn override_file_path(ctx: &Context, slug: &str) -> Result<PathBuf, ManagedError> {
+    let base = managed_dir(ctx).ok_or(ManagedError::NotInitialized)?;
+    Ok(base.join(format!("overrides/{slug}.{}", ctx.shell.file_ext())))
 }
 
-fn override_file_path(slug: &str) -> Result<PathBuf, ManagedError> {
-    let base = managed_dir().ok_or(ManagedError::NotInitialized)?;
-    Ok(base.join(format!("overrides/{slug}.zsh")))
+fn check_kind_supported(ctx: &Context, kind: AliasKind) -> Result<(), ManagedError> {
+    let shell = ctx.shell;
+    if kind.supported_by(shell) {
+        Ok(())
+    } else {
+        Err(ManagedError::UnsupportedKind(kind, shell))
+    }
 }
 
-/// Write (append or update) a custom alias to custom.zsh.
-pub fn write_custom_alias(name: &str, command: &str) -> Result<(), ManagedError> {
-    if !is_initialized() {
+/// Write (append or update) a custom alias to the shell's custom file
+/// (custom.zsh or custom.bash), or a named directory to dirs/custom.zsh.
+/// A description is written as a comment on the line above.
+pub fn write_custom_alias(
+    ctx: &Context,
+    name: &str,
+    command: &str,
+    kind: AliasKind,
+    description: Option<&str>,
+) -> Result<(), ManagedError> {
+    if !is_initialized(ctx) {
         return Err(ManagedError::NotInitialized);
     }
     validate_alias_name(name)?;
+    check_kind_supported(ctx, kind)?;
 
-    let path = custom_file_path()?;
-    let new_line = format_alias_line(name, command);
-
-    let content = fs::read_to_string(&path).unwrap_or_default();
-    let prefix = format!("alias {name}=");
-
-    let mut found = false;
-    let mut lines: Vec<String> = content
-        .lines()
-        .map(|line| {
-            if line.starts_with(&prefix) {
-                found = true;
-                new_line.clone()
-            } else {
-                line.to_string()
-            }
-        })
-        .collect();
+    let path = custom_file_path(ctx, kind)?;
+    // dirs/ is newer than `zam --init` on existing installs
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(ManagedError::CreateDir)?;
+    }
+    let new_lines = described_lines(ctx, name, command, kind, description);
+    write_definition(&path, name, kind, &new_lines)
+}
 
-    if !found {
-        lines.push(new_line);
+/// Replace the definition of `name` in the file at `path` with
+/// `new_lines`, or append them.
+fn write_definition(path: &Path, name: &str, kind: AliasKind, new_lines: &[String]) -> Result<(), ManagedError> {
+    let content = fs::read_to_string(path).unwrap_or_default();
+    let (mut lines, removed) = replace_definition(&content, name, kind, new_lines);
+    if removed.is_empty() {
+        lines.extend(new_lines.iter().cloned());
     }
 
     let mut output = lines.join("\n");
@@ -204,44 +524,37 @@ pub fn write_custom_alias(name: &str, command: &str) -> Result<(), ManagedError>
         output.push('\n');
     }
 
-    fs::write(&path, output).map_err(ManagedError::WriteFile)
+    fs::write(path, output).map_err(ManagedError::WriteFile)
 }
 
-/// Write an override alias to the appropriate override file.
+/// Write an override alias to the appropriate override file, with its
+/// description as a comment above it.
 pub fn write_override(
+    ctx: &Context,
     name: &str,
     command: &str,
+    kind: AliasKind,
     source: &AliasSource,
+    description: Option<&str>,
 ) -> Result<(), ManagedError> {
-    if !is_initialized() {
+    if !is_initialized(ctx) {
         return Err(ManagedError::NotInitialized);
     }
     validate_alias_name(name)?;
+    check_kind_supported(ctx, kind)?;
 
     let slug = source_slug(source);
-    let path = override_file_path(&slug)?;
-    let new_line = format_alias_line(name, command);
+    let path = override_file_path(ctx, &slug)?;
+    let new_lines = described_lines(ctx, name, command, kind, description);
 
     let content = fs::read_to_string(&path).unwrap_or_default();
-    let prefix = format!("alias {name}=");
-    let unalias_line = format!("unalias {name} 2>/dev/null");
-
-    let mut found = false;
-    let mut lines: Vec<String> = content
-        .lines()
-        .filter(|line| line.trim() != unalias_line) // remove any existing unalias
-        .map(|line| {
-            if line.starts_with(&prefix) {
-                found = true;
-                new_line.clone()
-            } else {
-                line.to_string()
-            }
-        })
-        .collect();
+    let unalias_line = removal_line(ctx, name, kind);
+    // Remove any existing unalias
+    let content: Vec<&str> = content.lines().filter(|line| line.trim() != unalias_line).collect();
 
-    if !found {
-        lines.push(new_line);
+    let (mut lines, removed) = replace_definition(&content.join("\n"), name, kind, &new_lines);
+    if removed.is_empty() {
+        lines.extend(new_lines);
     }
 
     let mut output = lines.join("\n");
@@ -252,20 +565,16 @@ pub fn write_override(
     fs::write(&path, output).map_err(ManagedError::WriteFile)
 }
 
-/// Delete a custom alias from custom.zsh.
-pub fn delete_custom_alias(name: &str) -> Result<(), ManagedError> {
-    if !is_initialized() {
+/// Delete a custom alias from the shell's custom file, or a named directory
+/// from dirs/custom.zsh.
+pub fn delete_custom_alias(ctx: &Context, name: &str, kind: AliasKind) -> Result<(), ManagedError> {
+    if !is_initialized(ctx) {
         return Err(ManagedError::NotInitialized);
     }
 
-    let path = custom_file_path()?;
+    let path = custom_file_path(ctx, kind)?;
     let content = fs::read_to_string(&path).unwrap_or_default();
-    let prefix = format!("alias {name}=");
-
-    let lines: Vec<&str> = content
-        .lines()
-        .filter(|line| !line.starts_with(&prefix))
-        .collect();
+    let (lines, _) = replace_definition(&content, name, kind, &[]);
 
     let mut output = lines.join("\n");
     if !output.is_empty() && !output.ends_with('\n') {
@@ -275,34 +584,91 @@ pub fn delete_custom_alias(name: &str) -> Result<(), ManagedError> {
     fs::write(&path, output).map_err(ManagedError::WriteFile)
 }
 
-fn extension_file_path(name: &str) -> Result<PathBuf, ManagedError> {
-    let base = managed_dir().ok_or(ManagedError::NotInitialized)?;
-    Ok(base.join(format!("aliases/{name}.zsh")))
+fn extension_file_path(ctx: &Context, name: &str) -> Result<PathBuf, ManagedError> {
+    let base = managed_dir(ctx).ok_or(ManagedError::NotInitialized)?;
+    Ok(base.join(format!("aliases/{name}.{}", ctx.shell.file_ext())))
 }
 
 /// Write an extension alias file with all aliases and comments.
 pub fn install_extension(
+    ctx: &Context,
     name: &str,
     aliases: &[(&str, &str, &str)],
 ) -> Result<(), ManagedError> {
-    if !is_initialized() {
+    if !is_initialized(ctx) {
         return Err(ManagedError::NotInitialized);
     }
 
-    let path = extension_file_path(name)?;
+    let path = extension_file_path(ctx, name)?;
     let mut output = format!("# zam extension: {name}\n\n");
     for &(alias_name, command, comment) in aliases {
         output.push_str(&format!("# {comment}\n"));
-        output.push_str(&format_alias_line(alias_name, command));
+        output.push_str(&definition_line(ctx, alias_name, command, AliasKind::Regular));
         output.push('\n');
     }
 
     fs::write(&path, output).map_err(ManagedError::WriteFile)
 }
 
+/// The zam file an alias from `source` is defined in: the custom file or
+/// an extension's file. Other sources can only be overridden.
+fn managed_alias_file(ctx: &Context, source: &AliasSource, kind: AliasKind) -> Option<PathBuf> {
+    match source {
+        AliasSource::ZamCustom | AliasSource::ZamDirs => custom_file_path(ctx, kind).ok(),
+        AliasSource::ZamExtension(name) => extension_file_path(ctx, name).ok(),
+        _ => None,
+    }
+}
+
+/// Move an alias, with its description, from the zam file it is defined
+/// in to `to`'s file (the custom file or an installed extension). The
+/// lines move as they are.
+pub fn move_alias(ctx: &Context, alias: &Alias, to: &AliasSource) -> Result<(), ManagedError> {
+    if !is_initialized(ctx) {
+        return Err(ManagedError::NotInitialized);
+    }
+    let from = managed_alias_file(ctx, &alias.source, alias.kind)
+        .ok_or_else(|| ManagedError::NotManaged(alias.name.clone()))?;
+    let to = managed_alias_file(ctx, to, alias.kind)
+        .ok_or_else(|| ManagedError::NotManaged(to.display_name()))?;
+    if from == to {
+        return Ok(());
+    }
+
+    let content = fs::read_to_string(&from).map_err(ManagedError::ReadFile)?;
+    let (lines, removed) = replace_definition(&content, &alias.name, alias.kind, &[]);
+    if removed.is_empty() {
+        return Err(ManagedError::NotManaged(alias.name.clone()));
+    }
+    write_definition(&to, &alias.name, alias.kind, &removed)?;
+
+    let mut output = lines.join("\n");
+    if !output.is_empty() && !output.ends_with('\n') {
+        output.push('\n');
+    }
+    fs::write(&from, output).map_err(ManagedError::WriteFile)
+}
+
+/// Write `aliases` with their descriptions to ~/.config/zam/export.zsh
+/// (or .bash, .fish), replacing any earlier export, and return its path.
+/// The file isn't sourced; copy it wherever the aliases should go.
+pub fn export_aliases(ctx: &Context, aliases: &[Alias]) -> Result<PathBuf, ManagedError> {
+    let base = managed_dir(ctx).ok_or(ManagedError::NotInitialized)?;
+    let path = base.join(format!("export.{}", ctx.shell.file_ext()));
+    let mut output = String::from("# zam export\n\n");
+    for alias in aliases {
+        for line in described_lines(ctx, &alias.name, &alias.command, alias.kind, alias.description.as_deref()) {
+            output.push_str(&line);
+            output.push('\n');
+        }
+    }
+    fs::write(&path, output).map_err(ManagedError::WriteFile)?;
+    Ok(path)
+}
+
 /// Remove an installed extension by deleting its alias file.
-pub fn remove_extension(name: &str) -> Result<(), ManagedError> {
-    let path = extension_file_path(name)?;
+pub fn remove_extension(ctx: &Context, name: &str) -> Result<(), ManagedError> {
+    let path = extension_file_path(ctx, name)?;
     if path.is_file() {
         fs::remove_file(&path).map_err(ManagedError::WriteFile)?;
     }
@@ -310,21 +676,26 @@ pub fn remove_extension(name: &str) -> Result<(), ManagedError> {
 }
 
 /// Check whether an extension is installed.
-pub fn is_extension_installed(name: &str) -> bool {
-    extension_file_path(name)
+pub fn is_extension_installed(ctx: &Context, name: &str) -> bool {
+    extension_file_path(ctx, name)
         .map(|p| p.is_file())
         .unwrap_or(false)
 }
 
 /// Delete a plugin/system alias by writing `unalias` to the override file.
-pub fn delete_override(name: &str, source: &AliasSource) -> Result<(), ManagedError> {
-    if !is_initialized() {
+pub fn delete_override(
+    ctx: &Context,
+    name: &str,
+    kind: AliasKind,
+    source: &AliasSource,
+) -> Result<(), ManagedError> {
+    if !is_initialized(ctx) {
         return Err(ManagedError::NotInitialized);
     }
 
     let slug = source_slug(source);
-    let path = override_file_path(&slug)?;
-    let unalias_line = format!("unalias {name} 2>/dev/null");
+    let path = override_file_path(ctx, &slug)?;
+    let unalias_line = removal_line(ctx, name, kind);
 
     let content = fs::read_to_string(&path).unwrap_or_default();
 
@@ -334,13 +705,8 @@ pub fn delete_override(name: &str, source: &AliasSource) -> Result<(), ManagedEr
     }
 
     // Also remove any existing override alias for this name
-    let prefix = format!("alias {name}=");
-    let mut lines: Vec<&str> = content
-        .lines()
-        .filter(|line| !line.starts_with(&prefix))
-        .collect();
-
-    lines.push(&unalias_line);
+    let (mut lines, _) = replace_definition(&content, name, kind, &[]);
+    lines.push(unalias_line);
 
     let mut output = lines.join("\n");
     if !output.ends_with('\n') {
@@ -349,3 +715,231 @@ pub fn delete_override(name: &str, source: &AliasSource) -> Result<(), ManagedEr
 
     fs::write(&path, output).map_err(ManagedError::WriteFile)
 }
+
+/// Disable an alias without losing its definition. In a zam file the
+/// definition line is commented out with `#zam-disabled`; other aliases
+/// get the marked definition and an unalias in their override file.
+pub fn disable_alias(ctx: &Context, alias: &Alias) -> Result<(), ManagedError> {
+    if !is_initialized(ctx) {
+        return Err(ManagedError::NotInitialized);
+    }
+    let (name, kind) = (alias.name.as_str(), alias.kind);
+
+    let (path, lines) = if let Some(path) = managed_alias_file(ctx, &alias.source, kind) {
+        let content = fs::read_to_string(&path).map_err(ManagedError::ReadFile)?;
+        let mut found = false;
+        let lines: Vec<String> = content
+            .lines()
+            .map(|line| {
+                if !found && defines_alias(line, name, kind) {
+                    found = true;
+                    format!("{}{line}", parser::DISABLED_MARKER)
+                } else {
+                    line.to_string()
+                }
+            })
+            .collect();
+        if !found {
+            return Err(ManagedError::NotManaged(name.to_string()));
+        }
+        (path, lines)
+    } else {
+        let path = override_file_path(ctx, &source_slug(&alias.source))?;
+        let content = fs::read_to_string(&path).unwrap_or_default();
+        let unalias_line = removal_line(ctx, name, kind);
+        let content: Vec<&str> = content.lines().filter(|line| line.trim() != unalias_line).collect();
+
+        // An override definition is what's live, so that is what to keep
+        let (mut lines, mut removed) = replace_definition(&content.join("\n"), name, kind, &[]);
+        if removed.is_empty() {
+            removed.push(definition_line(ctx, name, &alias.command, kind));
+        }
+        if let Some(definition) = removed.last_mut() {
+            definition.insert_str(0, parser::DISABLED_MARKER);
+        }
+        lines.extend(removed);
+        lines.push(unalias_line);
+        (path, lines)
+    };
+    write_lines(&path, &lines)
+}
+
+/// Re-enable an alias `disable_alias` disabled.
+pub fn enable_alias(ctx: &Context, alias: &Alias) -> Result<(), ManagedError> {
+    if !is_initialized(ctx) {
+        return Err(ManagedError::NotInitialized);
+    }
+    let (name, kind) = (alias.name.as_str(), alias.kind);
+    let path = match managed_alias_file(ctx, &alias.source, kind) {
+        Some(path) => path,
+        None => override_file_path(ctx, &source_slug(&alias.source))?,
+    };
+
+    let content = fs::read_to_string(&path).map_err(ManagedError::ReadFile)?;
+    let unalias_line = removal_line(ctx, name, kind);
+    let mut found = false;
+    let mut lines = Vec::new();
+    for line in content.lines() {
+        if line.trim() == unalias_line {
+            continue;
+        }
+        match parser::disabled_definition(line) {
+            Some(definition) if !found && defines_alias(definition, name, kind) => {
+                found = true;
+                lines.push(definition.to_string());
+            }
+            _ => lines.push(line.to_string()),
+        }
+    }
+    if !found {
+        return Err(ManagedError::NotManaged(name.to_string()));
+    }
+    write_lines(&path, &lines)
+}
+
+/// Tags by alias name, from ~/.config/zam/tags: one alias per line, its
+/// name then its tags. Tags are zam's own, so no shell file holds them.
+pub fn read_tags(ctx: &Context) -> BTreeMap<String, Vec<String>> {
+    let Some(base) = managed_dir(ctx) else {
+        return BTreeMap::new();
+    };
+    let content = fs::read_to_string(base.join("tags")).unwrap_or_default();
+    content
+        .lines()
+        .filter(|line| !line.trim_start().starts_with('#'))
+        .filter_map(|line| {
+            let mut words = line.split_whitespace().map(str::to_string);
+            Some((words.next()?, words.collect()))
+        })
+        .collect()
+}
+
+fn write_tags(ctx: &Context, tags: &BTreeMap<String, Vec<String>>) -> Result<(), ManagedError> {
+    if !is_initialized(ctx) {
+        return Err(ManagedError::NotInitialized);
+    }
+    let base = managed_dir(ctx).ok_or(ManagedError::NotInitialized)?;
+    let mut lines = vec!["# zam tags: an alias name, then its tags".to_string()];
+    lines.extend(
+        tags.iter()
+            .filter(|(_, tags)| !tags.is_empty())
+            .map(|(name, tags)| format!("{name} {}", tags.join(" "))),
+    );
+    write_lines(&base.join("tags"), &lines)
+}
+
+/// A tag as written: one word, with any leading `#` dropped.
+pub fn validate_tag(tag: &str) -> Result<&str, ManagedError> {
+    let tag = tag.trim().trim_start_matches('#');
+    if tag.is_empty() || tag.contains(|c: char| c.is_whitespace() || c == '#') {
+        return Err(ManagedError::InvalidTag(tag.to_string()));
+    }
+    Ok(tag)
+}
+
+/// Add `tag` to the alias `name`.
+pub fn tag_alias(ctx: &Context, name: &str, tag: &str) -> Result<(), ManagedError> {
+    validate_alias_name(name)?;
+    let tag = validate_tag(tag)?;
+    let mut tags = read_tags(ctx);
+    let entry = tags.entry(name.to_string()).or_default();
+    if !entry.iter().any(|t| t == tag) {
+        entry.push(tag.to_string());
+    }
+    write_tags(ctx, &tags)
+}
+
+/// Remove `tag` from the alias `name`, or all its tags.
+pub fn untag_alias(ctx: &Context, name: &str, tag: Option<&str>) -> Result<(), ManagedError> {
+    let mut tags = read_tags(ctx);
+    match tag {
+        Some(tag) => {
+            let tag = validate_tag(tag)?;
+            if let Some(entry) = tags.get_mut(name) {
+                entry.retain(|t| t != tag);
+            }
+        }
+        None => {
+            tags.remove(name);
+        }
+    }
+    write_tags(ctx, &tags)
+}
+
+/// Carry an alias's tags over to its new name.
+pub fn rename_tags(ctx: &Context, from: &str, to: &str) -> Result<(), ManagedError> {
+    let mut tags = read_tags(ctx);
+    let Some(moved) = tags.remove(from) else {
+        return Ok(());
+    };
+    tags.insert(to.to_string(), moved);
+    write_tags(ctx, &tags)
+}
+
+fn write_lines(path: &Path, lines: &[String]) -> Result<(), ManagedError> {
+    let mut output = lines.join("\n");
+    if !output.is_empty() && !output.ends_with('\n') {
+        output.push('\n');
+    }
+    fs::write(path, output).map_err(ManagedError::WriteFile)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_alias_line_kinds() {
+        assert_eq!(
+            format_alias_line("gst", "git status", AliasKind::Regular),
+            "alias gst='git status'"
+        );
+        assert_eq!(
+            format_alias_line("G", "| grep", AliasKind::Global),
+            "alias -g G='| grep'"
+        );
+        assert_eq!(
+            format_alias_line("pdf", "zathura", AliasKind::Suffix),
+            "alias -s pdf='zathura'"
+        );
+        assert_eq!(
+            format_alias_line("-", "cd -", AliasKind::Regular),
+            "alias -- -='cd -'"
+        );
+        assert_eq!(
+            format_alias_line("proj", "/src/proj", AliasKind::NamedDir),
+            "hash -d proj='/src/proj'"
+        );
+        assert_eq!(
+            format_unalias_line("proj", AliasKind::NamedDir),
+            "unhash -d proj 2>/dev/null"
+        );
+    }
+
+    #[test]
+    fn test_defines_alias_tables() {
+        assert!(defines_alias("alias G='| grep'", "G", AliasKind::Global));
+        assert!(defines_alias("alias -g G='| grep'", "G", AliasKind::Regular));
+        assert!(!defines_alias("alias -s G='| grep'", "G", AliasKind::Regular));
+        assert!(defines_alias("alias -s pdf='zathura'", "pdf", AliasKind::Suffix));
+        assert!(!defines_alias("alias gst='git status'", "gs", AliasKind::Regular));
+        assert!(defines_alias("alias -- -='cd -'", "-", AliasKind::Regular));
+        assert!(defines_alias("hash -d proj='/src'", "proj", AliasKind::NamedDir));
+        assert!(!defines_alias("hash -d proj='/src'", "proj", AliasKind::Regular));
+        assert!(!defines_alias("alias proj='cd /src'", "proj", AliasKind::NamedDir));
+    }
+
+    #[test]
+    fn test_format_abbr_line_round_trip() {
+        let line = format_abbr_line("q", "echo 'hi' \\o/", AliasKind::Regular);
+        assert_eq!(line, "abbr -a -- q 'echo \\'hi\\' \\\\o/'");
+        assert_eq!(
+            parser::parse_fish_definition(&line),
+            Some(("q".into(), "echo 'hi' \\o/".into(), AliasKind::Regular))
+        );
+        let global = format_abbr_line("L", "| less", AliasKind::Global);
+        assert_eq!(global, "abbr -a --position anywhere -- L '| less'");
+        assert!(defines_alias(&global, "L", AliasKind::Regular));
+        assert!(!defines_alias(&global, "Le", AliasKind::Regular));
+    }
+}
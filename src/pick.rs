@@ -0,0 +1,107 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! `zam pick`: a few lines under the prompt for searching the aliases and
+//! printing the chosen name, for the zsh widget that inserts it at the
+//! cursor. The search is the TUI's, so `=command`, `@group` and `#tag`
+//! work here too.
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+use crate::action::Action;
+use crate::alias::types::{Alias, AliasGroup};
+use crate::app::{AppState, ListItem};
+use crate::context::Context;
+
+/// Rows the picker takes: the query line and the matches under it.
+pub const HEIGHT: u16 = 10;
+
+pub struct Picker {
+    /// Holds the aliases and the query; only its search is used.
+    pub app: AppState,
+    /// Index into `matches()`.
+    pub selected: usize,
+    pub scroll: usize,
+    /// Set by Enter, which also fills in `app.picked`, and by Esc.
+    pub done: bool,
+}
+
+impl Picker {
+    pub fn new(groups: Vec<AliasGroup>, ctx: Context, query: &str) -> Picker {
+        let mut app = AppState::new(groups, ctx);
+        app.search_query = query.to_string();
+        app.rebuild_visible();
+        Picker {
+            app,
+            selected: 0,
+            scroll: 0,
+            done: false,
+        }
+    }
+
+    /// The aliases the query matches, in list order without the headers.
+    pub fn matches(&self) -> Vec<&Alias> {
+        self.app
+            .visible_items
+            .iter()
+            .filter_map(|item| match item {
+                ListItem::AliasEntry { alias, .. } => Some(alias),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Typing edits the query; Up/Down (or Ctrl-P/Ctrl-N) move, Enter
+    /// picks and Esc, Ctrl-C or Ctrl-G give up.
+    pub fn key(&mut self, key: KeyEvent) {
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        match key.code {
+            KeyCode::Enter => {
+                self.app.picked = self.matches().get(self.selected).map(|a| a.name.clone());
+                self.done = true;
+            }
+            KeyCode::Esc => self.done = true,
+            KeyCode::Char('c' | 'g') if ctrl => self.done = true,
+            KeyCode::Up => self.move_selection(-1),
+            KeyCode::Char('p') if ctrl => self.move_selection(-1),
+            KeyCode::Down => self.move_selection(1),
+            KeyCode::Char('n') if ctrl => self.move_selection(1),
+            KeyCode::Backspace => self.search(Action::SearchBackspace),
+            KeyCode::Char('u') if ctrl => self.search(Action::SearchClear),
+            KeyCode::Char(c) if !ctrl => self.search(Action::SearchInput(c)),
+            _ => {}
+        }
+    }
+
+    fn search(&mut self, action: Action) {
+        self.app.handle_action(action);
+        self.selected = 0;
+        self.scroll = 0;
+    }
+
+    fn move_selection(&mut self, delta: isize) {
+        let last = self.matches().len().saturating_sub(1);
+        self.selected = self.selected.saturating_add_signed(delta).min(last);
+    }
+}
+
+/// The row and column in a terminal's reply to `ESC [ 6 n`, which is
+/// `ESC [ row ; col R`, 1-based; returned 0-based.
+pub fn parse_cursor_report(reply: &[u8]) -> Option<(u16, u16)> {
+    let text = std::str::from_utf8(reply).ok()?;
+    let start = text.rfind("\x1b[")?;
+    let (row, col) = text[start + 2..].strip_suffix('R')?.split_once(';')?;
+    Some((row.parse::<u16>().ok()?.checked_sub(1)?, col.parse::<u16>().ok()?.checked_sub(1)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_cursor_report() {
+        assert_eq!(parse_cursor_report(b"\x1b[12;5R"), Some((11, 4)));
+        assert_eq!(parse_cursor_report(b"x\x1b[1;1R"), Some((0, 0)));
+        assert_eq!(parse_cursor_report(b"\x1b[12;5"), None);
+        assert_eq!(parse_cursor_report(b"\x1b[0;1R"), None);
+    }
+}
@@ -0,0 +1,78 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Running an alias from the TUI, in a subshell.
+
+use std::process::Stdio;
+use std::time::Duration;
+
+use crate::alias::collector::{wait_with_timeout, Drain};
+use crate::alias::types::{Alias, AliasKind};
+use crate::context::Context;
+use crate::shell::Shell;
+
+/// How long a command may run before it is killed.
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+/// What a run printed and how it ended.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Output {
+    pub stdout: String,
+    pub stderr: String,
+    /// `exit 0`, `killed after 30s`, or why the shell didn't start.
+    pub outcome: String,
+}
+
+/// The command line running `alias` with `args`: its name, so the shell
+/// expands it as at the prompt. fish abbreviations only expand when
+/// typed, so they run as their command.
+pub fn command_line(ctx: &Context, alias: &Alias, args: &str) -> String {
+    let word = match (ctx.shell, alias.kind) {
+        (Shell::Fish, kind) if kind != AliasKind::Function => alias.command.as_str(),
+        _ => alias.name.as_str(),
+    };
+    match args.trim() {
+        "" => word.to_string(),
+        args => format!("{word} {args}"),
+    }
+}
+
+/// Run `command_line` in an interactive `ctx.shell`, so the rc files
+/// define its aliases and functions. stdin is /dev/null, and the shell
+/// is killed after `TIMEOUT`.
+pub fn run(ctx: &Context, command_line: &str) -> Output {
+    let mut cmd = ctx.shell_command();
+    cmd.args(["-i", "-c", command_line])
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    let mut child = match cmd.spawn() {
+        Ok(child) => child,
+        Err(e) => {
+            return Output {
+                stdout: String::new(),
+                stderr: String::new(),
+                outcome: format!("could not start {}: {e}", ctx.shell),
+            }
+        }
+    };
+    let stdout = Drain::spawn(child.stdout.take());
+    let stderr = Drain::spawn(child.stderr.take());
+
+    let outcome = match wait_with_timeout(&mut child, TIMEOUT) {
+        Ok(Some(status)) => match status.code() {
+            Some(code) => format!("exit {code}"),
+            None => "killed by a signal".to_string(),
+        },
+        Ok(None) => {
+            let _ = child.kill();
+            let _ = child.wait();
+            format!("killed after {}s", TIMEOUT.as_secs())
+        }
+        Err(e) => e.to_string(),
+    };
+    Output {
+        stdout: String::from_utf8_lossy(&stdout.finish()).into_owned(),
+        stderr: String::from_utf8_lossy(&stderr.finish()).into_owned(),
+        outcome,
+    }
+}
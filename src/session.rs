@@ -0,0 +1,99 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! TUI state kept between runs in `~/.local/state/zam/session`.
+
+use std::fmt;
+use std::fs;
+use std::io;
+
+use crate::app::ListMode;
+use crate::context::Context;
+
+const SESSION_FILE: &str = "session";
+
+/// What the TUI restores on startup. The file holds one `key value` line
+/// per setting; unknown keys and bad values are ignored, so an old or
+/// damaged file only loses what it can't express.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Session {
+    pub list_mode: ListMode,
+    pub search: String,
+    /// Collapsed groups, by `GroupKey::name()`.
+    pub collapsed: Vec<String>,
+    /// The selected alias's name and source display name.
+    pub selected: Option<(String, String)>,
+}
+
+impl Session {
+    /// The saved session, or the defaults if there is none.
+    pub fn load(ctx: &Context) -> Session {
+        if ctx.state_dir.as_os_str().is_empty() {
+            return Session::default();
+        }
+        fs::read_to_string(ctx.state_dir.join(SESSION_FILE))
+            .map(|text| Session::parse(&text))
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, ctx: &Context) -> io::Result<()> {
+        if ctx.state_dir.as_os_str().is_empty() {
+            return Ok(());
+        }
+        fs::create_dir_all(&ctx.state_dir)?;
+        fs::write(ctx.state_dir.join(SESSION_FILE), self.to_string())
+    }
+
+    fn parse(text: &str) -> Session {
+        let mut session = Session::default();
+        for line in text.lines() {
+            let (key, value) = line.split_once(' ').unwrap_or((line, ""));
+            match key {
+                "list-mode" => {
+                    if let Some(mode) = ListMode::from_name(value) {
+                        session.list_mode = mode;
+                    }
+                }
+                "search" => session.search = value.to_string(),
+                "collapsed" if !value.is_empty() => session.collapsed.push(value.to_string()),
+                "selected" => {
+                    if let Some((name, source)) = value.split_once(' ') {
+                        session.selected = Some((name.to_string(), source.to_string()));
+                    }
+                }
+                _ => {}
+            }
+        }
+        session
+    }
+}
+
+impl fmt::Display for Session {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "list-mode {}", self.list_mode.name())?;
+        writeln!(f, "search {}", self.search)?;
+        for group in &self.collapsed {
+            writeln!(f, "collapsed {group}")?;
+        }
+        if let Some((name, source)) = &self.selected {
+            writeln!(f, "selected {name} {source}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let session = Session {
+            list_mode: ListMode::ByUsage,
+            search: "=git ".to_string(),
+            collapsed: vec!["source oh-my-zsh plugin: git".to_string(), "untagged".to_string()],
+            selected: Some(("gst".to_string(), "oh-my-zsh plugin: git".to_string())),
+        };
+        assert_eq!(Session::parse(&session.to_string()), session);
+        assert_eq!(Session::parse("list-mode sideways\nfuture-key 1\n"), Session::default());
+    }
+}
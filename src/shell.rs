@@ -0,0 +1,204 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use std::path::{Path, PathBuf};
+
+/// The shells zam can manage. Everything that differs between them —
+/// how aliases are collected, which rc files are scanned, what the managed
+/// files look like — branches on this enum.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Shell {
+    Zsh,
+    Bash,
+    Fish,
+}
+
+impl Shell {
+    pub fn from_name(name: &str) -> Option<Shell> {
+        match name {
+            "zsh" => Some(Shell::Zsh),
+            "bash" => Some(Shell::Bash),
+            "fish" => Some(Shell::Fish),
+            _ => None,
+        }
+    }
+
+    /// Pick the shell from `$SHELL`, falling back to zsh.
+    pub fn detect() -> Shell {
+        std::env::var("SHELL")
+            .ok()
+            .and_then(|s| {
+                let name = Path::new(&s).file_name()?.to_str()?.to_string();
+                Shell::from_name(&name)
+            })
+            .unwrap_or(Shell::Zsh)
+    }
+
+    /// Executable name, also used in messages.
+    pub fn name(self) -> &'static str {
+        match self {
+            Shell::Zsh => "zsh",
+            Shell::Bash => "bash",
+            Shell::Fish => "fish",
+        }
+    }
+
+    /// Extension of zam's managed files for this shell.
+    pub fn file_ext(self) -> &'static str {
+        self.name()
+    }
+
+    /// The per-user file `zam --init` adds its source line to, relative to
+    /// the home directory. fish has no single rc file that zam should edit,
+    /// so it gets a zam-owned snippet in conf.d/, which fish loads on its own.
+    /// zsh's moves with `$ZDOTDIR`; see `ShellPaths::rc_file`.
+    pub fn rc_file(self) -> &'static str {
+        match self {
+            Shell::Zsh => ".zshrc",
+            Shell::Bash => ".bashrc",
+            Shell::Fish => ".config/fish/conf.d/zam.fish",
+        }
+    }
+
+    /// Command that prints what kind of word `name` is: `whence -w` in zsh
+    /// (`name: command`), `type -t` in bash and fish (`file`).
+    pub fn type_query(self, quoted_name: &str) -> String {
+        match self {
+            Shell::Zsh => format!("whence -w {quoted_name}"),
+            Shell::Bash | Shell::Fish => format!("type -t {quoted_name}"),
+        }
+    }
+}
+
+impl std::fmt::Display for Shell {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Locations that zsh configuration can move with environment variables.
+/// Defaults come from zam's own environment and are replaced by what the
+/// interactive shell reports when aliases are collected.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShellPaths {
+    pub home: PathBuf,
+    /// `$ZDOTDIR`: where zsh reads .zshrc from.
+    pub zdotdir: PathBuf,
+    /// `$ZSH`: the oh-my-zsh checkout.
+    pub omz: PathBuf,
+    /// `$ZSH_CUSTOM`: the oh-my-zsh custom directory.
+    pub omz_custom: PathBuf,
+    /// `$HISTFILE`, if the shell sets one.
+    pub histfile: Option<PathBuf>,
+}
+
+impl ShellPaths {
+    /// The locations zsh and oh-my-zsh use when nothing is overridden.
+    pub fn defaults(home: PathBuf) -> Self {
+        let omz = home.join(".oh-my-zsh");
+        ShellPaths {
+            zdotdir: home.clone(),
+            omz_custom: omz.join("custom"),
+            omz,
+            home,
+            histfile: None,
+        }
+    }
+
+    /// Defaults adjusted by `ZDOTDIR`, `ZSH`, `ZSH_CUSTOM` and `HISTFILE`
+    /// in zam's own environment — the best guess before the shell has
+    /// been asked.
+    pub fn from_env(home: PathBuf) -> Self {
+        let var = |key| std::env::var(key).ok();
+        ShellPaths::defaults(home).with_vars(var("ZDOTDIR"), var("ZSH"), var("ZSH_CUSTOM"), var("HISTFILE"))
+    }
+
+    /// Apply the `KEY=value` lines the collector prints in its `env`
+    /// section. Unset or empty variables keep their default.
+    pub fn with_env_section(self, section: &str) -> Self {
+        let var = |key: &str| {
+            section.lines().find_map(|line| {
+                let value = line.strip_prefix(key)?.strip_prefix('=')?;
+                (!value.is_empty()).then(|| value.to_string())
+            })
+        };
+        let home = self.home.clone();
+        ShellPaths::defaults(home).with_vars(var("ZDOTDIR"), var("ZSH"), var("ZSH_CUSTOM"), var("HISTFILE"))
+    }
+
+    fn with_vars(
+        mut self,
+        zdotdir: Option<String>,
+        omz: Option<String>,
+        omz_custom: Option<String>,
+        histfile: Option<String>,
+    ) -> Self {
+        if let Some(dir) = zdotdir.filter(|d| !d.is_empty()) {
+            self.zdotdir = PathBuf::from(dir);
+        }
+        if let Some(dir) = omz.filter(|d| !d.is_empty()) {
+            self.omz = PathBuf::from(dir);
+            self.omz_custom = self.omz.join("custom");
+        }
+        if let Some(dir) = omz_custom.filter(|d| !d.is_empty()) {
+            self.omz_custom = PathBuf::from(dir);
+        }
+        self.histfile = histfile.filter(|f| !f.is_empty()).map(PathBuf::from);
+        self
+    }
+
+    /// The rc file `shell` actually reads.
+    pub fn rc_file(&self, shell: Shell) -> PathBuf {
+        match shell {
+            Shell::Zsh => self.zdotdir.join(".zshrc"),
+            Shell::Bash | Shell::Fish => self.home.join(shell.rc_file()),
+        }
+    }
+
+    /// Where `shell` keeps its command history.
+    pub fn history_file(&self, shell: Shell) -> PathBuf {
+        match (&self.histfile, shell) {
+            (_, Shell::Fish) => self.home.join(".local/share/fish/fish_history"),
+            (Some(file), _) => file.clone(),
+            (None, Shell::Zsh) => self.zdotdir.join(".zsh_history"),
+            (None, Shell::Bash) => self.home.join(".bash_history"),
+        }
+    }
+
+    /// The file oh-my-zsh loads for `plugin`: a plugin of the same name in
+    /// `$ZSH_CUSTOM/plugins` takes precedence over the bundled one.
+    pub fn omz_plugin_file(&self, plugin: &str) -> PathBuf {
+        let file = format!("{plugin}.plugin.zsh");
+        let custom = self.omz_custom.join("plugins").join(plugin).join(&file);
+        if custom.is_file() {
+            custom
+        } else {
+            self.omz.join("plugins").join(plugin).join(file)
+        }
+    }
+
+    /// `path` with the home directory shortened to `~`, for display.
+    pub fn display(&self, path: &Path) -> String {
+        match path.strip_prefix(&self.home) {
+            Ok(rest) if !self.home.as_os_str().is_empty() => format!("~/{}", rest.display()),
+            _ => path.display().to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_env_section_overrides_defaults() {
+        let home = PathBuf::from("/home/u");
+        let paths = ShellPaths::defaults(home.clone())
+            .with_env_section("ZDOTDIR=/home/u/.config/zsh\nZSH=/opt/omz\nZSH_CUSTOM=\nHISTFILE=\n");
+        assert_eq!(paths.rc_file(Shell::Zsh), home.join(".config/zsh/.zshrc"));
+        assert_eq!(paths.omz, PathBuf::from("/opt/omz"));
+        assert_eq!(paths.omz_custom, PathBuf::from("/opt/omz/custom"));
+        assert_eq!(paths.display(&paths.omz_custom), "/opt/omz/custom");
+        assert_eq!(paths.display(&paths.rc_file(Shell::Bash)), "~/.bashrc");
+        assert_eq!(paths.history_file(Shell::Zsh), home.join(".config/zsh/.zsh_history"));
+    }
+}
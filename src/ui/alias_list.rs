@@ -6,43 +6,68 @@ use ratatui::text::{Line, Span};
 use ratatui::widgets::{Block, Borders, Paragraph};
 use ratatui::Frame;
 
-use super::theme;
-use crate::app::{ListItem, PanelFocus};
+use super::theme::Theme;
+use crate::alias::types::{AliasKind, AliasState};
+use crate::app::{AppState, ListItem, ListMode, PanelFocus};
 
-pub fn render(
-    frame: &mut Frame,
-    area: Rect,
-    items: &[ListItem],
-    selected: usize,
-    scroll_offset: usize,
-    focus: PanelFocus,
-) {
-    let focused = focus == PanelFocus::List;
+/// The list, from `app.list_scroll_offset`, which `compute_scroll()` has
+/// set to keep the selection in view.
+pub fn render(frame: &mut Frame, area: Rect, app: &AppState) {
+    let theme = &app.theme;
+    let items = &app.visible_items;
+    let focused = app.mode.panel_focus() == PanelFocus::List;
+    let mut title = " Aliases ".to_string();
+    if app.list_mode != ListMode::BySource {
+        title.push_str(&format!("· {} ", app.list_mode.label()));
+    }
+    let marked = app.marked.len();
+    if marked > 0 {
+        title.push_str(&format!("· {marked} marked "));
+    }
     let border = Block::default()
         .borders(Borders::ALL)
-        .border_style(theme::border_style(focused))
-        .title(" Aliases ");
+        .border_style(theme.border_style(focused))
+        .title(title);
 
     let inner = border.inner(area);
     frame.render_widget(border, area);
 
     let visible_height = inner.height as usize;
     if items.is_empty() || visible_height == 0 {
-        let msg = Paragraph::new("  No aliases found").style(Style::default().fg(theme::HELP_FG));
+        let msg = Paragraph::new("  No aliases found").style(Style::default().fg(theme.help_fg));
         frame.render_widget(msg, inner);
         return;
     }
 
+    let scroll_offset = app.list_scroll_offset;
     let end = (scroll_offset + visible_height).min(items.len());
     let lines: Vec<Line> = (scroll_offset..end)
-        .map(|i| render_item(&items[i], i == selected, inner.width))
+        .map(|i| render_item(&items[i], i == app.selected_index, inner.width, theme))
         .collect();
 
     frame.render_widget(Paragraph::new(lines), inner);
 }
 
-fn render_item(item: &ListItem, is_selected: bool, panel_width: u16) -> Line<'static> {
+fn render_item(item: &ListItem, is_selected: bool, panel_width: u16, theme: &Theme) -> Line<'static> {
     match item {
+        ListItem::SectionHeader {
+            section,
+            alias_count,
+        } => {
+            let style = if is_selected {
+                theme.selected_style()
+            } else {
+                theme.group_style()
+            };
+            Line::from(vec![
+                Span::styled(format!("━━ {} ", section.title()), style),
+                Span::styled(
+                    format!("({alias_count}) "),
+                    Style::default().fg(theme.help_fg),
+                ),
+                Span::styled("━━", style),
+            ])
+        }
         ListItem::GroupHeader {
             display_name,
             alias_count,
@@ -51,42 +76,66 @@ fn render_item(item: &ListItem, is_selected: bool, panel_width: u16) -> Line<'st
         } => {
             let arrow = if *collapsed { "▸" } else { "▾" };
             let style = if is_selected {
-                theme::selected_style()
+                theme.selected_style()
             } else {
-                theme::group_style()
+                theme.group_style()
             };
             Line::from(vec![
                 Span::styled(format!(" {arrow} "), style),
                 Span::styled(display_name.clone(), style),
                 Span::styled(
                     format!(" ({alias_count})"),
-                    Style::default().fg(theme::HELP_FG),
+                    Style::default().fg(theme.help_fg),
                 ),
             ])
         }
-        ListItem::AliasEntry { alias, .. } => {
+        ListItem::AliasEntry { alias, marked, .. } => {
+            let disabled = alias.state == AliasState::Disabled;
             let name_style = if is_selected {
-                theme::selected_style()
+                theme.selected_style()
+            } else if disabled {
+                theme.disabled_style()
             } else {
-                theme::alias_name_style()
+                theme.alias_name_style()
             };
             let cmd_style = if is_selected {
-                theme::selected_style()
+                theme.selected_style()
+            } else if disabled {
+                theme.disabled_style()
+            } else if alias.missing {
+                // Named directories whose target is gone are flagged
+                Style::default().fg(theme.missing_fg)
             } else {
-                theme::alias_cmd_style()
+                theme.alias_cmd_style()
             };
 
+            let summary = alias.summary();
             let max_cmd = panel_width.saturating_sub(12) as usize;
-            let cmd_display = if alias.command.len() > max_cmd {
-                format!("{}…", &alias.command[..max_cmd.saturating_sub(1)])
+            let cmd_display = if summary.chars().count() > max_cmd {
+                let truncated: String = summary.chars().take(max_cmd.saturating_sub(1)).collect();
+                format!("{truncated}…")
             } else {
-                alias.command.clone()
+                summary.to_string()
             };
 
-            Line::from(vec![
-                Span::styled(format!("   {:<8}", alias.name), name_style),
-                Span::styled(cmd_display, cmd_style),
-            ])
+            let name = if alias.kind == AliasKind::Function {
+                format!("{}()", alias.name)
+            } else {
+                alias.name.clone()
+            };
+            let mark = if *marked { " ● " } else { "   " };
+            let mut spans = vec![
+                Span::styled(mark, Style::default().fg(theme.search_fg)),
+                Span::styled(format!("{name:<8}"), name_style),
+            ];
+            if let Some(flag) = alias.kind.flag() {
+                spans.push(Span::styled(
+                    format!("{flag} "),
+                    Style::default().fg(theme.help_fg),
+                ));
+            }
+            spans.push(Span::styled(cmd_display, cmd_style));
+            Line::from(spans)
         }
     }
 }
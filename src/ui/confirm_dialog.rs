@@ -6,39 +6,59 @@ use ratatui::text::{Line, Span};
 use ratatui::widgets::{Block, Borders, Paragraph, Wrap};
 use ratatui::Frame;
 
-use super::theme;
-use crate::app::PendingAction;
+use super::theme::Theme;
+use crate::action::Action;
+use crate::alias::types::Alias;
+use crate::app::{BulkOp, PendingAction};
+use crate::keymap::{KeyTable, Keymap};
 
-pub fn render(frame: &mut Frame, area: Rect, pending: &PendingAction) {
+pub fn render(
+    frame: &mut Frame,
+    area: Rect,
+    pending: &PendingAction,
+    keymap: &Keymap,
+    theme: &Theme,
+) {
     match pending {
-        PendingAction::Delete {
-            alias_name,
-            alias_source,
-        } => render_delete(frame, area, alias_name, &alias_source.display_name()),
+        PendingAction::Delete { alias } => {
+            render_delete(frame, area, &alias.name, &alias.source.display_name(), keymap, theme)
+        }
         PendingAction::CollisionOverride {
             edit_state,
             collision_desc,
-        } => render_collision(frame, area, &edit_state.name, collision_desc),
+        } => render_collision(frame, area, edit_state.name.text(), collision_desc, keymap, theme),
+        PendingAction::Bulk {
+            op,
+            aliases,
+            skipped,
+        } => render_bulk(frame, area, op, aliases, *skipped, keymap, theme),
     }
 }
 
-fn render_delete(frame: &mut Frame, area: Rect, name: &str, source_display: &str) {
+fn render_delete(
+    frame: &mut Frame,
+    area: Rect,
+    name: &str,
+    source_display: &str,
+    keymap: &Keymap,
+    theme: &Theme,
+) {
     let border = Block::default()
         .borders(Borders::ALL)
-        .border_style(theme::border_style(true))
+        .border_style(theme.border_style(true))
         .title(" Delete Alias ");
 
     let inner = border.inner(area);
     frame.render_widget(border, area);
 
-    let label = Style::default().fg(theme::DETAIL_LABEL_FG);
-    let value = Style::default().fg(theme::DETAIL_VALUE_FG);
+    let label = Style::default().fg(theme.detail_label_fg);
+    let value = Style::default().fg(theme.detail_value_fg);
 
     let lines = vec![
         Line::from(""),
         Line::from(vec![
             Span::styled("  Delete alias ", label),
-            Span::styled(name, theme::alias_name_style()),
+            Span::styled(name, theme.alias_name_style()),
             Span::styled("?", label),
         ]),
         Line::from(""),
@@ -48,30 +68,37 @@ fn render_delete(frame: &mut Frame, area: Rect, name: &str, source_display: &str
         ]),
         Line::from(""),
         Line::from(""),
-        confirm_keys_line(),
+        confirm_keys_line(keymap, theme),
     ];
 
     let paragraph = Paragraph::new(lines).wrap(Wrap { trim: false });
     frame.render_widget(paragraph, inner);
 }
 
-fn render_collision(frame: &mut Frame, area: Rect, name: &str, collision_desc: &str) {
+fn render_collision(
+    frame: &mut Frame,
+    area: Rect,
+    name: &str,
+    collision_desc: &str,
+    keymap: &Keymap,
+    theme: &Theme,
+) {
     let border = Block::default()
         .borders(Borders::ALL)
-        .border_style(theme::border_style(true))
+        .border_style(theme.border_style(true))
         .title(" Name Collision ");
 
     let inner = border.inner(area);
     frame.render_widget(border, area);
 
-    let label = Style::default().fg(theme::DETAIL_LABEL_FG);
-    let value = Style::default().fg(theme::DETAIL_VALUE_FG);
+    let label = Style::default().fg(theme.detail_label_fg);
+    let value = Style::default().fg(theme.detail_value_fg);
 
     let lines = vec![
         Line::from(""),
         Line::from(vec![
             Span::styled("  \"", label),
-            Span::styled(name, theme::alias_name_style()),
+            Span::styled(name, theme.alias_name_style()),
             Span::styled("\" ", label),
             Span::styled(collision_desc, value),
         ]),
@@ -79,21 +106,106 @@ fn render_collision(frame: &mut Frame, area: Rect, name: &str, collision_desc: &
         Line::from(Span::styled("  Override and save?", label)),
         Line::from(""),
         Line::from(""),
-        confirm_keys_line(),
+        confirm_keys_line(keymap, theme),
     ];
 
     let paragraph = Paragraph::new(lines).wrap(Wrap { trim: false });
     frame.render_widget(paragraph, inner);
 }
 
-fn confirm_keys_line() -> Line<'static> {
-    Line::from(vec![
-        Span::styled("  ", Style::default()),
-        Span::styled("y", Style::default().fg(theme::SEARCH_FG)),
-        Span::styled(":confirm  ", Style::default().fg(theme::HELP_FG)),
-        Span::styled("n", Style::default().fg(theme::SEARCH_FG)),
-        Span::styled("/", Style::default().fg(theme::HELP_FG)),
-        Span::styled("Esc", Style::default().fg(theme::SEARCH_FG)),
-        Span::styled(":cancel", Style::default().fg(theme::HELP_FG)),
-    ])
+/// One dialog for every bulk action: the question, each affected alias
+/// (as many as fit) and how many marked aliases were left out.
+fn render_bulk(
+    frame: &mut Frame,
+    area: Rect,
+    op: &BulkOp,
+    aliases: &[Alias],
+    skipped: usize,
+    keymap: &Keymap,
+    theme: &Theme,
+) {
+    let count = aliases.len();
+    let noun = if count == 1 { "alias" } else { "aliases" };
+    let (title, verb) = match op {
+        BulkOp::Delete => (format!(" Delete {count} {noun} "), "Delete"),
+        BulkOp::Disable => (format!(" Disable {count} {noun} "), "Disable"),
+        BulkOp::Enable => (format!(" Enable {count} {noun} "), "Enable"),
+        BulkOp::Export => (format!(" Export {count} {noun} "), "Export"),
+        BulkOp::Move { .. } => (format!(" Move {count} {noun} "), "Move"),
+    };
+    let border = Block::default()
+        .borders(Borders::ALL)
+        .border_style(theme.border_style(true))
+        .title(title);
+
+    let inner = border.inner(area);
+    frame.render_widget(border, area);
+
+    let label = Style::default().fg(theme.detail_label_fg);
+    let value = Style::default().fg(theme.detail_value_fg);
+    let help = Style::default().fg(theme.help_fg);
+
+    let these = if count == 1 { "this alias" } else { "these aliases" };
+    let mut question = vec![Span::styled(format!("  {verb} {these}"), label)];
+    let mut header = vec![Line::from("")];
+    match op {
+        BulkOp::Move { targets, target } => {
+            question.push(Span::styled(" to ", label));
+            question.push(Span::styled(targets[*target].display_name(), value));
+            question.push(Span::styled("?", label));
+            header.push(Line::from(question));
+            if let (true, Some(key)) = (targets.len() > 1, keymap.short_hint(KeyTable::Confirm, &[Action::ConfirmNext])) {
+                header.push(Line::from(vec![
+                    Span::styled(format!("  {key}"), Style::default().fg(theme.search_fg)),
+                    Span::styled(":other file", help),
+                ]));
+            }
+        }
+        BulkOp::Export => {
+            question.push(Span::styled(" to a file?", label));
+            header.push(Line::from(question));
+        }
+        BulkOp::Delete | BulkOp::Disable | BulkOp::Enable => {
+            question.push(Span::styled("?", label));
+            header.push(Line::from(question));
+        }
+    }
+    header.push(Line::from(""));
+
+    let mut footer = Vec::new();
+    if skipped > 0 {
+        let why = match op {
+            BulkOp::Move { .. } => "not in a zam file",
+            _ => "read-only functions",
+        };
+        footer.push(Line::from(Span::styled(format!("  {skipped} skipped: {why}"), help)));
+    }
+    footer.push(Line::from(""));
+    footer.push(confirm_keys_line(keymap, theme));
+
+    // List what fits, keeping a row for the overflow count
+    let room = (inner.height as usize).saturating_sub(header.len() + footer.len());
+    let shown = if count > room { room.saturating_sub(1) } else { count };
+    let mut lines = header;
+    for alias in &aliases[..shown] {
+        lines.push(Line::from(vec![
+            Span::styled(format!("    {:<8} ", alias.name), theme.alias_name_style()),
+            Span::styled(alias.source.display_name(), help),
+        ]));
+    }
+    if shown < count {
+        lines.push(Line::from(Span::styled(format!("    … and {} more", count - shown), help)));
+    }
+    lines.extend(footer);
+
+    frame.render_widget(Paragraph::new(lines), inner);
+}
+
+fn confirm_keys_line(keymap: &Keymap, theme: &Theme) -> Line<'static> {
+    super::key_hints_line(
+        keymap,
+        theme,
+        KeyTable::Confirm,
+        &[(&[Action::ConfirmYes], "confirm"), (&[Action::ConfirmNo], "cancel")],
+    )
 }
@@ -6,15 +6,18 @@ use ratatui::text::{Line, Span};
 use ratatui::widgets::{Block, Borders, Paragraph, Wrap};
 use ratatui::Frame;
 
-use super::theme;
-use crate::alias::types::Alias;
-use crate::app::PanelFocus;
+use crate::alias::types::{AliasKind, AliasState};
+use crate::app::{AppState, PanelFocus};
 
-pub fn render(frame: &mut Frame, area: Rect, alias: Option<&Alias>, focus: PanelFocus) {
-    let focused = focus == PanelFocus::Detail;
+/// The selected alias, scrolled by `app.detail_scroll`, which is clamped
+/// so the last line stays at the bottom and returned.
+pub fn render(frame: &mut Frame, area: Rect, app: &AppState) -> u16 {
+    let theme = &app.theme;
+    let alias = app.selected_alias();
+    let focused = app.mode.panel_focus() == PanelFocus::Detail;
     let border = Block::default()
         .borders(Borders::ALL)
-        .border_style(theme::border_style(focused))
+        .border_style(theme.border_style(focused))
         .title(" Details ");
 
     let inner = border.inner(area);
@@ -22,45 +25,79 @@ pub fn render(frame: &mut Frame, area: Rect, alias: Option<&Alias>, focus: Panel
 
     let Some(alias) = alias else {
         let msg = Paragraph::new("  Select an alias to view details")
-            .style(Style::default().fg(theme::HELP_FG));
+            .style(Style::default().fg(theme.help_fg));
         frame.render_widget(msg, inner);
-        return;
+        return 0;
     };
 
-    let label = Style::default().fg(theme::DETAIL_LABEL_FG);
-    let value = Style::default().fg(theme::DETAIL_VALUE_FG);
+    let label = Style::default().fg(theme.detail_label_fg);
+    let value = Style::default().fg(theme.detail_value_fg);
 
+    let is_dir = alias.kind == AliasKind::NamedDir;
+    let (name_label, command_label) = match alias.kind {
+        AliasKind::NamedDir => ("  Name:    ", "  Target:  "),
+        AliasKind::Function => ("  Function: ", "  Body:    "),
+        _ => ("  Alias:   ", "  Command: "),
+    };
     let mut lines = vec![
         Line::from(""),
         Line::from(vec![
-            Span::styled("  Alias:   ", label),
-            Span::styled(&alias.name, theme::alias_name_style()),
+            Span::styled(name_label, label),
+            Span::styled(&alias.name, theme.alias_name_style()),
         ]),
         Line::from(""),
-        Line::from(vec![Span::styled("  Command: ", label)]),
+        Line::from(vec![Span::styled(command_label, label)]),
     ];
 
-    // Wrap long commands across multiple lines
+    // Wrap long commands across multiple lines; function bodies keep
+    // their own line breaks
     let max_width = inner.width.saturating_sub(13) as usize;
     if max_width > 0 {
-        let mut start = 0;
-        while start < alias.command.len() {
-            let end = (start + max_width).min(alias.command.len());
-            lines.push(Line::from(vec![
-                Span::styled("             ", label),
-                Span::styled(alias.command[start..end].to_string(), value),
-            ]));
-            start = end;
+        for body_line in alias.command.lines() {
+            let chars: Vec<char> = body_line.replace('\t', "  ").chars().collect();
+            if chars.is_empty() {
+                lines.push(Line::from(""));
+            }
+            for chunk in chars.chunks(max_width) {
+                lines.push(Line::from(vec![
+                    Span::styled("             ", label),
+                    Span::styled(chunk.iter().collect::<String>(), value),
+                ]));
+            }
         }
     }
 
+    lines.push(Line::from(""));
+    lines.push(Line::from(vec![
+        Span::styled("  Kind:     ", label),
+        Span::styled(alias.kind.label(), value),
+    ]));
+    if alias.state == AliasState::Disabled {
+        lines.push(Line::from(vec![
+            Span::styled("  State:    ", label),
+            Span::styled("disabled", theme.disabled_style()),
+        ]));
+    }
+
+    if is_dir {
+        let exists = !alias.missing;
+        lines.push(Line::from(vec![
+            Span::styled("  Exists:   ", label),
+            if exists {
+                Span::styled("yes", value)
+            } else {
+                Span::styled("no — directory not found", Style::default().fg(theme.missing_fg))
+            },
+        ]));
+    }
+
     lines.push(Line::from(""));
     lines.push(Line::from(vec![
         Span::styled("  Source:   ", label),
         Span::styled(alias.source.display_name(), value),
     ]));
 
-    if let Some(file_path) = alias.source.file_path() {
+    if let Some(file_path) = alias.source.file_path(&app.ctx) {
         lines.push(Line::from(""));
         lines.push(Line::from(vec![
             Span::styled("  File:    ", label),
@@ -68,6 +105,23 @@ pub fn render(frame: &mut Frame, area: Rect, alias: Option<&Alias>, focus: Panel
         ]));
     }
 
+    if !alias.tags.is_empty() {
+        let tags: Vec<String> = alias.tags.iter().map(|t| format!("#{t}")).collect();
+        lines.push(Line::from(""));
+        lines.push(Line::from(vec![
+            Span::styled("  Tags:     ", label),
+            Span::styled(tags.join(" "), value),
+        ]));
+    }
+
+    if let Some(uses) = app.uses(alias) {
+        lines.push(Line::from(""));
+        lines.push(Line::from(vec![
+            Span::styled("  Used:     ", label),
+            Span::styled(format!("{uses} times in history"), value),
+        ]));
+    }
+
     if let Some(desc) = &alias.description {
         lines.push(Line::from(""));
         lines.push(Line::from(vec![Span::styled("  Description:", label)]));
@@ -77,6 +131,12 @@ pub fn render(frame: &mut Frame, area: Rect, alias: Option<&Alias>, focus: Panel
         ]));
     }
 
-    let paragraph = Paragraph::new(lines).wrap(Wrap { trim: false });
+    // Stop scrolling once the last line is at the bottom
+    let width = usize::from(inner.width.max(1));
+    let height: usize = lines.iter().map(|l| l.width().div_ceil(width).max(1)).sum();
+    let scroll = app.detail_scroll.min(u16::try_from(height.saturating_sub(inner.height.into())).unwrap_or(u16::MAX));
+
+    let paragraph = Paragraph::new(lines).wrap(Wrap { trim: false }).scroll((scroll, 0));
     frame.render_widget(paragraph, inner);
+    scroll
 }
@@ -6,69 +6,226 @@ use ratatui::text::{Line, Span};
 use ratatui::widgets::{Block, Borders, Paragraph, Wrap};
 use ratatui::Frame;
 
-use super::theme;
+use super::theme::Theme;
+use crate::action::Action;
+use crate::alias::types::AliasKind;
 use crate::app::{EditField, EditState};
+use crate::context::Context;
+use crate::keymap::{KeyTable, Keymap};
+use crate::line_editor::LineEditor;
+use crate::managed;
 
-pub fn render(frame: &mut Frame, area: Rect, state: &EditState) {
-    let title = if state.is_new {
-        " New Alias "
-    } else {
-        " Edit Alias "
+pub fn render(
+    frame: &mut Frame,
+    area: Rect,
+    state: &mut EditState,
+    ctx: &Context,
+    keymap: &Keymap,
+    theme: &Theme,
+) {
+    let title = match (state.is_new, state.kind == AliasKind::NamedDir) {
+        (true, false) => " New Alias ",
+        (false, false) => " Edit Alias ",
+        (true, true) => " New Named Directory ",
+        (false, true) => " Edit Named Directory ",
     };
 
     let border = Block::default()
         .borders(Borders::ALL)
-        .border_style(theme::border_style(true))
+        .border_style(theme.border_style(true))
         .title(title);
 
     let inner = border.inner(area);
     frame.render_widget(border, area);
 
-    let label = Style::default().fg(theme::DETAIL_LABEL_FG);
-    let value = Style::default().fg(theme::DETAIL_VALUE_FG);
+    let label = Style::default().fg(theme.detail_label_fg);
+    let value = Style::default().fg(theme.detail_value_fg);
     let active = Style::default()
-        .fg(theme::SEARCH_FG)
+        .fg(theme.search_fg)
         .add_modifier(Modifier::BOLD);
-    let cursor = "█";
-
     let name_active = state.active_field == EditField::Name;
     let cmd_active = state.active_field == EditField::Command;
+    let desc_active = state.active_field == EditField::Description;
 
     let name_style = if name_active { active } else { value };
     let cmd_style = if cmd_active { active } else { value };
 
-    let name_cursor = if name_active { cursor } else { "" };
-    let cmd_cursor = if cmd_active { cursor } else { "" };
-
     let name_label_style = if name_active { active } else { label };
     let cmd_label_style = if cmd_active { active } else { label };
 
-    let lines = vec![
+    let kind_active = state.active_field == EditField::Kind;
+    let kind_label_style = if kind_active { active } else { label };
+    // Only the current kind, so the row fits however narrow the panel
+    let kinds = [
+        AliasKind::Regular,
+        AliasKind::Global,
+        AliasKind::Suffix,
+        AliasKind::NamedDir,
+    ]
+    .into_iter()
+    .filter(|kind| kind.supported_by(ctx.shell))
+    .count();
+    let kind_style = if kind_active {
+        theme.selected_style()
+    } else {
+        value.add_modifier(Modifier::BOLD)
+    };
+    let arrow = Style::default().fg(theme.help_fg);
+    let mut kind_spans = vec![Span::styled("  Kind:    ", kind_label_style)];
+    if kinds > 1 {
+        kind_spans.push(Span::styled("◂", arrow));
+        kind_spans.push(Span::styled(format!(" {} ", state.kind.label()), kind_style));
+        kind_spans.push(Span::styled("▸", arrow));
+        // Space is typed text in the other fields, not a binding
+        kind_spans.push(Span::styled("  Space", Style::default().fg(theme.search_fg)));
+        kind_spans.push(Span::styled(":change", Style::default().fg(theme.help_fg)));
+    } else {
+        kind_spans.push(Span::styled(state.kind.label(), value));
+    }
+
+    let hints = super::key_hints_line(
+        keymap,
+        theme,
+        KeyTable::Edit,
+        &[
+            (&[Action::EditNextField], "next field"),
+            (&[Action::SaveEdit], "save"),
+            (&[Action::CancelEdit], "cancel"),
+        ],
+    );
+
+    // Label, then the field up to the panel's edge
+    let width = (inner.width as usize).saturating_sub(LABEL_WIDTH + 1);
+    let cursor = Style::default().fg(theme.search_fg);
+    let mut name_spans = vec![Span::styled("  Name:    ", name_label_style)];
+    name_spans.extend(field_spans(&mut state.name, width, name_active, name_style, cursor));
+    let mut cmd_spans = vec![Span::styled(
+        if state.kind == AliasKind::NamedDir {
+            "  Target:  "
+        } else {
+            "  Command: "
+        },
+        cmd_label_style,
+    )];
+    cmd_spans.extend(field_spans(&mut state.command, width, cmd_active, cmd_style, cursor));
+    let mut desc_spans = vec![Span::styled("  Comment: ", if desc_active { active } else { label })];
+    desc_spans.extend(field_spans(
+        &mut state.description,
+        width,
+        desc_active,
+        if desc_active { active } else { value },
+        cursor,
+    ));
+
+    let mut lines = vec![
+        Line::from(""),
+        Line::from(name_spans),
         Line::from(""),
-        Line::from(vec![
-            Span::styled("  Name:    ", name_label_style),
-            Span::styled(&state.name, name_style),
-            Span::styled(name_cursor, Style::default().fg(theme::SEARCH_FG)),
-        ]),
+        Line::from(cmd_spans),
         Line::from(""),
-        Line::from(vec![
-            Span::styled("  Command: ", cmd_label_style),
-            Span::styled(&state.command, cmd_style),
-            Span::styled(cmd_cursor, Style::default().fg(theme::SEARCH_FG)),
-        ]),
+        Line::from(desc_spans),
         Line::from(""),
+        Line::from(kind_spans),
         Line::from(""),
-        Line::from(vec![
-            Span::styled("  ", label),
-            Span::styled("Tab", Style::default().fg(theme::SEARCH_FG)),
-            Span::styled(":next field  ", Style::default().fg(theme::HELP_FG)),
-            Span::styled("Enter", Style::default().fg(theme::SEARCH_FG)),
-            Span::styled(":save  ", Style::default().fg(theme::HELP_FG)),
-            Span::styled("Esc", Style::default().fg(theme::SEARCH_FG)),
-            Span::styled(":cancel", Style::default().fg(theme::HELP_FG)),
-        ]),
     ];
+    lines.extend(preview_lines(state, ctx, theme));
+    lines.push(Line::from(""));
+    lines.push(hints);
 
     let paragraph = Paragraph::new(lines).wrap(Wrap { trim: false });
     frame.render_widget(paragraph, inner);
 }
+
+/// The lines zam will write, then what the live check found. Problems
+/// stay up until a check of the current fields replaces them.
+fn preview_lines(state: &EditState, ctx: &Context, theme: &Theme) -> Vec<Line<'static>> {
+    let label = Style::default().fg(theme.detail_label_fg);
+    let help = Style::default().fg(theme.help_fg);
+    let name = state.name.text().trim();
+    let command = state.command.text().trim();
+    let complete = !name.is_empty() && !command.is_empty();
+    let preview = if complete {
+        Span::styled(
+            managed::definition_line(ctx, name, command, state.kind),
+            theme.alias_cmd_style(),
+        )
+    } else {
+        Span::styled("type a name and command to preview", help)
+    };
+
+    let mut lines = Vec::new();
+    let description = state.description.text().trim();
+    if complete && !description.is_empty() {
+        lines.push(Line::from(vec![
+            Span::styled("  Line:    ", label),
+            Span::styled(format!("# {description}"), theme.alias_cmd_style()),
+        ]));
+        lines.push(Line::from(vec![Span::raw(" ".repeat(LABEL_WIDTH)), preview]));
+    } else {
+        lines.push(Line::from(vec![Span::styled("  Line:    ", label), preview]));
+    }
+    let problem = Style::default().fg(theme.missing_fg);
+    for p in &state.problems {
+        lines.push(Line::from(Span::styled(format!("  ✗ {}", p.message()), problem)));
+    }
+    if complete && state.problems.is_empty() && state.checked.as_ref() == Some(&state.check_input()) {
+        lines.push(Line::from(Span::styled("  ✓ no problems found", help)));
+    }
+    lines
+}
+
+/// Width of the `  Command: ` style labels before each field.
+const LABEL_WIDTH: usize = 11;
+
+/// The visible part of a text field, scrolled to its cursor. The active
+/// field shows the cursor as a reversed char, or a block past the end;
+/// `…` marks text scrolled out of view.
+pub fn field_spans(
+    line: &mut LineEditor,
+    width: usize,
+    active: bool,
+    style: Style,
+    cursor_style: Style,
+) -> Vec<Span<'static>> {
+    let (start, end) = line.window(width);
+    let chars: Vec<char> = line.text().chars().collect();
+    let cursor = active.then_some(line.cursor());
+    let mut cells: Vec<(char, Style)> = (start..end)
+        .map(|i| {
+            if cursor == Some(i) {
+                (chars[i], style.add_modifier(Modifier::REVERSED))
+            } else {
+                (chars[i], style)
+            }
+        })
+        .collect();
+    if start > 0 && cursor != Some(start) {
+        if let Some(cell) = cells.first_mut() {
+            cell.0 = '…';
+        }
+    }
+    if end < chars.len() && cursor != Some(end - 1) {
+        if let Some(cell) = cells.last_mut() {
+            cell.0 = '…';
+        }
+    }
+    if cursor == Some(chars.len()) {
+        cells.push(('█', cursor_style));
+    }
+
+    // One span per run of equally styled cells
+    let mut spans: Vec<Span<'static>> = Vec::new();
+    let mut run = String::new();
+    let mut run_style = style;
+    for (c, s) in cells {
+        if s != run_style && !run.is_empty() {
+            spans.push(Span::styled(std::mem::take(&mut run), run_style));
+        }
+        run_style = s;
+        run.push(c);
+    }
+    if !run.is_empty() {
+        spans.push(Span::styled(run, run_style));
+    }
+    spans
+}
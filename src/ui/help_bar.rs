@@ -1,82 +1,95 @@
 // SPDX-License-Identifier: Apache-2.0
 
-use ratatui::layout::Rect;
+use ratatui::layout::{Position, Rect};
 use ratatui::style::Style;
 use ratatui::text::{Line, Span};
 use ratatui::widgets::Paragraph;
 use ratatui::Frame;
 
-use super::theme;
+use super::theme::Theme;
+use crate::action::Action;
 use crate::app::InputMode;
+use crate::keymap::{KeyTable, Keymap};
 
 const CELL: usize = 16;
 
-type Bindings<'a> = Vec<(&'a str, &'a str)>;
-
-pub fn render(frame: &mut Frame, area: Rect, mode: &InputMode, status: Option<&str>) {
-    let (row1, row2): (Bindings, Bindings) = match mode {
-        InputMode::Normal { .. } => (
-            vec![
-                ("/", "search"),
-                ("a", "add"),
-                ("e", "edit"),
-                ("d", "delete"),
-                ("?", "help"),
-                ("q", "quit"),
-            ],
-            vec![
-                ("j/k", "navigate"),
-                ("g/G", "top/bottom"),
-                ("^d/u", "page"),
-                ("Enter", "toggle"),
-                ("Tab", "focus"),
-            ],
-        ),
-        InputMode::Search => (
-            vec![
-                ("Esc", "exit"),
-                ("Enter", "confirm"),
-                ("^u", "clear"),
-            ],
-            vec![
-                ("=", "cmd search"),
-                ("@", "group search"),
-            ],
-        ),
-        InputMode::Editing { .. } => (
-            vec![
-                ("Tab", "next field"),
-                ("Enter", "save"),
-                ("Esc", "cancel"),
-                ("^u", "clear field"),
-            ],
-            Vec::new(),
-        ),
-        InputMode::Confirm => (
-            vec![
-                ("y", "confirm"),
-                ("n/Esc", "cancel"),
-            ],
-            Vec::new(),
-        ),
-        InputMode::ResultPopup => (
-            vec![("any key", "continue")],
-            Vec::new(),
-        ),
-        InputMode::Help => (
-            vec![("any key", "close help")],
-            Vec::new(),
-        ),
-    };
+/// Keys, label and the action a click on the hint runs.
+type Bindings<'a> = Vec<(String, &'a str, Action)>;
+
+/// One hint: the keys for these actions, then a label.
+type Hint = (&'static [Action], &'static str);
+
+const NORMAL: [&[Hint]; 2] = [
+    &[
+        (&[Action::FocusSearch], "search"),
+        (&[Action::CreateAlias], "add"),
+        (&[Action::EditAlias], "edit"),
+        (&[Action::DeleteAlias], "delete"),
+        (&[Action::ShowHelp], "help"),
+        (&[Action::Quit], "quit"),
+    ],
+    &[
+        (&[Action::ScrollDown, Action::ScrollUp], "navigate"),
+        (&[Action::GoToTop, Action::GoToBottom], "top/bottom"),
+        (&[Action::PageDown, Action::PageUp], "page"),
+        (&[Action::ToggleCollapse], "toggle"),
+        (&[Action::ToggleMark], "mark"),
+        (&[Action::SwitchFocus], "focus"),
+    ],
+];
+
+const SEARCH: [&[Hint]; 2] = [
+    &[(&[Action::ExitSearch], "exit"), (&[Action::SearchClear], "clear")],
+    &[],
+];
+
+const EDIT: [&[Hint]; 2] = [
+    &[
+        (&[Action::EditNextField], "next field"),
+        (&[Action::SaveEdit], "save"),
+        (&[Action::CancelEdit], "cancel"),
+        (&[Action::EditClear], "clear field"),
+    ],
+    &[(&[Action::EditPrevField], "prev field")],
+];
 
-    let line1 = build_aligned_line(&row1);
-    let mut line2 = build_aligned_line(&row2);
+const RUN_ARGS: [&[Hint]; 2] = [
+    &[(&[Action::SaveEdit], "run"), (&[Action::CancelEdit], "cancel"), (&[Action::EditClear], "clear")],
+    &[],
+];
+
+const RUN_OUTPUT: [&[Hint]; 2] = [
+    &[
+        (&[Action::ScrollDown, Action::ScrollUp], "scroll"),
+        (&[Action::PageDown, Action::PageUp], "page"),
+        (&[Action::GoToTop, Action::GoToBottom], "top/bottom"),
+        (&[Action::CloseRun], "close"),
+    ],
+    &[],
+];
+
+const CONFIRM: [&[Hint]; 2] = [
+    &[(&[Action::ConfirmYes], "confirm"), (&[Action::ConfirmNo], "cancel")],
+    &[],
+];
+
+pub fn render(
+    frame: &mut Frame,
+    area: Rect,
+    mode: &InputMode,
+    keymap: &Keymap,
+    status: Option<&str>,
+    theme: &Theme,
+) {
+    let (row1, row2) = rows(*mode, keymap);
+    let line1 = build_aligned_line(&row1, theme);
+    let mut line2 = build_aligned_line(&row2, theme);
 
     if let Some(msg) = status {
-        line2.push(Span::styled("  │ ", Style::default().fg(theme::HELP_FG)));
+        line2.push(Span::styled("  │ ", Style::default().fg(theme.help_fg)));
         line2.push(Span::styled(
             msg.to_string(),
-            Style::default().fg(theme::SEARCH_FG),
+            Style::default().fg(theme.search_fg),
         ));
     }
 
@@ -85,17 +98,85 @@ pub fn render(frame: &mut Frame, area: Rect, mode: &InputMode, status: Option<&s
     frame.render_widget(paragraph, area);
 }
 
-fn build_aligned_line<'a>(bindings: &[(&'a str, &'a str)]) -> Vec<Span<'a>> {
+/// The action of the hint at `at` in a help bar drawn in `area`, for
+/// mouse clicks.
+pub fn hint_at(area: Rect, mode: InputMode, keymap: &Keymap, at: Position) -> Option<Action> {
+    let (row1, row2) = rows(mode, keymap);
+    let row = match at.y.checked_sub(area.y)? {
+        0 => row1,
+        1 => row2,
+        _ => return None,
+    };
+    let mut right = area.x as usize;
+    for (key, desc, action) in row {
+        right += Span::raw(format!("{key}:")).width() + desc_width(&key, desc);
+        if (at.x as usize) < right {
+            return Some(action);
+        }
+    }
+    None
+}
+
+/// The two rows of hints for `mode`.
+fn rows(mode: InputMode, keymap: &Keymap) -> (Bindings<'static>, Bindings<'static>) {
+    match KeyTable::for_mode(mode) {
+        Some(table) => {
+            let hints = match (mode, table) {
+                (InputMode::RunArgs, _) => RUN_ARGS,
+                (_, KeyTable::Normal) => NORMAL,
+                (_, KeyTable::Search) => SEARCH,
+                (_, KeyTable::Edit) => EDIT,
+                (_, KeyTable::Confirm) => CONFIRM,
+                (_, KeyTable::Output) => RUN_OUTPUT,
+            };
+            let row1 = bindings(keymap, table, hints[0]);
+            let mut row2 = bindings(keymap, table, hints[1]);
+            // Typed text, not bindings
+            match mode {
+                InputMode::Search => {
+                    row2.push(("=".into(), "cmd search", Action::SearchInput('=')));
+                    row2.push(("@".into(), "group search", Action::SearchInput('@')));
+                    row2.push(("#".into(), "tag search", Action::SearchInput('#')));
+                }
+                InputMode::Editing { .. } => {
+                    row2.push(("Space".into(), "cycle kind", Action::EditInput(' ')))
+                }
+                _ => {}
+            }
+            (row1, row2)
+        }
+        None if mode == InputMode::Help => {
+            let mut row1 = vec![("any key".into(), "close help", Action::CloseHelp)];
+            row1.extend(bindings(keymap, KeyTable::Normal, &[(&[Action::ScrollDown, Action::ScrollUp], "scroll")]));
+            (row1, Vec::new())
+        }
+        None => (vec![("any key".into(), "continue", Action::DismissResult)], Vec::new()),
+    }
+}
+
+/// The bound hints in `hints`, with their current keys.
+fn bindings(keymap: &Keymap, table: KeyTable, hints: &[Hint]) -> Bindings<'static> {
+    hints
+        .iter()
+        .filter_map(|&(actions, label)| Some((keymap.short_hint(table, actions)?, label, actions[0])))
+        .collect()
+}
+
+/// Width of a hint's label: at least one space before the next hint.
+fn desc_width(key: &str, desc: &str) -> usize {
+    CELL.saturating_sub(key.len() + 1).max(desc.len() + 1)
+}
+
+fn build_aligned_line<'a>(bindings: &[(String, &'a str, Action)], theme: &Theme) -> Vec<Span<'a>> {
     let mut spans = Vec::new();
-    for (key, desc) in bindings {
+    for (key, desc, _) in bindings {
         spans.push(Span::styled(
             format!("{key}:"),
-            Style::default().fg(theme::SEARCH_FG),
+            Style::default().fg(theme.search_fg),
         ));
-        let desc_width = CELL.saturating_sub(key.len() + 1);
         spans.push(Span::styled(
-            format!("{:<width$}", desc, width = desc_width),
-            Style::default().fg(theme::HELP_FG),
+            format!("{:<width$}", desc, width = desc_width(key, desc)),
+            Style::default().fg(theme.help_fg),
         ));
     }
     spans
@@ -6,49 +6,143 @@ use ratatui::text::{Line, Span};
 use ratatui::widgets::{Block, Borders, Paragraph};
 use ratatui::Frame;
 
-use super::theme;
+use super::theme::Theme;
+use crate::action::Action;
+use crate::keymap::{KeyTable, Keymap};
 
-pub fn render(frame: &mut Frame, area: Rect) {
+/// A help row: the keys bound to these actions in a table, then a label.
+type Entry = (KeyTable, &'static [Action], &'static str);
+
+/// Left and right column headers and entries, one block per pair.
+const SECTIONS: [(&str, &[Entry], &str, &[Entry]); 3] = [
+    (
+        "  Navigation",
+        &[
+            (KeyTable::Normal, &[Action::ScrollDown, Action::ScrollUp], "down / up"),
+            (KeyTable::Normal, &[Action::GoToTop, Action::GoToBottom], "top / bottom"),
+            (KeyTable::Normal, &[Action::PageDown, Action::PageUp], "page down/up"),
+            (KeyTable::Normal, &[Action::ToggleCollapse], "toggle group"),
+            (KeyTable::Normal, &[Action::CycleListMode], "sort/group"),
+            (KeyTable::Normal, &[Action::SwitchFocus], "switch panel"),
+        ],
+        "Search",
+        &[
+            (KeyTable::Normal, &[Action::FocusSearch], "open search"),
+            (KeyTable::Search, &[Action::ExitSearch], "close search"),
+            (KeyTable::Search, &[Action::SearchClear], "clear query"),
+        ],
+    ),
+    (
+        "  Aliases",
+        &[
+            (KeyTable::Normal, &[Action::CreateAlias], "create new"),
+            (KeyTable::Normal, &[Action::EditAlias], "edit"),
+            (KeyTable::Normal, &[Action::DeleteAlias], "delete"),
+            (KeyTable::Normal, &[Action::ToggleDisabled], "dis/enable"),
+            (KeyTable::Normal, &[Action::ToggleMark], "mark / group"),
+            (KeyTable::Normal, &[Action::MarkRange], "mark range"),
+            (KeyTable::Normal, &[Action::ClearMarks], "unmark all"),
+            (KeyTable::Normal, &[Action::ExportAliases], "export"),
+            (KeyTable::Normal, &[Action::MoveAliases], "move to file"),
+        ],
+        "Edit Mode",
+        &[
+            (KeyTable::Edit, &[Action::EditNextField, Action::EditPrevField], "switch field"),
+            (KeyTable::Edit, &[Action::SaveEdit], "save"),
+            (KeyTable::Edit, &[Action::CancelEdit], "cancel"),
+            (KeyTable::Edit, &[Action::EditClear], "clear field"),
+            (KeyTable::Edit, &[Action::EditWordLeft, Action::EditWordRight], "word motion"),
+            (KeyTable::Edit, &[Action::EditDeleteWord], "delete word"),
+        ],
+    ),
+    (
+        "  General",
+        &[
+            (KeyTable::Normal, &[Action::CopyName], "copy name"),
+            (KeyTable::Normal, &[Action::CopyCommand], "copy command"),
+            (KeyTable::Normal, &[Action::CopyLine], "copy line"),
+            (KeyTable::Normal, &[Action::PickAlias], "pick & exit"),
+            (KeyTable::Normal, &[Action::RunAlias], "run"),
+            (KeyTable::Normal, &[Action::ShowHelp], "this help"),
+        ],
+        "",
+        &[(KeyTable::Normal, &[Action::Quit], "quit")],
+    ),
+];
+
+/// Rows that aren't bindings: key text and label.
+type Fixed = &'static [(&'static str, &'static str)];
+
+/// Typed into the search box or edit form rather than bound; listed
+/// after the generated rows of the matching block.
+const EXTRA: [(Fixed, Fixed); 3] = [
+    (&[], &[("=query", "by command"), ("@query", "by group"), ("#query", "by tag")]),
+    (&[], &[("Space", "cycle kind")]),
+    (&[("^c", "force quit")], &[]),
+];
+
+/// Draw the help screen scrolled by `scroll`, which is clamped so the
+/// last line stays at the bottom and returned.
+pub fn render(frame: &mut Frame, area: Rect, scroll: u16, keymap: &Keymap, theme: &Theme) -> u16 {
     let border = Block::default()
         .borders(Borders::ALL)
-        .border_style(theme::border_style(true))
-        .title(" Help ");
-
+        .border_style(theme.border_style(true));
     let inner = border.inner(area);
-    frame.render_widget(border, area);
 
-    let ks = Style::default().fg(theme::SEARCH_FG);
-    let ds = Style::default().fg(theme::DETAIL_VALUE_FG);
-    let hs = Style::default().fg(theme::GROUP_FG);
-    let dim = Style::default().fg(theme::HELP_FG);
+    let ks = Style::default().fg(theme.search_fg);
+    let ds = Style::default().fg(theme.detail_value_fg);
+    let hs = Style::default().fg(theme.group_fg);
+    let dim = Style::default().fg(theme.help_fg);
 
-    // Two-column layout: left column 22 chars, right column remainder
+    // Two-column layout: left column 24 chars, right column remainder
     let col = 24;
 
-    let lines = vec![
-        Line::from(""),
-        two_headers("  Navigation", "Search", col, hs),
-        two_row("  j / k    ", "up / down", "/          ", "open search", col, ks, ds),
-        two_row("  g / G    ", "top / bottom", "Esc        ", "close search", col, ks, ds),
-        two_row("  Ctrl+d/u ", "page down/up", "Ctrl+u     ", "clear query", col, ks, ds),
-        two_row("  Enter    ", "toggle group", "=query     ", "search commands", col, ks, ds),
-        two_row("  Tab      ", "switch panel", "@query     ", "search groups", col, ks, ds),
-        Line::from(""),
-        two_headers("  Aliases", "Edit Mode", col, hs),
-        two_row("  a        ", "create new", "Tab        ", "switch field", col, ks, ds),
-        two_row("  e        ", "edit selected", "Enter      ", "save", col, ks, ds),
-        two_row("  d        ", "delete selected", "Esc        ", "cancel", col, ks, ds),
-        two_row("  ", "", "Ctrl+u     ", "clear field", col, ks, ds),
-        Line::from(""),
-        one_header("  General", hs),
-        two_row("  ?        ", "this help", "q          ", "quit", col, ks, ds),
-        two_row("  Ctrl+c   ", "force quit", "", "", col, ks, ds),
-        Line::from(""),
-        Line::from(Span::styled("  Press any key to close", dim)),
-    ];
+    let mut lines = vec![Line::from("")];
+    for ((left_header, left, right_header, right), (left_extra, right_extra)) in SECTIONS.iter().zip(EXTRA) {
+        let left = rows(keymap, left, left_extra);
+        let right = rows(keymap, right, right_extra);
+        lines.push(two_headers(left_header, right_header, col, hs));
+        for i in 0..left.len().max(right.len()) {
+            let (lk, ld) = left.get(i).cloned().unwrap_or_default();
+            let (rk, rd) = right.get(i).cloned().unwrap_or_default();
+            let lk = if lk.is_empty() { "  ".to_string() } else { format!("  {}", pad_key(&lk, 9)) };
+            lines.push(two_row(lk, ld, pad_key(&rk, 11), rd, col, ks, ds));
+        }
+        lines.push(Line::from(""));
+    }
+    lines.push(Line::from(Span::styled("  Press any key to close", dim)));
 
-    let paragraph = Paragraph::new(lines);
-    frame.render_widget(paragraph, inner);
+    let max = u16::try_from(lines.len().saturating_sub(inner.height.into())).unwrap_or(u16::MAX);
+    let scroll = scroll.min(max);
+    let title = if max == 0 {
+        " Help ".to_string()
+    } else {
+        let last = (usize::from(scroll) + usize::from(inner.height)).min(lines.len());
+        format!(" Help · {last}/{} ", lines.len())
+    };
+    frame.render_widget(border.title(title), area);
+    frame.render_widget(Paragraph::new(lines).scroll((scroll, 0)), inner);
+    scroll
+}
+
+/// Key and label pairs for the bound entries, then the fixed ones.
+fn rows(keymap: &Keymap, entries: &[Entry], extra: &[(&str, &'static str)]) -> Vec<(String, &'static str)> {
+    entries
+        .iter()
+        .filter_map(|&(table, actions, label)| Some((keymap.hint(table, actions)?, label)))
+        .chain(extra.iter().map(|&(key, label)| (key.to_string(), label)))
+        .collect()
+}
+
+/// Pad a key to `width`, keeping at least one space before its label.
+fn pad_key(key: &str, width: usize) -> String {
+    if key.is_empty() {
+        String::new()
+    } else if key.len() < width {
+        format!("{key:<width$}")
+    } else {
+        format!("{key} ")
+    }
 }
 
 fn two_headers<'a>(left: &'a str, right: &'a str, col: usize, style: Style) -> Line<'a> {
@@ -60,21 +154,17 @@ fn two_headers<'a>(left: &'a str, right: &'a str, col: usize, style: Style) -> L
     ])
 }
 
-fn one_header<'a>(text: &'a str, style: Style) -> Line<'a> {
-    Line::from(Span::styled(text, style))
-}
-
 fn two_row<'a>(
-    lk: &'a str,
+    lk: String,
     ld: &'a str,
-    rk: &'a str,
+    rk: String,
     rd: &'a str,
     col: usize,
     ks: Style,
     ds: Style,
 ) -> Line<'a> {
     let left_len = lk.len() + ld.len();
-    let pad = col.saturating_sub(left_len);
+    let pad = col.saturating_sub(left_len).max(1);
     Line::from(vec![
         Span::styled(lk, ks),
         Span::styled(ld, ds),
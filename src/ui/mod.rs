@@ -7,13 +7,37 @@ pub mod edit_form;
 pub mod help_bar;
 pub mod help_panel;
 pub mod layout;
+pub mod picker;
 pub mod result_popup;
+pub mod run_popup;
 pub mod search_bar;
 pub mod theme;
 
+use ratatui::buffer::Buffer;
+use ratatui::style::Style;
+use ratatui::text::{Line, Span};
 use ratatui::Frame;
 
+use crate::action::Action;
 use crate::app::{AppState, InputMode};
+use crate::keymap::{KeyTable, Keymap};
+use theme::Theme;
+
+/// The text of a rendered buffer, one line per row with trailing blanks
+/// trimmed. Used by `--mockup` and the snapshot tests.
+pub fn buffer_text(buffer: &Buffer) -> String {
+    let area = buffer.area;
+    let mut text = String::new();
+    for y in area.top()..area.bottom() {
+        let mut line = String::new();
+        for x in area.left()..area.right() {
+            line.push_str(buffer.cell((x, y)).map_or(" ", |c| c.symbol()));
+        }
+        text.push_str(line.trim_end());
+        text.push('\n');
+    }
+    text
+}
 
 pub fn render(frame: &mut Frame, app: &mut AppState) {
     let layout = layout::build_layout(frame.area());
@@ -21,55 +45,70 @@ pub fn render(frame: &mut Frame, app: &mut AppState) {
     let list_inner_height = layout.alias_list.height.saturating_sub(2) as usize;
     app.compute_scroll(list_inner_height);
 
-    let panel_focus = app.mode.panel_focus();
+    let theme = &app.theme;
 
-    search_bar::render(
-        frame,
-        layout.search_bar,
-        app.search_field(),
-        app.search_display(),
-        app.mode.is_search(),
-        app.visible_alias_count(),
-        app.total_aliases(),
-    );
-
-    alias_list::render(
-        frame,
-        layout.alias_list,
-        &app.visible_items,
-        app.selected_index,
-        app.list_scroll_offset,
-        panel_focus,
-    );
+    search_bar::render(frame, layout.search_bar, app);
+    alias_list::render(frame, layout.alias_list, app);
 
     // Detail panel branches on mode
     match app.mode {
         InputMode::Editing { .. } => {
-            if let Some(state) = &app.edit_state {
-                edit_form::render(frame, layout.detail_panel, state);
+            if let Some(state) = &mut app.edit_state {
+                edit_form::render(frame, layout.detail_panel, state, &app.ctx, &app.keymap, theme);
             }
         }
         InputMode::Confirm => {
             if let Some(pending) = &app.pending_action {
-                confirm_dialog::render(frame, layout.detail_panel, pending);
+                confirm_dialog::render(frame, layout.detail_panel, pending, &app.keymap, theme);
             }
         }
         InputMode::Help => {
-            help_panel::render(frame, layout.detail_panel);
+            app.help_scroll = help_panel::render(frame, layout.detail_panel, app.help_scroll, &app.keymap, theme);
         }
         _ => {
-            detail_panel::render(
-                frame,
-                layout.detail_panel,
-                app.selected_alias(),
-                panel_focus,
-            );
+            app.detail_scroll = detail_panel::render(frame, layout.detail_panel, app);
         }
     }
 
-    help_bar::render(frame, layout.help_bar, &app.mode, app.status_message.as_deref());
+    help_bar::render(
+        frame,
+        layout.help_bar,
+        &app.mode,
+        &app.keymap,
+        app.status_message.as_deref(),
+        theme,
+    );
 
     if let Some(msg) = &app.result_message {
-        result_popup::render(frame, msg);
+        result_popup::render(frame, msg, theme);
+    }
+    if let Some(run) = &mut app.run {
+        match app.mode {
+            InputMode::RunArgs => run_popup::render_args(frame, run, &app.keymap, theme),
+            InputMode::RunOutput => run_popup::render_output(frame, run, theme),
+            _ => {}
+        }
+    }
+}
+
+/// An indented `key:label` hint line for dialogs, with each action's
+/// current keys; unbound actions are left out.
+fn key_hints_line(
+    keymap: &Keymap,
+    theme: &Theme,
+    table: KeyTable,
+    hints: &[(&[Action], &str)],
+) -> Line<'static> {
+    let mut spans = vec![Span::raw("  ")];
+    for &(actions, label) in hints {
+        let Some(keys) = keymap.hint(table, actions) else {
+            continue;
+        };
+        if spans.len() > 1 {
+            spans.push(Span::raw("  "));
+        }
+        spans.push(Span::styled(keys, Style::default().fg(theme.search_fg)));
+        spans.push(Span::styled(format!(":{label}"), Style::default().fg(theme.help_fg)));
     }
+    Line::from(spans)
 }
@@ -0,0 +1,55 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use ratatui::style::{Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::Paragraph;
+use ratatui::Frame;
+
+use crate::pick::Picker;
+
+/// `zam pick`'s viewport: the query with a match count, then a window of
+/// matches scrolled to keep the selection in view.
+pub fn render(frame: &mut Frame, picker: &mut Picker) {
+    let area = frame.area();
+    let height = area.height.saturating_sub(1) as usize;
+    if picker.selected < picker.scroll {
+        picker.scroll = picker.selected;
+    } else if height > 0 && picker.selected >= picker.scroll + height {
+        picker.scroll = picker.selected + 1 - height;
+    }
+
+    let theme = &picker.app.theme;
+    let matches = picker.matches();
+
+    let count = format!("  {}/{}", matches.len(), picker.app.total_aliases());
+    let mut lines = vec![Line::from(vec![
+        Span::styled("zam> ", Style::default().fg(theme.detail_label_fg)),
+        Span::styled(
+            picker.app.search_display(),
+            Style::default().fg(theme.search_fg).add_modifier(Modifier::BOLD),
+        ),
+        Span::styled("█", Style::default().fg(theme.search_fg)),
+        Span::styled(count, Style::default().fg(theme.help_fg)),
+    ])];
+
+    let name_width = matches.iter().map(|a| a.name.chars().count()).max().unwrap_or(0).min(24);
+    for (i, alias) in matches.iter().enumerate().skip(picker.scroll).take(height) {
+        let selected = i == picker.selected;
+        let (name_style, cmd_style) = if selected {
+            (theme.selected_style(), theme.selected_style())
+        } else {
+            (theme.alias_name_style(), theme.alias_cmd_style())
+        };
+        let marker = if selected { "> " } else { "  " };
+        lines.push(Line::from(vec![
+            Span::styled(marker, Style::default().fg(theme.search_fg)),
+            Span::styled(format!("{:<name_width$}  ", alias.name), name_style),
+            Span::styled(alias.summary().to_string(), cmd_style),
+        ]));
+    }
+    if matches.is_empty() {
+        lines.push(Line::from(Span::styled("  No aliases found", Style::default().fg(theme.help_fg))));
+    }
+
+    frame.render_widget(Paragraph::new(lines), area);
+}
@@ -6,23 +6,23 @@ use ratatui::text::{Line, Span};
 use ratatui::widgets::{Block, Borders, Clear, Paragraph, Wrap};
 use ratatui::Frame;
 
-use super::theme;
+use super::theme::Theme;
 
-pub fn render(frame: &mut Frame, message: &str) {
+pub fn render(frame: &mut Frame, message: &str, theme: &Theme) {
     let area = centered_rect(50, 7, frame.area());
 
     frame.render_widget(Clear, area);
 
     let border = Block::default()
         .borders(Borders::ALL)
-        .border_style(theme::border_style(true))
+        .border_style(theme.border_style(true))
         .title(" Result ");
 
     let inner = border.inner(area);
     frame.render_widget(border, area);
 
-    let label = Style::default().fg(theme::DETAIL_LABEL_FG);
-    let value = Style::default().fg(theme::DETAIL_VALUE_FG);
+    let label = Style::default().fg(theme.detail_label_fg);
+    let value = Style::default().fg(theme.detail_value_fg);
 
     let lines = vec![
         Line::from(""),
@@ -36,7 +36,8 @@ pub fn render(frame: &mut Frame, message: &str) {
     frame.render_widget(paragraph, inner);
 }
 
-fn centered_rect(percent_x: u16, height: u16, area: Rect) -> Rect {
+/// A `percent_x` wide, `height` tall rect in the middle of `area`.
+pub fn centered_rect(percent_x: u16, height: u16, area: Rect) -> Rect {
     let vertical = Layout::vertical([Constraint::Length(height)])
         .flex(Flex::Center)
         .split(area);
@@ -0,0 +1,87 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use ratatui::style::Style;
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Clear, Paragraph, Wrap};
+use ratatui::Frame;
+
+use super::edit_form::field_spans;
+use super::result_popup::centered_rect;
+use super::theme::Theme;
+use crate::action::Action;
+use crate::app::RunState;
+use crate::keymap::{KeyTable, Keymap};
+
+/// The arguments prompt, before the alias runs.
+pub fn render_args(frame: &mut Frame, run: &mut RunState, keymap: &Keymap, theme: &Theme) {
+    let area = centered_rect(60, 7, frame.area());
+    frame.render_widget(Clear, area);
+
+    let border = Block::default()
+        .borders(Borders::ALL)
+        .border_style(theme.border_style(true))
+        .title(format!(" Run {} ", run.alias.name));
+    let inner = border.inner(area);
+    frame.render_widget(border, area);
+
+    let prompt = format!("  $ {} ", run.alias.name);
+    let width = (inner.width as usize).saturating_sub(prompt.chars().count() + 1);
+    let value = Style::default().fg(theme.detail_value_fg);
+    let mut args = vec![Span::styled(prompt, theme.alias_name_style())];
+    args.extend(field_spans(&mut run.args, width, true, value, Style::default().fg(theme.search_fg)));
+
+    let lines = vec![
+        Line::from(""),
+        Line::from(args),
+        Line::from(""),
+        Line::from(Span::styled("  Arguments, if any", Style::default().fg(theme.help_fg))),
+        super::key_hints_line(
+            keymap,
+            theme,
+            KeyTable::Edit,
+            &[(&[Action::SaveEdit], "run"), (&[Action::CancelEdit], "cancel")],
+        ),
+    ];
+    frame.render_widget(Paragraph::new(lines), inner);
+}
+
+/// What the alias printed, stdout then stderr, scrolled by `run.scroll`,
+/// which is clamped to the output.
+pub fn render_output(frame: &mut Frame, run: &mut RunState, theme: &Theme) {
+    let full = frame.area();
+    let area = centered_rect(80, full.height.saturating_sub(4), full);
+    frame.render_widget(Clear, area);
+
+    let title = run.command_line.as_deref().unwrap_or(&run.alias.name);
+    let border = Block::default()
+        .borders(Borders::ALL)
+        .border_style(theme.border_style(true))
+        .title(format!(" $ {title} "));
+    let inner = border.inner(area);
+    frame.render_widget(border, area);
+
+    let label = Style::default().fg(theme.detail_label_fg);
+    let Some(output) = &run.output else {
+        frame.render_widget(Paragraph::new(Span::styled(" Running…", label)), inner);
+        return;
+    };
+
+    let value = Style::default().fg(theme.detail_value_fg);
+    let error = Style::default().fg(theme.missing_fg);
+    let mut lines: Vec<Line> = output
+        .stdout
+        .lines()
+        .map(|l| Line::from(Span::styled(format!(" {l}"), value)))
+        .chain(output.stderr.lines().map(|l| Line::from(Span::styled(format!(" {l}"), error))))
+        .collect();
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(format!(" [{}]", output.outcome), label)));
+
+    let width = usize::from(inner.width.max(1));
+    let height: usize = lines.iter().map(|l| l.width().div_ceil(width).max(1)).sum();
+    let max_scroll = u16::try_from(height.saturating_sub(inner.height.into())).unwrap_or(u16::MAX);
+    run.scroll = run.scroll.min(max_scroll);
+
+    let paragraph = Paragraph::new(lines).wrap(Wrap { trim: false }).scroll((run.scroll, 0));
+    frame.render_widget(paragraph, inner);
+}
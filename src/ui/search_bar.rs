@@ -6,20 +6,18 @@ use ratatui::text::{Line, Span};
 use ratatui::widgets::{Block, Borders, Paragraph};
 use ratatui::Frame;
 
-use super::theme;
+use crate::app::AppState;
 
-pub fn render(
-    frame: &mut Frame,
-    area: Rect,
-    label: &str,
-    display_query: &str,
-    focused: bool,
-    visible: usize,
-    total: usize,
-) {
+pub fn render(frame: &mut Frame, area: Rect, app: &AppState) {
+    let theme = &app.theme;
+    let label = app.search_field();
+    let display_query = app.search_display();
+    let focused = app.mode.is_search();
+    let visible = app.visible_alias_count();
+    let total = app.total_aliases();
     let border = Block::default()
         .borders(Borders::ALL)
-        .border_style(theme::border_style(focused))
+        .border_style(theme.border_style(focused))
         .title(" Search ");
 
     let cursor_char = if focused { "█" } else { "" };
@@ -31,22 +29,22 @@ pub fn render(
 
     let empty = display_query.is_empty();
     let query_span = if empty {
-        Span::styled("name  =command  @group", Style::default().fg(theme::HELP_FG))
+        Span::styled("name  =command  @group  #tag", Style::default().fg(theme.help_fg))
     } else {
         Span::styled(
             display_query,
             Style::default()
-                .fg(theme::SEARCH_FG)
+                .fg(theme.search_fg)
                 .add_modifier(Modifier::BOLD),
         )
     };
 
     let line = Line::from(vec![
-        Span::styled("[/] ", Style::default().fg(theme::HELP_FG)),
-        Span::styled(format!("{label}: "), Style::default().fg(theme::DETAIL_LABEL_FG)),
+        Span::styled("[/] ", Style::default().fg(theme.help_fg)),
+        Span::styled(format!("{label}: "), Style::default().fg(theme.detail_label_fg)),
         query_span,
-        Span::styled(cursor_char, Style::default().fg(theme::SEARCH_FG)),
-        Span::styled(count_text, Style::default().fg(theme::HELP_FG)),
+        Span::styled(cursor_char, Style::default().fg(theme.search_fg)),
+        Span::styled(count_text, Style::default().fg(theme.help_fg)),
     ]);
 
     let paragraph = Paragraph::new(line).block(border);
@@ -1,42 +1,344 @@
 // SPDX-License-Identifier: Apache-2.0
 
+use std::env;
+use std::str::FromStr;
+
 use ratatui::style::{Color, Modifier, Style};
 
-pub const SELECTED_BG: Color = Color::Rgb(60, 60, 100);
-pub const SELECTED_FG: Color = Color::White;
-pub const GROUP_FG: Color = Color::Rgb(120, 180, 255);
-pub const ALIAS_NAME_FG: Color = Color::Rgb(200, 200, 100);
-pub const ALIAS_CMD_FG: Color = Color::Rgb(160, 160, 160);
-pub const BORDER_COLOR: Color = Color::Rgb(80, 80, 120);
-pub const BORDER_FOCUSED: Color = Color::Rgb(120, 140, 255);
-pub const SEARCH_FG: Color = Color::Rgb(255, 200, 100);
-pub const HELP_FG: Color = Color::Rgb(120, 120, 140);
-pub const DETAIL_LABEL_FG: Color = Color::Rgb(140, 140, 180);
-pub const DETAIL_VALUE_FG: Color = Color::White;
-
-pub fn border_style(focused: bool) -> Style {
-    if focused {
-        Style::default().fg(BORDER_FOCUSED)
-    } else {
-        Style::default().fg(BORDER_COLOR)
+use crate::config::{ConfigError, Section};
+
+/// How much colour the terminal can show.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorSupport {
+    /// `NO_COLOR` is set
+    None,
+    Ansi256,
+    /// `COLORTERM` is `truecolor` or `24bit`
+    TrueColor,
+}
+
+impl ColorSupport {
+    pub fn from_env() -> Self {
+        if env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty()) {
+            return ColorSupport::None;
+        }
+        match env::var("COLORTERM").as_deref() {
+            Ok("truecolor" | "24bit") => ColorSupport::TrueColor,
+            _ => ColorSupport::Ansi256,
+        }
     }
 }
 
-pub fn selected_style() -> Style {
-    Style::default()
-        .bg(SELECTED_BG)
-        .fg(SELECTED_FG)
-        .add_modifier(Modifier::BOLD)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Preset {
+    Dark,
+    Light,
+    Ansi16,
+}
+
+impl Preset {
+    pub fn from_name(name: &str) -> Option<Preset> {
+        match name {
+            "dark" => Some(Preset::Dark),
+            "light" => Some(Preset::Light),
+            "ansi16" => Some(Preset::Ansi16),
+            _ => None,
+        }
+    }
+}
+
+/// Colours for every part of the UI, chosen at startup from a preset, the
+/// `[theme]` section of the config file and the terminal's capabilities.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Theme {
+    pub selected_bg: Color,
+    pub selected_fg: Color,
+    pub group_fg: Color,
+    pub alias_name_fg: Color,
+    pub alias_cmd_fg: Color,
+    pub border: Color,
+    pub border_focused: Color,
+    pub search_fg: Color,
+    pub help_fg: Color,
+    pub detail_label_fg: Color,
+    pub detail_value_fg: Color,
+    pub missing_fg: Color,
+    pub disabled_fg: Color,
+    /// No colours at all; selection and focus use reverse video and bold.
+    pub plain: bool,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme::preset(Preset::Dark)
+    }
 }
 
-pub fn group_style() -> Style {
-    Style::default().fg(GROUP_FG).add_modifier(Modifier::BOLD)
+impl Theme {
+    pub fn preset(preset: Preset) -> Theme {
+        match preset {
+            Preset::Dark => Theme {
+                selected_bg: Color::Rgb(60, 60, 100),
+                selected_fg: Color::White,
+                group_fg: Color::Rgb(120, 180, 255),
+                alias_name_fg: Color::Rgb(200, 200, 100),
+                alias_cmd_fg: Color::Rgb(160, 160, 160),
+                border: Color::Rgb(80, 80, 120),
+                border_focused: Color::Rgb(120, 140, 255),
+                search_fg: Color::Rgb(255, 200, 100),
+                help_fg: Color::Rgb(120, 120, 140),
+                detail_label_fg: Color::Rgb(140, 140, 180),
+                detail_value_fg: Color::White,
+                missing_fg: Color::Rgb(230, 110, 110),
+                disabled_fg: Color::Rgb(95, 95, 110),
+                plain: false,
+            },
+            Preset::Light => Theme {
+                selected_bg: Color::Rgb(205, 215, 245),
+                selected_fg: Color::Black,
+                group_fg: Color::Rgb(30, 90, 180),
+                alias_name_fg: Color::Rgb(135, 95, 0),
+                alias_cmd_fg: Color::Rgb(85, 85, 85),
+                border: Color::Rgb(170, 170, 200),
+                border_focused: Color::Rgb(60, 80, 200),
+                search_fg: Color::Rgb(185, 95, 0),
+                help_fg: Color::Rgb(110, 110, 130),
+                detail_label_fg: Color::Rgb(80, 80, 140),
+                detail_value_fg: Color::Black,
+                missing_fg: Color::Rgb(190, 40, 40),
+                disabled_fg: Color::Rgb(165, 165, 175),
+                plain: false,
+            },
+            // The terminal's own palette, so it suits light and dark
+            Preset::Ansi16 => Theme {
+                selected_bg: Color::Blue,
+                selected_fg: Color::White,
+                group_fg: Color::LightBlue,
+                alias_name_fg: Color::Yellow,
+                alias_cmd_fg: Color::Reset,
+                border: Color::DarkGray,
+                border_focused: Color::LightBlue,
+                search_fg: Color::Yellow,
+                help_fg: Color::DarkGray,
+                detail_label_fg: Color::Cyan,
+                detail_value_fg: Color::Reset,
+                missing_fg: Color::Red,
+                disabled_fg: Color::DarkGray,
+                plain: false,
+            },
+        }
+    }
+
+    /// The terminal's default colours only, for `NO_COLOR`.
+    pub fn plain() -> Theme {
+        Theme {
+            plain: true,
+            ..Theme::preset(Preset::Ansi16).map(|_| Color::Reset)
+        }
+    }
+
+    /// The `[theme]` sections of the config file applied to a preset:
+    ///
+    /// ```text
+    /// [theme]
+    /// preset = light
+    /// search-fg = #d08000
+    /// ```
+    ///
+    /// `NO_COLOR` gives the plain theme unless the config sets a theme;
+    /// without truecolor, RGB colours are approximated in 256 colours.
+    pub fn from_config(sections: &[&Section], colors: ColorSupport) -> Result<Theme, ConfigError> {
+        let entries: Vec<_> = sections.iter().flat_map(|s| &s.entries).collect();
+        if colors == ColorSupport::None && entries.is_empty() {
+            return Ok(Theme::plain());
+        }
+
+        let mut theme = Theme::default();
+        if let Some(entry) = entries.iter().find(|e| e.key == "preset") {
+            let preset = Preset::from_name(&entry.value).ok_or_else(|| ConfigError::UnknownPreset {
+                line: entry.line,
+                name: entry.value.clone(),
+            })?;
+            theme = Theme::preset(preset);
+        }
+        for entry in entries.iter().filter(|e| e.key != "preset") {
+            let slot = theme.slot(&entry.key).ok_or_else(|| ConfigError::UnknownSetting {
+                line: entry.line,
+                name: entry.key.clone(),
+                expected: std::iter::once("preset")
+                    .chain(SLOTS.iter().copied())
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            })?;
+            *slot = Color::from_str(&entry.value).map_err(|_| ConfigError::BadColor {
+                line: entry.line,
+                value: entry.value.clone(),
+            })?;
+        }
+
+        if colors != ColorSupport::TrueColor {
+            theme = theme.map(to_ansi256);
+        }
+        Ok(theme)
+    }
+
+    /// The colour a `[theme]` setting names.
+    fn slot(&mut self, name: &str) -> Option<&mut Color> {
+        Some(match name {
+            "selected-bg" => &mut self.selected_bg,
+            "selected-fg" => &mut self.selected_fg,
+            "group-fg" => &mut self.group_fg,
+            "alias-name-fg" => &mut self.alias_name_fg,
+            "alias-cmd-fg" => &mut self.alias_cmd_fg,
+            "border" => &mut self.border,
+            "border-focused" => &mut self.border_focused,
+            "search-fg" => &mut self.search_fg,
+            "help-fg" => &mut self.help_fg,
+            "detail-label-fg" => &mut self.detail_label_fg,
+            "detail-value-fg" => &mut self.detail_value_fg,
+            "missing-fg" => &mut self.missing_fg,
+            "disabled-fg" => &mut self.disabled_fg,
+            _ => return None,
+        })
+    }
+
+    fn map(mut self, f: impl Fn(Color) -> Color) -> Theme {
+        for name in SLOTS {
+            if let Some(slot) = self.slot(name) {
+                *slot = f(*slot);
+            }
+        }
+        self
+    }
+
+    pub fn border_style(&self, focused: bool) -> Style {
+        match (focused, self.plain) {
+            (true, true) => Style::default().add_modifier(Modifier::BOLD),
+            (true, false) => Style::default().fg(self.border_focused),
+            (false, _) => Style::default().fg(self.border),
+        }
+    }
+
+    pub fn selected_style(&self) -> Style {
+        let style = Style::default()
+            .bg(self.selected_bg)
+            .fg(self.selected_fg)
+            .add_modifier(Modifier::BOLD);
+        if self.plain {
+            style.add_modifier(Modifier::REVERSED)
+        } else {
+            style
+        }
+    }
+
+    pub fn group_style(&self) -> Style {
+        Style::default().fg(self.group_fg).add_modifier(Modifier::BOLD)
+    }
+
+    pub fn alias_name_style(&self) -> Style {
+        Style::default().fg(self.alias_name_fg)
+    }
+
+    pub fn alias_cmd_style(&self) -> Style {
+        Style::default().fg(self.alias_cmd_fg)
+    }
+
+    /// Disabled aliases, name and command alike.
+    pub fn disabled_style(&self) -> Style {
+        let style = Style::default().fg(self.disabled_fg);
+        if self.plain {
+            style.add_modifier(Modifier::DIM)
+        } else {
+            style
+        }
+    }
 }
 
-pub fn alias_name_style() -> Style {
-    Style::default().fg(ALIAS_NAME_FG)
+/// `[theme]` setting names, in the order of the fields.
+const SLOTS: [&str; 13] = [
+    "selected-bg",
+    "selected-fg",
+    "group-fg",
+    "alias-name-fg",
+    "alias-cmd-fg",
+    "border",
+    "border-focused",
+    "search-fg",
+    "help-fg",
+    "detail-label-fg",
+    "detail-value-fg",
+    "missing-fg",
+    "disabled-fg",
+];
+
+/// The nearest colour in the xterm 256-colour palette: the 6x6x6 cube or
+/// the grey ramp. Other colours pass through.
+fn to_ansi256(color: Color) -> Color {
+    let Color::Rgb(r, g, b) = color else {
+        return color;
+    };
+    let level = |v: u8| -> (u8, u8) {
+        // Cube levels are 0, 95, 135, 175, 215, 255
+        let i = if v < 48 { 0 } else if v < 115 { 1 } else { (v - 35) / 40 };
+        (i, if i == 0 { 0 } else { 55 + 40 * i })
+    };
+    let ((ri, rv), (gi, gv), (bi, bv)) = (level(r), level(g), level(b));
+    let cube = 16 + 36 * ri + 6 * gi + bi;
+    let distance = |(x, y, z): (u8, u8, u8)| {
+        let d = |a: u8, b: u8| (a as i32 - b as i32).pow(2);
+        d(r, x) + d(g, y) + d(b, z)
+    };
+
+    let average = (r as u16 + g as u16 + b as u16) / 3;
+    let grey_index = if average > 238 { 23 } else { (average.saturating_sub(3) / 10) as u8 };
+    let grey = 8 + 10 * grey_index;
+
+    if distance((grey, grey, grey)) < distance((rv, gv, bv)) {
+        Color::Indexed(232 + grey_index)
+    } else {
+        Color::Indexed(cube)
+    }
 }
 
-pub fn alias_cmd_style() -> Style {
-    Style::default().fg(ALIAS_CMD_FG)
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+
+    fn theme(text: &str, colors: ColorSupport) -> Theme {
+        Config::parse(text, colors).unwrap().theme
+    }
+
+    #[test]
+    fn test_presets_and_overrides() {
+        assert_eq!(theme("", ColorSupport::TrueColor), Theme::preset(Preset::Dark));
+        let light = theme("[theme]\npreset = light\nsearch-fg = #102030\nborder = blue", ColorSupport::TrueColor);
+        assert_eq!(light.search_fg, Color::Rgb(0x10, 0x20, 0x30));
+        assert_eq!(light.border, Color::Blue);
+        assert_eq!(light.selected_fg, Color::Black);
+    }
+
+    #[test]
+    fn test_no_color_and_fallback() {
+        let plain = theme("", ColorSupport::None);
+        assert!(plain.plain);
+        assert_eq!(plain.group_fg, Color::Reset);
+        // A theme in the config file wins over NO_COLOR
+        assert!(!theme("[theme]\npreset = ansi16", ColorSupport::None).plain);
+
+        let dark = theme("", ColorSupport::Ansi256);
+        assert_eq!(dark.selected_fg, Color::White);
+        assert_eq!(dark.search_fg, Color::Indexed(221));
+        assert_eq!(dark.border, Color::Indexed(60));
+        assert_eq!(dark.alias_cmd_fg, Color::Indexed(247));
+    }
+
+    #[test]
+    fn test_to_ansi256() {
+        assert_eq!(to_ansi256(Color::Rgb(0, 0, 0)), Color::Indexed(16));
+        assert_eq!(to_ansi256(Color::Rgb(255, 255, 255)), Color::Indexed(231));
+        assert_eq!(to_ansi256(Color::Rgb(255, 0, 0)), Color::Indexed(196));
+        assert_eq!(to_ansi256(Color::Rgb(128, 128, 128)), Color::Indexed(244));
+        assert_eq!(to_ansi256(Color::Yellow), Color::Yellow);
+    }
 }
@@ -0,0 +1,191 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use std::path::Path;
+
+use crate::alias::types::{AliasGroup, AliasKind, AliasState};
+use crate::collision::{self, Collision};
+use crate::context::Context;
+use crate::managed;
+
+/// What the edit form holds when a check starts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Input {
+    pub name: String,
+    pub command: String,
+    pub kind: AliasKind,
+    /// For edits: the name and kind being replaced, which don't collide
+    /// with themselves.
+    pub original: Option<(String, AliasKind)>,
+}
+
+/// Something wrong with the alias being edited. None of these stop a
+/// save; they are shown while typing.
+#[derive(Debug, Clone)]
+pub enum Problem {
+    Name(String),
+    Collision(Collision),
+    Syntax(String),
+    NotFound(String),
+    MissingDir(String),
+}
+
+impl Problem {
+    pub fn message(&self) -> String {
+        match self {
+            Problem::Name(e) => e.clone(),
+            Problem::Collision(c) => format!("name {}", c.description()),
+            Problem::Syntax(e) => format!("syntax: {e}"),
+            Problem::NotFound(word) => format!("{word}: command not found"),
+            Problem::MissingDir(dir) => format!("{dir}: no such directory"),
+        }
+    }
+}
+
+/// Check an alias as it is being typed: the name, collisions, whether the
+/// shell parses the line zam would write and the command it runs, and
+/// whether the command's first word resolves. Runs the shell a few times,
+/// so the TUI calls it off the main thread. Empty fields aren't checked.
+pub fn check(ctx: &Context, groups: &[AliasGroup], input: &Input) -> Vec<Problem> {
+    let name = input.name.trim();
+    let command = input.command.trim();
+    let kind = input.kind;
+    let mut problems = Vec::new();
+
+    if !name.is_empty() {
+        if let Err(e) = managed::validate_alias_name(name) {
+            problems.push(Problem::Name(e.to_string()));
+        } else {
+            let unchanged = input
+                .original
+                .as_ref()
+                .is_some_and(|(n, k)| n == name && k.same_table(kind));
+            if !unchanged {
+                if let Some(c) = collision::check_name_collision(ctx, name, kind, groups) {
+                    problems.push(Problem::Collision(c));
+                }
+            }
+        }
+    }
+    if command.is_empty() {
+        return problems;
+    }
+
+    match kind {
+        AliasKind::NamedDir => {
+            let dir = managed::expand_home(command, ctx.home());
+            if !Path::new(&dir).is_dir() {
+                problems.push(Problem::MissingDir(command.to_string()));
+            }
+        }
+        // Global aliases are fragments like `| grep`, not commands
+        AliasKind::Global | AliasKind::Function => {}
+        AliasKind::Regular | AliasKind::Suffix => {
+            if let Some(word) = first_word(command) {
+                if !resolves(ctx, groups, word) {
+                    problems.push(Problem::NotFound(word.to_string()));
+                }
+            }
+        }
+    }
+
+    // The exact line written, then the body as it runs once expanded
+    let mut script = managed::definition_line(ctx, if name.is_empty() { "_" } else { name }, command, kind);
+    if matches!(kind, AliasKind::Regular | AliasKind::Suffix) {
+        script.push('\n');
+        script.push_str(command);
+    }
+    if let Some(e) = syntax_error(ctx, &script) {
+        problems.push(Problem::Syntax(e));
+    }
+    problems
+}
+
+/// The command word: the first word after any `VAR=value` assignments,
+/// or None when it can only be known at run time (`$EDITOR`, quoted,
+/// subshells).
+fn first_word(command: &str) -> Option<&str> {
+    let word = command
+        .split_whitespace()
+        .find(|w| !w.split_once('=').is_some_and(|(var, _)| is_identifier(var)))?;
+    let word = word.strip_prefix('\\').unwrap_or(word);
+    if word.is_empty() || word.contains(['$', '`', '(', ')', '{', '}', '\'', '"', ';', '|', '&', '<', '>']) {
+        return None;
+    }
+    Some(word)
+}
+
+fn is_identifier(s: &str) -> bool {
+    !s.is_empty()
+        && !s.starts_with(|c: char| c.is_ascii_digit())
+        && s.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Whether `word` names an enabled alias or function, or something the
+/// shell knows: a command on `PATH`, a builtin or keyword, or a path.
+fn resolves(ctx: &Context, groups: &[AliasGroup], word: &str) -> bool {
+    let known = groups.iter().flat_map(|g| &g.aliases).any(|a| {
+        a.name == word
+            && a.state == AliasState::Enabled
+            && matches!(a.kind, AliasKind::Regular | AliasKind::Function)
+    });
+    if known {
+        return true;
+    }
+    if word.contains('/') {
+        return Path::new(&managed::expand_home(word, ctx.home())).is_file();
+    }
+    collision::word_type(ctx, word).is_some()
+}
+
+/// The shell's complaint about `script`, if it doesn't parse.
+fn syntax_error(ctx: &Context, script: &str) -> Option<String> {
+    let output = ctx.shell_command().arg("-n").arg("-c").arg(script).output().ok()?;
+    if output.status.success() {
+        return None;
+    }
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let line = stderr.lines().find(|l| !l.trim().is_empty())?;
+    Some(strip_location(line).to_string())
+}
+
+/// Drop the `zsh:2: `, `bash: -c: line 1: ` or `sh: 2: Syntax error: `
+/// prefix shells put before a parse error.
+fn strip_location(line: &str) -> &str {
+    let mut rest = line.trim();
+    while let Some((head, tail)) = rest.split_once(": ") {
+        if head.contains(' ') && !head.starts_with("line ") && head != "Syntax error" {
+            break;
+        }
+        rest = tail;
+    }
+    rest
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_first_word() {
+        assert_eq!(first_word("git status -sb"), Some("git"));
+        assert_eq!(first_word("LC_ALL=C GIT_PAGER= git log"), Some("git"));
+        assert_eq!(first_word("\\ls -l"), Some("ls"));
+        assert_eq!(first_word("~/bin/deploy --prod"), Some("~/bin/deploy"));
+        assert_eq!(first_word("$EDITOR ~/.zshrc"), None);
+        assert_eq!(first_word("(cd ~ && ls)"), None);
+        assert_eq!(first_word("FOO=1"), None);
+    }
+
+    #[test]
+    fn test_strip_location() {
+        assert_eq!(strip_location("zsh:2: unmatched '"), "unmatched '");
+        assert_eq!(
+            strip_location("bash: -c: line 2: unexpected EOF while looking for matching `''"),
+            "unexpected EOF while looking for matching `''"
+        );
+        assert_eq!(
+            strip_location("sh: 2: Syntax error: Unterminated quoted string"),
+            "Unterminated quoted string"
+        );
+    }
+}
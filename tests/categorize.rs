@@ -0,0 +1,224 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Collection and attribution against fixture homes and a fake zsh.
+
+mod common;
+
+use common::{source_of, touch, Fixture};
+use zam::alias::collector::CollectOptions;
+use zam::alias::types::{AliasKind, AliasSource, AliasState};
+use zam::alias::{self, Collected};
+use zam::context::Context;
+use zam::shell::Shell;
+
+const OMZ_OUTPUT: &str = "\
+alias md='mkdir -p'
+alias gst='git status'
+alias wk='cd ~/work'
+alias ll='ls -l'
+alias hi='echo hi'
+alias cb='cargo build'
+alias -g G='| grep'
+alias mystery=true
+#zam-section named-dirs
+proj=/src/proj
+#zam-section env
+ZDOTDIR=
+ZSH=
+ZSH_CUSTOM=
+";
+
+/// An oh-my-zsh install with one enabled plugin, a custom file, a .zshrc
+/// and zam's own custom and extension files.
+fn omz_home(name: &str) -> Fixture {
+    let fx = Fixture::new(name);
+    fx.write(".oh-my-zsh/lib/directories.zsh", "alias md='mkdir -p'\n");
+    fx.write(".oh-my-zsh/plugins/git/git.plugin.zsh", "alias gst='git status'\n");
+    fx.write(".oh-my-zsh/plugins/docker/docker.plugin.zsh", "alias dps='docker ps'\n");
+    fx.write(".oh-my-zsh/custom/work.zsh", "alias wk='cd ~/work'\n");
+    fx.write(
+        ".zshrc",
+        "plugins=(git)\nsource $ZSH/oh-my-zsh.sh\n# long listing\nalias ll='ls -l'\nalias -g G='| grep'\n",
+    );
+    fx.write(".config/zam/init.zsh", "");
+    fx.write(".config/zam/aliases/custom.zsh", "alias hi='echo hi'\n");
+    fx.write(".config/zam/aliases/rust.zsh", "# zam extension: rust\n\nalias cb='cargo build'\n");
+    fx.fake_shell(Shell::Zsh, OMZ_OUTPUT, &[]);
+    fx
+}
+
+fn load(ctx: &Context) -> Collected {
+    alias::load_aliases(ctx, &CollectOptions::default()).expect("fake zsh should run")
+}
+
+#[test]
+fn test_omz_layout_attribution() {
+    let fx = omz_home("omz-layout");
+    let groups = load(&fx.ctx(Shell::Zsh)).groups;
+
+    assert_eq!(source_of(&groups, "md"), AliasSource::OhMyZshLib("directories.zsh".into()));
+    assert_eq!(source_of(&groups, "gst"), AliasSource::OhMyZshPlugin("git".into()));
+    assert_eq!(source_of(&groups, "wk"), AliasSource::OhMyZshCustom("work.zsh".into()));
+    assert_eq!(source_of(&groups, "ll"), AliasSource::Zshrc);
+    assert_eq!(source_of(&groups, "G"), AliasSource::Zshrc);
+    assert_eq!(source_of(&groups, "hi"), AliasSource::ZamCustom);
+    assert_eq!(source_of(&groups, "cb"), AliasSource::ZamExtension("rust".into()));
+    assert_eq!(source_of(&groups, "mystery"), AliasSource::Unknown);
+    assert_eq!(source_of(&groups, "proj"), AliasSource::NamedDirs);
+
+    let ll = groups.iter().flat_map(|g| &g.aliases).find(|a| a.name == "ll").unwrap();
+    assert_eq!(ll.description.as_deref(), Some("long listing"));
+    let global = groups.iter().flat_map(|g| &g.aliases).find(|a| a.name == "G").unwrap();
+    assert_eq!(global.kind, AliasKind::Global);
+
+    // Groups come out in load order: lib, plugins, custom, .zshrc, zam
+    let order: Vec<AliasSource> = groups.iter().map(|g| g.source.clone()).collect();
+    assert_eq!(
+        order,
+        vec![
+            AliasSource::OhMyZshLib("directories.zsh".into()),
+            AliasSource::OhMyZshPlugin("git".into()),
+            AliasSource::OhMyZshCustom("work.zsh".into()),
+            AliasSource::Zshrc,
+            AliasSource::NamedDirs,
+            AliasSource::ZamExtension("rust".into()),
+            AliasSource::ZamCustom,
+            AliasSource::Unknown,
+        ]
+    );
+}
+
+#[test]
+fn test_paths_reported_by_shell() {
+    let fx = Fixture::new("zdotdir");
+    let omz = fx.root.join("dotfiles/omz");
+    let custom = fx.root.join("dotfiles/zsh-custom");
+    fx.write(".config/zsh/.zshrc", "plugins=(git)\nalias ll='ls -l'\n");
+    fx.write("../dotfiles/omz/plugins/git/git.plugin.zsh", "alias gst='git status'\n");
+    fx.write("../dotfiles/zsh-custom/work.zsh", "alias wk='cd ~/work'\n");
+    // Not read: .zshrc in ~ is ignored once ZDOTDIR moves it
+    fx.write(".zshrc", "alias ll='ls -la'\n");
+    let output = format!(
+        "alias ll='ls -l'\nalias gst='git status'\nalias wk='cd ~/work'\n\
+         #zam-section env\nZDOTDIR={}\nZSH={}\nZSH_CUSTOM={}\n",
+        fx.path(".config/zsh").display(),
+        omz.display(),
+        custom.display(),
+    );
+    fx.fake_shell(Shell::Zsh, &output, &[]);
+
+    let ctx = fx.ctx(Shell::Zsh);
+    let collected = load(&ctx);
+    assert_eq!(collected.paths.zdotdir, fx.path(".config/zsh"));
+    assert_eq!(collected.paths.omz, omz);
+    assert_eq!(collected.paths.omz_custom, custom);
+
+    let groups = &collected.groups;
+    assert_eq!(source_of(groups, "ll"), AliasSource::Zshrc);
+    assert_eq!(source_of(groups, "gst"), AliasSource::OhMyZshPlugin("git".into()));
+    assert_eq!(source_of(groups, "wk"), AliasSource::OhMyZshCustom("work.zsh".into()));
+
+    let ctx = Context {
+        paths: collected.paths,
+        ..ctx
+    };
+    assert_eq!(
+        AliasSource::Zshrc.file_path(&ctx).as_deref(),
+        Some("~/.config/zsh/.zshrc")
+    );
+    assert_eq!(
+        AliasSource::ZamCustom.file_path(&ctx).as_deref(),
+        Some("~/.config/zam/aliases/custom.zsh")
+    );
+}
+
+#[test]
+fn test_cache_reuse_and_invalidation() {
+    let fx = omz_home("cache");
+    let ctx = fx.ctx(Shell::Zsh);
+
+    assert!(alias::load_cached_aliases(&ctx).is_none());
+    let fresh = load(&ctx);
+    assert_eq!(fx.collector_runs(), 1);
+    assert!(fx.exists(".cache/zam/aliases.zsh"));
+
+    let cached = alias::load_cached_aliases(&ctx).expect("cache should be fresh");
+    assert_eq!(fx.collector_runs(), 1);
+    assert_eq!(cached.groups.len(), fresh.groups.len());
+
+    touch(&fx.path(".zshrc"));
+    assert!(alias::load_cached_aliases(&ctx).is_none());
+}
+
+#[test]
+fn test_cache_watches_function_files() {
+    let fx = Fixture::new("cache-functions");
+    fx.write(".zshrc", "source ~/.zsh/functions.zsh\n");
+    let functions = fx.write(".zsh/functions.zsh", "# make and enter\nmkcd() { mkdir -p $1 && cd $1 }\n");
+    let output = format!(
+        "#zam-section functions\n#zam-function mkcd\t{}\nmkdir -p $1 && cd $1\n",
+        functions.display()
+    );
+    fx.fake_shell(Shell::Zsh, &output, &[]);
+    let ctx = fx.ctx(Shell::Zsh);
+
+    load(&ctx);
+    assert!(alias::load_cached_aliases(&ctx).is_some());
+    touch(&functions);
+    assert!(alias::load_cached_aliases(&ctx).is_none());
+}
+
+#[test]
+fn test_bash_attribution() {
+    // Names unlikely to be in the machine's /etc/bash.bashrc, which is
+    // scanned first
+    let fx = Fixture::new("bash");
+    fx.write(".bashrc", "alias zt-ll='ls -l'\n");
+    fx.write(".bash_aliases", "alias zt-gs='git status'\n");
+    fx.write(".config/zam/aliases/custom.bash", "alias zt-hi='echo hi'\n");
+    fx.fake_shell(
+        Shell::Bash,
+        "alias zt-gs='git status'\nalias zt-hi='echo hi'\nalias zt-ll='ls -l'\n",
+        &[],
+    );
+
+    let groups = load(&fx.ctx(Shell::Bash)).groups;
+    assert_eq!(source_of(&groups, "zt-ll"), AliasSource::Bashrc);
+    assert_eq!(source_of(&groups, "zt-gs"), AliasSource::BashAliases);
+    assert_eq!(source_of(&groups, "zt-hi"), AliasSource::ZamCustom);
+}
+
+#[test]
+fn test_disabled_aliases_are_listed() {
+    let fx = omz_home("disabled");
+    // Neither is in the shell's output; `gst` is still in the git plugin
+    fx.write(
+        ".config/zam/aliases/custom.zsh",
+        "alias hi='echo hi'\n# say bye\n#zam-disabled alias bye='echo bye'\n",
+    );
+    fx.write(
+        ".config/zam/overrides/oh-my-zsh-plugin-git.zsh",
+        "#zam-disabled alias gs='git status -sb'\nunalias gs 2>/dev/null\n",
+    );
+    fx.write(".oh-my-zsh/plugins/git/git.plugin.zsh", "alias gst='git status'\n# short\nalias gs='git status -sb'\n");
+    let groups = load(&fx.ctx(Shell::Zsh)).groups;
+    let find = |name: &str| {
+        groups
+            .iter()
+            .flat_map(|g| &g.aliases)
+            .find(|a| a.name == name)
+            .unwrap_or_else(|| panic!("{name} not listed"))
+    };
+
+    let bye = find("bye");
+    assert_eq!(bye.state, AliasState::Disabled);
+    assert_eq!(bye.source, AliasSource::ZamCustom);
+    assert_eq!(bye.command, "echo bye");
+    assert_eq!(bye.description.as_deref(), Some("say bye"));
+    let gs = find("gs");
+    assert_eq!(gs.state, AliasState::Disabled);
+    assert_eq!(gs.source, AliasSource::OhMyZshPlugin("git".into()));
+    assert_eq!(gs.description.as_deref(), Some("short"));
+    assert_eq!(find("hi").state, AliasState::Enabled);
+    assert_eq!(find("hi").description, None);
+}
@@ -0,0 +1,73 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Collision checks against in-memory aliases and a fake shell.
+
+mod common;
+
+use common::Fixture;
+use zam::alias::types::{Alias, AliasGroup, AliasKind, AliasSource, AliasState};
+use zam::collision::{self, Collision};
+use zam::shell::Shell;
+
+fn groups() -> Vec<AliasGroup> {
+    let alias = |name: &str, kind| Alias {
+        name: name.into(),
+        command: "true".into(),
+        kind,
+        source: AliasSource::Zshrc,
+        description: None,
+        state: AliasState::Enabled,
+        tags: Vec::new(),
+        missing: false,
+    };
+    vec![AliasGroup {
+        source: AliasSource::Zshrc,
+        aliases: vec![alias("gst", AliasKind::Regular), alias("pdf", AliasKind::Suffix)],
+    }]
+}
+
+const WORDS: &[(&str, &str)] = &[
+    ("ls", "command"),
+    ("cd", "builtin"),
+    ("mkcd", "function"),
+];
+
+#[test]
+fn test_zsh_collisions() {
+    let fx = Fixture::new("collision-zsh");
+    fx.fake_shell(Shell::Zsh, "", WORDS);
+    let ctx = fx.ctx(Shell::Zsh);
+    let groups = groups();
+    let check = |name, kind| collision::check_name_collision(&ctx, name, kind, &groups);
+
+    assert!(matches!(
+        check("gst", AliasKind::Global),
+        Some(Collision::Alias { source }) if source == "~/.zshrc"
+    ));
+    assert!(matches!(check("ls", AliasKind::Regular), Some(Collision::Command)));
+    assert!(matches!(check("cd", AliasKind::Regular), Some(Collision::Builtin)));
+    assert!(matches!(check("mkcd", AliasKind::Regular), Some(Collision::Function)));
+    assert!(check("zz", AliasKind::Regular).is_none());
+
+    // Suffix aliases only collide within their own table
+    assert!(check("ls", AliasKind::Suffix).is_none());
+    assert!(matches!(check("pdf", AliasKind::Suffix), Some(Collision::Alias { .. })));
+    assert!(check("pdf", AliasKind::NamedDir).is_none());
+}
+
+#[test]
+fn test_bash_file_is_command() {
+    let fx = Fixture::new("collision-bash");
+    fx.fake_shell(Shell::Bash, "", &[("ls", "file"), ("cd", "builtin")]);
+    let ctx = fx.ctx(Shell::Bash);
+
+    let collisions = collision::check_batch_collisions(
+        &ctx,
+        &[("ls", "ls -l", ""), ("cd", "cd ..", ""), ("zz", "true", "")],
+        &[],
+    );
+    let names: Vec<&str> = collisions.iter().map(|(n, _)| n.as_str()).collect();
+    assert_eq!(names, vec!["ls", "cd"]);
+    assert!(matches!(collisions[0].1, Collision::Command));
+    assert!(matches!(collisions[1].1, Collision::Builtin));
+}
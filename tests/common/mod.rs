@@ -0,0 +1,138 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Fixture homes and a scripted fake shell, so tests never read the
+//! developer's own configuration or run their real shell.
+
+#![allow(dead_code)] // each test crate uses a different subset
+
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+
+use zam::alias::types::{AliasGroup, AliasSource};
+use zam::context::Context;
+use zam::shell::Shell;
+
+/// A scratch directory holding a home directory and a `bin/` for the fake
+/// shell, removed again when dropped.
+pub struct Fixture {
+    pub root: PathBuf,
+    pub home: PathBuf,
+}
+
+impl Fixture {
+    /// An empty fixture; `name` must be unique across the test suite.
+    pub fn new(name: &str) -> Fixture {
+        let root = std::env::temp_dir().join(format!("zam-test-{}-{name}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        let home = root.join("home");
+        fs::create_dir_all(&home).unwrap();
+        Fixture { root, home }
+    }
+
+    /// Write `content` to `rel`, relative to the fixture home.
+    pub fn write(&self, rel: &str, content: &str) -> PathBuf {
+        let path = self.home.join(rel);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, content).unwrap();
+        path
+    }
+
+    /// Contents of `rel` under the fixture home, panicking if it's missing.
+    pub fn read(&self, rel: &str) -> String {
+        let path = self.home.join(rel);
+        fs::read_to_string(&path).unwrap_or_else(|e| panic!("{}: {e}", path.display()))
+    }
+
+    pub fn exists(&self, rel: &str) -> bool {
+        self.home.join(rel).exists()
+    }
+
+    /// A context rooted at the fixture home that runs `shell` as
+    /// `shell_program`, which must be set up with `fake_shell` first if
+    /// anything is collected or collision-checked.
+    pub fn ctx(&self, shell: Shell) -> Context {
+        let mut ctx = Context::for_home(shell, self.home.clone());
+        ctx.shell_program = self.root.join("bin").join(shell.name());
+        ctx
+    }
+
+    /// Install a fake `shell` that prints `output` when run as the alias
+    /// collector (`-ic`), and answers type queries (`-c`) with `words`,
+    /// pairs of a name and what it is (`command`, `builtin`, ...).
+    /// Syntax checks (`-n -c`) are passed on to `sh -n`, and commands run
+    /// from the TUI (`-i -c`) echo what they were asked to run.
+    /// Every invocation appends its mode to `calls.log` in the root.
+    pub fn fake_shell(&self, shell: Shell, output: &str, words: &[(&str, &str)]) {
+        let bin = self.root.join("bin");
+        fs::create_dir_all(&bin).unwrap();
+        let output_file = self.root.join(format!("{}.out", shell.name()));
+        fs::write(&output_file, output).unwrap();
+
+        let mut cases = String::new();
+        for (name, word) in words {
+            // zsh's `whence -w` prints `name: word`, `type -t` just `word`
+            let answer = match shell {
+                Shell::Zsh => format!("{name}: {word}"),
+                Shell::Bash | Shell::Fish => word.to_string(),
+            };
+            cases.push_str(&format!("    *\"'{name}'\") echo '{answer}' ;;\n"));
+        }
+        let script = format!(
+            "#!/bin/sh\n\
+             echo \"$1\" >> '{log}'\n\
+             if [ \"$1\" = -c ]; then\n\
+             \x20 case \"$2\" in\n\
+             {cases}\
+             \x20 esac\n\
+             \x20 exit 0\n\
+             fi\n\
+             if [ \"$1\" = -n ]; then exec sh -n -c \"$3\"; fi\n\
+             if [ \"$1\" = -i ]; then echo \"ran $3\"; exit 0; fi\n\
+             cat '{out}'\n",
+            log = self.root.join("calls.log").display(),
+            out = output_file.display(),
+        );
+        let path = bin.join(shell.name());
+        fs::write(&path, script).unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+    }
+
+    /// How many times the fake shell was run as the alias collector.
+    pub fn collector_runs(&self) -> usize {
+        fs::read_to_string(self.root.join("calls.log"))
+            .unwrap_or_default()
+            .lines()
+            .filter(|l| *l == "-ic")
+            .count()
+    }
+
+    pub fn path(&self, rel: &str) -> PathBuf {
+        self.home.join(rel)
+    }
+}
+
+impl Drop for Fixture {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.root);
+    }
+}
+
+/// The source the alias called `name` was attributed to.
+pub fn source_of(groups: &[AliasGroup], name: &str) -> AliasSource {
+    groups
+        .iter()
+        .flat_map(|g| &g.aliases)
+        .find(|a| a.name == name)
+        .unwrap_or_else(|| panic!("no alias named {name}"))
+        .source
+        .clone()
+}
+
+/// Make `path`'s mtime differ from what a fingerprint recorded, even on
+/// filesystems with coarse timestamps, by changing its size as well.
+pub fn touch(path: &Path) {
+    let mut content = fs::read_to_string(path).unwrap_or_default();
+    content.push('\n');
+    fs::write(path, content).unwrap();
+}
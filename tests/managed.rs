@@ -0,0 +1,337 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! The exact bytes zam writes to rc files and its managed directory.
+
+mod common;
+
+use common::Fixture;
+use zam::action::Action;
+use zam::alias::types::{Alias, AliasGroup, AliasKind, AliasSource, AliasState, ManagedError};
+use zam::app::AppState;
+use zam::managed;
+use zam::shell::Shell;
+
+#[test]
+fn test_init_appends_source_line_once() {
+    let fx = Fixture::new("init");
+    fx.write(".zshrc", "alias ll='ls -l'");
+    let ctx = fx.ctx(Shell::Zsh);
+
+    managed::init(&ctx, None).unwrap();
+    managed::init(&ctx, None).unwrap();
+
+    assert_eq!(
+        fx.read(".zshrc"),
+        "alias ll='ls -l'\nsource ~/.config/zam/init.zsh\n"
+    );
+    assert!(fx
+        .read(".config/zam/init.zsh")
+        .starts_with("# zam managed aliases — sourced from your .zshrc\n"));
+    for dir in ["aliases", "overrides", "dirs"] {
+        assert!(fx.path(".config/zam").join(dir).is_dir());
+    }
+    assert!(managed::is_initialized(&ctx));
+}
+
+#[test]
+fn test_init_binds_the_picker_on_request() {
+    let fx = Fixture::new("init-pick-key");
+    let ctx = fx.ctx(Shell::Zsh);
+
+    managed::init(&ctx, None).unwrap();
+    let init = fx.read(".config/zam/init.zsh");
+    assert!(init.contains("zle -N zam-pick\n"));
+    assert!(!init.contains("\nbindkey"));
+
+    managed::init(&ctx, Some("^G")).unwrap();
+    assert!(fx.read(".config/zam/init.zsh").ends_with("zle -N zam-pick\nbindkey '^G' zam-pick\n"));
+}
+
+#[test]
+fn test_init_and_nuke_follow_zdotdir() {
+    let fx = Fixture::new("init-zdotdir");
+    fx.write(".config/zsh/.zshrc", "# dotfiles\n");
+    fx.write(".zshrc", "source ~/.config/zam/init.zsh\n");
+    let mut ctx = fx.ctx(Shell::Zsh);
+    ctx.paths.zdotdir = fx.path(".config/zsh");
+
+    managed::init(&ctx, None).unwrap();
+    assert_eq!(
+        fx.read(".config/zsh/.zshrc"),
+        "# dotfiles\nsource ~/.config/zam/init.zsh\n"
+    );
+
+    managed::nuke(&ctx).unwrap();
+    assert_eq!(fx.read(".config/zsh/.zshrc"), "# dotfiles\n");
+    assert_eq!(fx.read(".zshrc"), "");
+    assert!(!fx.exists(".config/zam"));
+}
+
+#[test]
+fn test_custom_alias_file_bytes() {
+    let fx = Fixture::new("custom");
+    let ctx = fx.ctx(Shell::Zsh);
+    managed::init(&ctx, None).unwrap();
+
+    managed::write_custom_alias(&ctx, "gst", "git status", AliasKind::Regular, None).unwrap();
+    managed::write_custom_alias(&ctx, "G", "| grep", AliasKind::Global, None).unwrap();
+    managed::write_custom_alias(&ctx, "pdf", "zathura", AliasKind::Suffix, None).unwrap();
+    managed::write_custom_alias(&ctx, "say", "echo 'hi'", AliasKind::Regular, None).unwrap();
+    assert_eq!(
+        fx.read(".config/zam/aliases/custom.zsh"),
+        "alias gst='git status'\n\
+         alias -g G='| grep'\n\
+         alias -s pdf='zathura'\n\
+         alias say='echo '\\''hi'\\'''\n"
+    );
+
+    // Updating keeps the line in place; a global alias replaces a regular
+    // one of the same name since they share a table
+    managed::write_custom_alias(&ctx, "gst", "git status -sb", AliasKind::Global, None).unwrap();
+    managed::delete_custom_alias(&ctx, "pdf", AliasKind::Suffix).unwrap();
+    assert_eq!(
+        fx.read(".config/zam/aliases/custom.zsh"),
+        "alias -g gst='git status -sb'\n\
+         alias -g G='| grep'\n\
+         alias say='echo '\\''hi'\\'''\n"
+    );
+}
+
+#[test]
+fn test_rename_replaces_the_old_definition() {
+    let fx = Fixture::new("rename");
+    fx.fake_shell(Shell::Zsh, "", &[]);
+    let ctx = fx.ctx(Shell::Zsh);
+    managed::init(&ctx, None).unwrap();
+    let path = ".config/zam/aliases/custom.zsh";
+    fx.write(path, "alias gst='git status'\nalias gd='git diff'\n");
+    fx.write(".config/zam/overrides/zshrc.zsh", "");
+    managed::tag_alias(&ctx, "gst", "git").unwrap();
+
+    let alias = |name: &str, command: &str, source| Alias {
+        name: name.into(),
+        command: command.into(),
+        kind: AliasKind::Regular,
+        source,
+        description: None,
+        state: AliasState::Enabled,
+        tags: Vec::new(),
+        missing: false,
+    };
+    let groups = vec![
+        AliasGroup {
+            source: AliasSource::ZamCustom,
+            aliases: vec![alias("gst", "git status", AliasSource::ZamCustom)],
+        },
+        AliasGroup {
+            source: AliasSource::Zshrc,
+            aliases: vec![alias("ll", "ls -l", AliasSource::Zshrc)],
+        },
+    ];
+    let mut app = AppState::new(groups, ctx.clone());
+    let rename = |app: &mut AppState, old: &str, new: &str| {
+        app.search_query = old.to_string();
+        app.rebuild_visible();
+        app.selected_index = 1;
+        app.handle_action(Action::EditAlias);
+        app.handle_action(Action::EditPrevField);
+        app.handle_action(Action::EditClear);
+        new.chars().for_each(|c| app.handle_action(Action::EditInput(c)));
+        app.handle_action(Action::SaveEdit);
+        assert!(app.edit_state.is_none(), "{old} should be saved as {new}");
+    };
+
+    rename(&mut app, "gst", "gs");
+    assert_eq!(fx.read(path), "alias gd='git diff'\nalias gs='git status'\n");
+    assert_eq!(fx.read(".config/zam/tags"), "# zam tags: an alias name, then its tags\ngs git\n");
+
+    // An alias from elsewhere is unaliased under its old name
+    rename(&mut app, "ll", "l");
+    assert_eq!(
+        fx.read(".config/zam/overrides/zshrc.zsh"),
+        "unalias ll 2>/dev/null\nalias l='ls -l'\n"
+    );
+}
+
+#[test]
+fn test_descriptions_stay_with_their_alias() {
+    let fx = Fixture::new("descriptions");
+    let ctx = fx.ctx(Shell::Zsh);
+    managed::init(&ctx, None).unwrap();
+    let path = ".config/zam/aliases/custom.zsh";
+
+    managed::write_custom_alias(&ctx, "gst", "git status", AliasKind::Regular, Some("short status")).unwrap();
+    managed::write_custom_alias(&ctx, "gd", "git diff", AliasKind::Regular, None).unwrap();
+    managed::write_custom_alias(&ctx, "gl", "git log", AliasKind::Regular, Some(" history ")).unwrap();
+    assert_eq!(
+        fx.read(path),
+        "# short status\nalias gst='git status'\nalias gd='git diff'\n# history\nalias gl='git log'\n"
+    );
+
+    // Rewriting replaces the whole comment block; deleting takes it along
+    fx.write(path, "# status\n# of the tree\nalias gst='git status'\n\n# diff\nalias gd='git diff'\n");
+    managed::write_custom_alias(&ctx, "gst", "git status -sb", AliasKind::Regular, Some("short status")).unwrap();
+    managed::write_custom_alias(&ctx, "gd", "git diff -w", AliasKind::Regular, None).unwrap();
+    assert_eq!(fx.read(path), "# short status\nalias gst='git status -sb'\n\nalias gd='git diff -w'\n");
+    managed::delete_custom_alias(&ctx, "gst", AliasKind::Regular).unwrap();
+    assert_eq!(fx.read(path), "\nalias gd='git diff -w'\n");
+
+    let git = AliasSource::OhMyZshPlugin("git".into());
+    let path = ".config/zam/overrides/oh-my-zsh-plugin-git.zsh";
+    managed::write_override(&ctx, "gco", "git checkout -p", AliasKind::Regular, &git, Some("patch")).unwrap();
+    assert_eq!(fx.read(path), "# patch\nalias gco='git checkout -p'\n");
+    managed::delete_override(&ctx, "gco", AliasKind::Regular, &git).unwrap();
+    assert_eq!(fx.read(path), "unalias gco 2>/dev/null\n");
+}
+
+#[test]
+fn test_disable_keeps_the_definition() {
+    let fx = Fixture::new("disable");
+    let ctx = fx.ctx(Shell::Zsh);
+    managed::init(&ctx, None).unwrap();
+    let alias = |name: &str, command: &str, source| Alias {
+        name: name.into(),
+        command: command.into(),
+        kind: AliasKind::Regular,
+        source,
+        description: None,
+        state: AliasState::Enabled,
+        tags: Vec::new(),
+        missing: false,
+    };
+
+    // In zam's own files the line is commented out, and its comment stays
+    // its description rather than the next alias's
+    let path = ".config/zam/aliases/custom.zsh";
+    fx.write(path, "# status\nalias gst='git status'\nalias gd='git diff'\n");
+    let gst = alias("gst", "git status", AliasSource::ZamCustom);
+    managed::disable_alias(&ctx, &gst).unwrap();
+    assert_eq!(fx.read(path), "# status\n#zam-disabled alias gst='git status'\nalias gd='git diff'\n");
+    managed::delete_custom_alias(&ctx, "gd", AliasKind::Regular).unwrap();
+    assert_eq!(fx.read(path), "# status\n#zam-disabled alias gst='git status'\n");
+    managed::enable_alias(&ctx, &gst).unwrap();
+    assert_eq!(fx.read(path), "# status\nalias gst='git status'\n");
+    assert!(matches!(managed::enable_alias(&ctx, &gst), Err(ManagedError::NotManaged(_))));
+
+    // Elsewhere the override keeps the definition and unaliases it
+    let git = AliasSource::OhMyZshPlugin("git".into());
+    let path = ".config/zam/overrides/oh-my-zsh-plugin-git.zsh";
+    let gco = alias("gco", "git checkout", git.clone());
+    managed::disable_alias(&ctx, &gco).unwrap();
+    assert_eq!(fx.read(path), "#zam-disabled alias gco='git checkout'\nunalias gco 2>/dev/null\n");
+    managed::enable_alias(&ctx, &gco).unwrap();
+    assert_eq!(fx.read(path), "alias gco='git checkout'\n");
+
+    // An edited alias keeps its edit and comment while disabled
+    managed::write_override(&ctx, "gco", "git checkout -p", AliasKind::Regular, &git, Some("patch")).unwrap();
+    managed::disable_alias(&ctx, &gco).unwrap();
+    assert_eq!(
+        fx.read(path),
+        "# patch\n#zam-disabled alias gco='git checkout -p'\nunalias gco 2>/dev/null\n"
+    );
+}
+
+#[test]
+fn test_named_dir_expands_home() {
+    let fx = Fixture::new("named-dir");
+    let ctx = fx.ctx(Shell::Zsh);
+    managed::init(&ctx, None).unwrap();
+
+    managed::write_custom_alias(&ctx, "proj", "~/src/proj", AliasKind::NamedDir, None).unwrap();
+    assert_eq!(
+        fx.read(".config/zam/dirs/custom.zsh"),
+        format!("hash -d proj='{}/src/proj'\n", fx.home.display())
+    );
+}
+
+#[test]
+fn test_override_file_bytes() {
+    let fx = Fixture::new("override");
+    let ctx = fx.ctx(Shell::Zsh);
+    managed::init(&ctx, None).unwrap();
+    let git = AliasSource::OhMyZshPlugin("git".into());
+    let path = ".config/zam/overrides/oh-my-zsh-plugin-git.zsh";
+
+    managed::write_override(&ctx, "gst", "git status -sb", AliasKind::Regular, &git, None).unwrap();
+    managed::delete_override(&ctx, "gco", AliasKind::Regular, &git).unwrap();
+    assert_eq!(
+        fx.read(path),
+        "alias gst='git status -sb'\nunalias gco 2>/dev/null\n"
+    );
+
+    // Deleting an overridden alias drops the override; deleting twice is a
+    // no-op; editing a deleted alias brings it back
+    managed::delete_override(&ctx, "gst", AliasKind::Regular, &git).unwrap();
+    managed::delete_override(&ctx, "gst", AliasKind::Regular, &git).unwrap();
+    managed::write_override(&ctx, "gco", "git checkout", AliasKind::Regular, &git, None).unwrap();
+    assert_eq!(
+        fx.read(path),
+        "unalias gst 2>/dev/null\nalias gco='git checkout'\n"
+    );
+}
+
+#[test]
+fn test_extension_file_bytes() {
+    let fx = Fixture::new("extension");
+    let ctx = fx.ctx(Shell::Zsh);
+    managed::init(&ctx, None).unwrap();
+    let aliases = [("cb", "cargo build", "Build"), ("ct", "cargo test", "Test")];
+
+    managed::install_extension(&ctx, "rust", &aliases).unwrap();
+    assert!(managed::is_extension_installed(&ctx, "rust"));
+    assert_eq!(
+        fx.read(".config/zam/aliases/rust.zsh"),
+        "# zam extension: rust\n\n# Build\nalias cb='cargo build'\n# Test\nalias ct='cargo test'\n"
+    );
+
+    managed::remove_extension(&ctx, "rust").unwrap();
+    assert!(!managed::is_extension_installed(&ctx, "rust"));
+}
+
+#[test]
+fn test_fish_abbreviations() {
+    let fx = Fixture::new("fish");
+    let ctx = fx.ctx(Shell::Fish);
+    managed::init(&ctx, None).unwrap();
+
+    managed::write_custom_alias(&ctx, "gst", "git status", AliasKind::Regular, None).unwrap();
+    managed::write_custom_alias(&ctx, "L", "| less", AliasKind::Global, None).unwrap();
+    assert_eq!(
+        fx.read(".config/fish/conf.d/zam.fish"),
+        "source ~/.config/zam/init.fish\n\
+         abbr -a -- gst 'git status'\n\
+         abbr -a --position anywhere -- L '| less'\n"
+    );
+
+    let fish_config = AliasSource::FishConfig;
+    managed::delete_override(&ctx, "ll", AliasKind::Regular, &fish_config).unwrap();
+    assert_eq!(
+        fx.read(".config/zam/overrides/fish-config.fish"),
+        "abbr -e -- ll 2>/dev/null; functions -e -- ll\n"
+    );
+
+    managed::nuke(&ctx).unwrap();
+    assert!(!fx.exists(".config/fish/conf.d/zam.fish"));
+}
+
+#[test]
+fn test_errors() {
+    let fx = Fixture::new("errors");
+    let ctx = fx.ctx(Shell::Bash);
+    assert!(matches!(
+        managed::write_custom_alias(&ctx, "gst", "git status", AliasKind::Regular, None),
+        Err(ManagedError::NotInitialized)
+    ));
+
+    managed::init(&ctx, None).unwrap();
+    assert_eq!(fx.read(".bashrc"), "source ~/.config/zam/init.bash\n");
+    assert!(matches!(
+        managed::write_custom_alias(&ctx, "G", "| grep", AliasKind::Global, None),
+        Err(ManagedError::UnsupportedKind(AliasKind::Global, Shell::Bash))
+    ));
+    assert!(matches!(
+        managed::write_custom_alias(&ctx, "a b", "true", AliasKind::Regular, None),
+        Err(ManagedError::InvalidName(_))
+    ));
+    assert!(!fx.exists(".config/zam/aliases/custom.bash"));
+}
@@ -0,0 +1,563 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Interactive flows driven by key sequences and compared against golden
+//! frames in tests/snapshots/. Run with `ZAM_UPDATE_SNAPSHOTS=1` to
+//! rewrite the goldens after an intentional UI change, and review the diff.
+
+mod common;
+
+use std::fs;
+use std::path::PathBuf;
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::backend::TestBackend;
+use ratatui::layout::Rect;
+use ratatui::Terminal;
+
+use common::Fixture;
+use zam::action::Action;
+use zam::alias;
+use zam::alias::collector::CollectOptions;
+use zam::app::{AppState, GroupKey, InputMode, ListItem, ListMode, PanelFocus};
+use zam::event::{self, Mouse, MouseKind};
+use zam::keymap::Keymap;
+use zam::managed;
+use zam::pick::{self, Picker};
+use zam::run;
+use zam::session::Session;
+use zam::shell::Shell;
+use zam::ui;
+use zam::validate;
+
+const WIDTH: u16 = 90;
+const HEIGHT: u16 = 22;
+
+const OUTPUT: &str = "\
+alias gst='git status'
+alias gco='git checkout'
+alias ll='ls -l'
+alias la='ls -la'
+alias hi='echo hi'
+";
+
+/// A TUI over an initialized fixture home with an oh-my-zsh git plugin,
+/// a .zshrc and one zam custom alias, rendered after every step.
+struct Driver {
+    fx: Fixture,
+    app: AppState,
+    terminal: Terminal<TestBackend>,
+}
+
+impl Driver {
+    fn new(name: &str) -> Driver {
+        let fx = Fixture::new(name);
+        fx.write(
+            ".oh-my-zsh/plugins/git/git.plugin.zsh",
+            "alias gst='git status'\nalias gco='git checkout'\n",
+        );
+        fx.write(".zshrc", "plugins=(git)\n# long listing\nalias ll='ls -l'\nalias la='ls -la'\n");
+        fx.fake_shell(Shell::Zsh, OUTPUT, &[("ls", "command"), ("git", "command"), ("echo", "builtin")]);
+        let ctx = fx.ctx(Shell::Zsh);
+        managed::init(&ctx, None).unwrap();
+        fx.write(".config/zam/aliases/custom.zsh", "alias hi='echo hi'\n");
+
+        let collected = alias::load_aliases(&ctx, &CollectOptions::default()).unwrap();
+        let app = AppState::new(collected.groups, ctx);
+        let terminal = Terminal::new(TestBackend::new(WIDTH, HEIGHT)).unwrap();
+        let mut driver = Driver { fx, app, terminal };
+        driver.draw();
+        driver
+    }
+
+    /// Run the edit form's live check and any alias started from the TUI
+    /// inline, where the event loop would use threads, then render.
+    fn draw(&mut self) {
+        if let Some(input) = self.app.next_check() {
+            let problems = validate::check(&self.app.ctx, &self.app.groups, &input);
+            self.app.finish_check(&input, problems);
+        }
+        if let Some(command_line) = self.app.run_request.take() {
+            let output = run::run(&self.app.ctx, &command_line);
+            self.app.finish_run(&command_line, output);
+        }
+        let app = &mut self.app;
+        self.terminal.draw(|frame| ui::render(frame, app)).unwrap();
+    }
+
+    /// Send one key through the same mapping the event loop uses.
+    fn key_with(&mut self, code: KeyCode, modifiers: KeyModifiers) -> &mut Self {
+        if let Some(action) = event::map_key(&self.app.keymap, KeyEvent::new(code, modifiers), self.app.mode) {
+            self.app.handle_action(action);
+        }
+        self.draw();
+        self
+    }
+
+    fn key(&mut self, code: KeyCode) -> &mut Self {
+        self.key_with(code, KeyModifiers::NONE)
+    }
+
+    fn ctrl(&mut self, c: char) -> &mut Self {
+        self.key_with(KeyCode::Char(c), KeyModifiers::CONTROL)
+    }
+
+    /// Type each character of `text` as a key press.
+    fn keys(&mut self, text: &str) -> &mut Self {
+        for c in text.chars() {
+            self.key(KeyCode::Char(c));
+        }
+        self
+    }
+
+    /// Deliver `text` as a bracketed paste.
+    fn paste(&mut self, text: &str) -> &mut Self {
+        self.app.paste(text);
+        self.draw();
+        self
+    }
+
+    fn action(&mut self, action: Action) -> &mut Self {
+        self.app.handle_action(action);
+        self.draw();
+        self
+    }
+
+    /// Send a mouse event at a cell, laid out as the last frame was.
+    fn mouse(&mut self, kind: MouseKind, column: u16, row: u16) -> &mut Self {
+        let layout = ui::layout::build_layout(Rect::new(0, 0, WIDTH, HEIGHT));
+        self.app.mouse(Mouse { kind, column, row }, &layout);
+        self.draw();
+        self
+    }
+
+    /// The aliases in the list, top to bottom.
+    fn names(&self) -> Vec<&str> {
+        self.app
+            .visible_items
+            .iter()
+            .filter_map(|item| match item {
+                ListItem::AliasEntry { alias, .. } => Some(alias.name.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Compare the current frame against tests/snapshots/<name>.txt.
+    fn assert_snapshot(&mut self, name: &str) -> &mut Self {
+        let actual = ui::buffer_text(self.terminal.backend().buffer());
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/snapshots")
+            .join(format!("{name}.txt"));
+        if std::env::var_os("ZAM_UPDATE_SNAPSHOTS").is_some() {
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(&path, &actual).unwrap();
+            return self;
+        }
+        let expected = fs::read_to_string(&path).unwrap_or_else(|_| {
+            panic!("missing snapshot {name}; run with ZAM_UPDATE_SNAPSHOTS=1\n{actual}")
+        });
+        assert!(
+            actual == expected,
+            "snapshot {name} differs\n--- expected\n{expected}--- actual\n{actual}"
+        );
+        self
+    }
+}
+
+#[test]
+fn test_initial_frame() {
+    Driver::new("tui-initial").assert_snapshot("initial");
+}
+
+#[test]
+fn test_search_and_clear() {
+    let mut d = Driver::new("tui-search");
+    d.key(KeyCode::Char('/'))
+        .keys("gc")
+        .assert_snapshot("search_name")
+        .ctrl('u')
+        .keys("=ls -l")
+        .assert_snapshot("search_command")
+        .key(KeyCode::Esc)
+        .assert_snapshot("search_done");
+}
+
+#[test]
+fn test_collapse_group() {
+    let mut d = Driver::new("tui-collapse");
+    // The first row is the git plugin's header
+    d.key(KeyCode::Enter)
+        .assert_snapshot("collapsed")
+        .key(KeyCode::Enter)
+        .assert_snapshot("initial");
+}
+
+#[test]
+fn test_help() {
+    let mut d = Driver::new("tui-help");
+    d.key(KeyCode::Char('?'))
+        .assert_snapshot("help")
+        .key(KeyCode::Char('G'))
+        .assert_snapshot("help_general")
+        .key(KeyCode::Char('k'));
+    assert_eq!(d.app.mode, InputMode::Help, "movement keys scroll rather than close");
+    d.key(KeyCode::Esc).assert_snapshot("initial");
+}
+
+#[test]
+fn test_create_alias() {
+    let mut d = Driver::new("tui-create");
+    d.key(KeyCode::Char('a'))
+        .keys("gs")
+        .key(KeyCode::Tab)
+        .keys("git status -sb")
+        .key(KeyCode::Tab)
+        .keys("short status")
+        .assert_snapshot("create_form")
+        .key(KeyCode::Enter)
+        .assert_snapshot("create_result")
+        .key(KeyCode::Enter)
+        .assert_snapshot("create_done");
+    assert_eq!(
+        d.fx.read(".config/zam/aliases/custom.zsh"),
+        "alias hi='echo hi'\n# short status\nalias gs='git status -sb'\n"
+    );
+}
+
+#[test]
+fn test_create_with_collision() {
+    let mut d = Driver::new("tui-collision");
+    d.key(KeyCode::Char('a'))
+        .keys("ls")
+        .key(KeyCode::Tab)
+        .keys("ls -G")
+        .key(KeyCode::Enter)
+        .assert_snapshot("collision_confirm")
+        .key(KeyCode::Char('n'))
+        .assert_snapshot("collision_declined")
+        .key(KeyCode::Enter)
+        .key(KeyCode::Char('y'))
+        .assert_snapshot("collision_result");
+    assert_eq!(
+        d.fx.read(".config/zam/aliases/custom.zsh"),
+        "alias hi='echo hi'\nalias ls='ls -G'\n"
+    );
+}
+
+#[test]
+fn test_edit_and_delete() {
+    let mut d = Driver::new("tui-edit");
+    d.key(KeyCode::Char('/'))
+        .keys("ll")
+        .key(KeyCode::Enter)
+        .key(KeyCode::Char('j'))
+        .key(KeyCode::Char('e'))
+        .ctrl('u')
+        .keys("ls -lh")
+        .assert_snapshot("edit_form")
+        .key(KeyCode::Enter)
+        .assert_snapshot("edit_result")
+        .action(Action::DismissResult)
+        .key(KeyCode::Char('d'))
+        .assert_snapshot("delete_confirm")
+        .key(KeyCode::Char('y'))
+        .assert_snapshot("delete_result");
+    assert_eq!(
+        d.fx.read(".config/zam/overrides/zshrc.zsh"),
+        "unalias ll 2>/dev/null\n"
+    );
+}
+
+#[test]
+fn test_remapped_keys() {
+    let mut d = Driver::new("tui-keymap");
+    d.app.keymap = Keymap::parse("[normal]\nd = none\nx = delete\n\n[confirm]\nenter = yes\n").unwrap();
+    d.draw();
+    d.assert_snapshot("remapped")
+        .key(KeyCode::Char('d'))
+        .assert_snapshot("remapped")
+        .key(KeyCode::Char('j'))
+        .key(KeyCode::Char('x'))
+        .assert_snapshot("remapped_confirm")
+        .key(KeyCode::Enter)
+        .assert_snapshot("remapped_result");
+    assert_eq!(
+        d.fx.read(".config/zam/overrides/oh-my-zsh-plugin-git.zsh"),
+        "unalias gco 2>/dev/null\n"
+    );
+}
+
+#[test]
+fn test_line_editing() {
+    let mut d = Driver::new("tui-line-edit");
+    d.key(KeyCode::Char('a'))
+        .keys("gl")
+        .key(KeyCode::Tab)
+        .paste("git log --format='%h %an %ar %s' --graph --decorate\n")
+        .assert_snapshot("line_edit_scrolled")
+        .key(KeyCode::Home)
+        .key_with(KeyCode::Char('f'), KeyModifiers::ALT)
+        .key_with(KeyCode::Char('f'), KeyModifiers::ALT)
+        .keys(" -n 5")
+        .assert_snapshot("line_edit_cursor")
+        .key(KeyCode::End)
+        .ctrl('w')
+        .key(KeyCode::Backspace)
+        .key(KeyCode::Left)
+        .key(KeyCode::Delete)
+        .key(KeyCode::Enter)
+        .key(KeyCode::Enter);
+    assert_eq!(
+        d.fx.read(".config/zam/aliases/custom.zsh"),
+        "alias hi='echo hi'\nalias gl='git log -n 5 --format='\\''%h %an %ar %s'\\'' --grap'\n"
+    );
+}
+
+#[test]
+fn test_live_validation() {
+    let mut d = Driver::new("tui-validate");
+    d.key(KeyCode::Char('a'))
+        .keys("ls")
+        .assert_snapshot("validate_collision")
+        .key(KeyCode::Tab)
+        .keys("gti sttaus 'x")
+        .assert_snapshot("validate_problems")
+        .ctrl('u')
+        .keys("echo done")
+        .key(KeyCode::BackTab)
+        .ctrl('u')
+        .keys("dn")
+        .assert_snapshot("validate_clean");
+}
+
+#[test]
+fn test_bulk_delete() {
+    let mut d = Driver::new("tui-bulk-delete");
+    // Space on the git header marks both of its aliases; Space on `la`
+    // marks it and moves on, and V marks from there through `hi`
+    d.key(KeyCode::Char(' '))
+        .keys("jjjj ")
+        .keys("jjV")
+        .assert_snapshot("bulk_marked")
+        .key(KeyCode::Char('d'))
+        .assert_snapshot("bulk_delete_confirm")
+        .key(KeyCode::Char('y'))
+        .assert_snapshot("bulk_delete_result");
+    assert_eq!(
+        d.fx.read(".config/zam/overrides/oh-my-zsh-plugin-git.zsh"),
+        "unalias gco 2>/dev/null\nunalias gst 2>/dev/null\n"
+    );
+    assert_eq!(
+        d.fx.read(".config/zam/overrides/zshrc.zsh"),
+        "unalias la 2>/dev/null\nunalias ll 2>/dev/null\n"
+    );
+    assert_eq!(d.fx.read(".config/zam/aliases/custom.zsh"), "");
+}
+
+#[test]
+fn test_bulk_move_and_export() {
+    let mut d = Driver::new("tui-bulk-move");
+    managed::install_extension(&d.app.ctx, "rust", &[("cb", "cargo build", "Build")]).unwrap();
+    d.fx.write(".config/zam/aliases/custom.zsh", "# say hi\nalias hi='echo hi'\n");
+    d.key(KeyCode::Char('G'))
+        .key(KeyCode::Char(' '))
+        .key(KeyCode::Char('k'))
+        .key(KeyCode::Char('k'))
+        .key(KeyCode::Char(' '))
+        .key(KeyCode::Char('m'))
+        .assert_snapshot("bulk_move_confirm")
+        .key(KeyCode::Char('y'));
+    assert_eq!(d.fx.read(".config/zam/aliases/custom.zsh"), "");
+    assert_eq!(
+        d.fx.read(".config/zam/aliases/rust.zsh"),
+        "# zam extension: rust\n\n# Build\nalias cb='cargo build'\n# say hi\nalias hi='echo hi'\n"
+    );
+
+    // Without marks, export takes the selected alias
+    d.action(Action::DismissResult).key(KeyCode::Char('g')).key(KeyCode::Char('j')).key(KeyCode::Char('x')).key(KeyCode::Char('y'));
+    assert_eq!(d.fx.read(".config/zam/export.zsh"), "# zam export\n\nalias gco='git checkout'\n");
+
+    // A disabled alias has no live line to move
+    d.fx.write(".config/zam/aliases/custom.zsh", "#zam-disabled alias bye='echo bye'\n");
+    let collected = alias::load_aliases(&d.app.ctx, &CollectOptions::default()).unwrap();
+    d.app.replace_groups(collected.groups);
+    d.action(Action::DismissResult).key(KeyCode::Char('G'));
+    assert_eq!(d.app.selected_alias().unwrap().name, "bye");
+    d.key(KeyCode::Char('m'));
+    assert!(d.app.pending_action.is_none());
+    assert_eq!(d.app.status_message.as_deref(), Some("Only enabled aliases in zam's own files can be moved"));
+    assert_eq!(d.fx.read(".config/zam/aliases/custom.zsh"), "#zam-disabled alias bye='echo bye'\n");
+}
+
+#[test]
+fn test_disable_and_enable() {
+    let mut d = Driver::new("tui-disable");
+    let path = ".config/zam/overrides/zshrc.zsh";
+    d.keys("jjjjjD").assert_snapshot("disabled");
+    assert_eq!(d.fx.read(path), "#zam-disabled alias ll='ls -l'\nunalias ll 2>/dev/null\n");
+    d.key(KeyCode::Char('D'));
+    assert_eq!(d.fx.read(path), "alias ll='ls -l'\n");
+
+    // With marks, one confirmation covers them all
+    d.keys("k  ").key(KeyCode::Char('D')).key(KeyCode::Char('y'));
+    assert_eq!(
+        d.fx.read(path),
+        "#zam-disabled alias la='ls -la'\nunalias la 2>/dev/null\n#zam-disabled alias ll='ls -l'\nunalias ll 2>/dev/null\n"
+    );
+}
+
+#[test]
+fn test_group_by_tag_and_flat() {
+    let mut d = Driver::new("tui-tags");
+    managed::tag_alias(&d.app.ctx, "gst", "git").unwrap();
+    managed::tag_alias(&d.app.ctx, "ll", "#files").unwrap();
+    managed::tag_alias(&d.app.ctx, "la", "files").unwrap();
+    managed::tag_alias(&d.app.ctx, "la", "long").unwrap();
+    let collected = alias::load_aliases(&d.app.ctx, &CollectOptions::default()).unwrap();
+    d.app.replace_groups(collected.groups);
+
+    d.key(KeyCode::Char('o'))
+        .assert_snapshot("by_tag")
+        .key(KeyCode::Char('o'))
+        .assert_snapshot("flat")
+        .key(KeyCode::Char('/'))
+        .keys("#fi")
+        .assert_snapshot("search_tag");
+    assert_eq!(d.app.visible_alias_count(), 2);
+}
+
+#[test]
+fn test_sort_modes() {
+    let mut d = Driver::new("tui-sort");
+    d.fx.write(".zsh_history", ": 1700000000:0;gst\n: 1700000001:0;ll | less\n: 1700000002:0;gst\n");
+
+    d.keys("ooo");
+    assert_eq!(d.names(), ["hi", "gco", "gst", "ll", "la"]);
+    d.key(KeyCode::Char('o'));
+    assert_eq!(d.names(), ["hi", "la", "ll", "gco", "gst"]);
+    d.key(KeyCode::Char('o')).assert_snapshot("by_usage");
+    assert_eq!(d.names(), ["gst", "ll", "gco", "hi", "la"]);
+
+    // The mode comes back in the next session
+    d.app.session().save(&d.app.ctx).unwrap();
+    let session = Session::load(&d.app.ctx);
+    assert_eq!(session.list_mode, ListMode::ByUsage);
+    let mut app = AppState::new(d.app.groups.clone(), d.app.ctx.clone());
+    app.restore(session);
+    assert_eq!(app.list_mode, ListMode::ByUsage);
+}
+
+#[test]
+fn test_session_restore() {
+    let mut d = Driver::new("tui-session");
+    d.key(KeyCode::Enter).keys("jjj");
+    assert_eq!(d.app.selected_alias().unwrap().name, "ll");
+    d.app.session().save(&d.app.ctx).unwrap();
+
+    let mut app = AppState::new(d.app.groups.clone(), d.app.ctx.clone());
+    app.restore(Session::load(&d.app.ctx));
+    assert_eq!(app.collapsed, [GroupKey::Source(d.app.groups[0].source.clone())]);
+    assert_eq!(app.selected_alias().unwrap().name, "ll");
+    d.app = app;
+    d.assert_snapshot("restored");
+
+    // Groups and aliases that no longer exist are skipped
+    let mut app = AppState::new(d.app.groups.clone(), d.app.ctx.clone());
+    app.restore(Session {
+        list_mode: ListMode::Flat,
+        search: "g".to_string(),
+        collapsed: vec!["source gone".to_string(), "tag gone".to_string()],
+        selected: Some(("gone".to_string(), "~/.zshrc".to_string())),
+    });
+    assert!(app.collapsed.is_empty());
+    assert_eq!(app.selected_index, 0);
+    assert_eq!(app.visible_alias_count(), 2);
+}
+
+#[test]
+fn test_mouse() {
+    let mut d = Driver::new("tui-mouse");
+
+    // Rows 4 and up are the list: the git header, then gco
+    d.mouse(MouseKind::Click, 5, 5);
+    assert_eq!(d.app.selected_alias().unwrap().name, "gco");
+    d.mouse(MouseKind::Click, 5, 4);
+    assert_eq!(d.names(), ["la", "ll", "hi"]);
+    d.mouse(MouseKind::ScrollDown, 5, 10);
+    assert_eq!(d.app.selected_alias().unwrap().name, "ll");
+
+    d.mouse(MouseKind::Click, 60, 8);
+    assert_eq!(d.app.mode, InputMode::Normal { focus: PanelFocus::Detail });
+    d.mouse(MouseKind::ScrollDown, 60, 8);
+    assert_eq!(d.app.detail_scroll, 0, "the details fit, so there is nothing to scroll");
+
+    // The help bar's fifth hint is ?:help, and any click there closes it
+    d.mouse(MouseKind::Click, 66, 20);
+    assert_eq!(d.app.mode, InputMode::Help);
+    d.mouse(MouseKind::Click, 2, 20);
+    d.mouse(MouseKind::Click, 10, 1);
+    assert_eq!(d.app.mode, InputMode::Search);
+}
+
+#[test]
+fn test_copy() {
+    let mut d = Driver::new("tui-copy");
+    d.keys("jj");
+    d.key(KeyCode::Char('y'));
+    assert_eq!(d.app.copy_request.take().as_deref(), Some("git status"));
+    assert_eq!(d.app.status_message.as_deref(), Some("Copied the command of gst"));
+    d.key(KeyCode::Char('Y'));
+    assert_eq!(d.app.copy_request.take().as_deref(), Some("alias gst='git status'"));
+    d.key(KeyCode::Char('c'));
+    assert_eq!(d.app.copy_request.take().as_deref(), Some("gst"));
+}
+
+#[test]
+fn test_run_and_pick() {
+    let mut d = Driver::new("tui-run");
+    d.keys("jjr").keys("-s").assert_snapshot("run_args");
+    d.key(KeyCode::Enter).assert_snapshot("run_output");
+    assert_eq!(d.app.run.as_ref().unwrap().output.as_ref().unwrap().stdout, "ran gst -s\n");
+    d.key(KeyCode::Esc);
+    assert!(d.app.run.is_none());
+    // q closes the output rather than quitting zam
+    d.keys("r").key(KeyCode::Enter).key(KeyCode::Char('q'));
+    assert!(d.app.run.is_none());
+    assert!(!d.app.should_quit);
+
+    d.key(KeyCode::Char('p'));
+    assert_eq!(d.app.picked.as_deref(), Some("gst"));
+    assert!(d.app.should_quit);
+}
+
+#[test]
+fn test_pick() {
+    let mut d = Driver::new("tui-pick");
+    let mut picker = Picker::new(d.app.groups.clone(), d.app.ctx.clone(), "l");
+    let press = |picker: &mut Picker, code| picker.key(KeyEvent::new(code, KeyModifiers::NONE));
+    let names = |picker: &Picker| picker.matches().iter().map(|a| a.name.clone()).collect::<Vec<_>>();
+    assert_eq!(names(&picker), ["la", "ll"]);
+
+    press(&mut picker, KeyCode::Char('l'));
+    assert_eq!(names(&picker), ["ll"]);
+    press(&mut picker, KeyCode::Backspace);
+    press(&mut picker, KeyCode::Down);
+    press(&mut picker, KeyCode::Down);
+    assert_eq!(picker.selected, 1);
+
+    d.terminal = Terminal::new(TestBackend::new(60, pick::HEIGHT)).unwrap();
+    d.terminal.draw(|frame| ui::picker::render(frame, &mut picker)).unwrap();
+    d.assert_snapshot("pick");
+
+    press(&mut picker, KeyCode::Enter);
+    assert!(picker.done);
+    assert_eq!(picker.app.picked.as_deref(), Some("ll"));
+
+    let mut picker = Picker::new(d.app.groups.clone(), d.app.ctx.clone(), "=git");
+    assert_eq!(names(&picker), ["gco", "gst"]);
+    picker.key(KeyEvent::new(KeyCode::Char('u'), KeyModifiers::CONTROL));
+    assert_eq!(names(&picker).len(), 5);
+    press(&mut picker, KeyCode::Esc);
+    assert!(picker.done);
+    assert!(picker.app.picked.is_none());
+}